@@ -0,0 +1,15 @@
+pragma circom 2.0.0;
+
+// d = a * b * c, with a public
+template Multiply3() {
+    signal input a;
+    signal input b;
+    signal input c;
+    signal output d;
+    signal ab;
+
+    ab <== a * b;
+    d <== ab * c;
+}
+
+component main {public [a]} = Multiply3();
@@ -5,17 +5,20 @@ mod mpc;
 use ark_bls12_377::Fr;
 use ark_ec::group::Group;
 use ark_ec::PairingEngine;
-use ark_ec::ProjectiveCurve;
+use ark_ec::{AffineCurve, ProjectiveCurve};
 use ark_poly::domain::radix2::Radix2EvaluationDomain;
 use ark_poly::EvaluationDomain;
 use ark_poly::{UVPolynomial,Polynomial};
-use ark_poly_commit::PolynomialCommitment;
-use ark_ff::Field;
+use ark_poly_commit::marlin_pc::MarlinKZG10;
+use ark_poly_commit::{Evaluations, LabeledPolynomial, LinearCombination, PolynomialCommitment, QuerySet};
+use ark_ff::{BigInteger, BigInteger256, Field, One, PrimeField, Zero};
 use ark_serialize::CanonicalSerialize;
 use ark_std::rand::SeedableRng;
 use std::net::{SocketAddr, ToSocketAddrs};
 
 use mpc::channel;
+use mpc::domain::BatchFft;
+use mpc::input::{self, SharedInputs};
 use mpc::ComField;
 use mpc::MpcCurve;
 use mpc::MpcCurve2;
@@ -24,6 +27,7 @@ use mpc::MpcWire;
 
 use clap::arg_enum;
 use merlin::Transcript;
+use sha2::Digest;
 use structopt::StructOpt;
 
 arg_enum! {
@@ -39,10 +43,32 @@ arg_enum! {
         PairingDh,
         PairingProd,
         PairingDiv,
+        PairingExp,
+        PairingMulti,
         Groth16,
         Marlin,
         PolyEval,
+        PolyArith,
         PcCom,
+        Kzg,
+        BitDecomp,
+        BitOps,
+        Schnorr,
+        Msm,
+        Cofactor,
+        HashToCurve,
+        Plonk,
+        Gadgets,
+    }
+}
+
+arg_enum! {
+    #[derive(PartialEq, Debug)]
+    pub enum Curve {
+        Bls12377,
+        Bls12381,
+        Bn254,
+        Bw6761,
     }
 }
 
@@ -53,6 +79,10 @@ enum ComputationDomain {
     Pairing,
     BlsPairing,
     PolyField,
+    SharedPoly,
+    Boolean,
+    Edwards,
+    BaseField,
 }
 
 #[derive(Debug, StructOpt)]
@@ -91,6 +121,22 @@ struct Opt {
     #[structopt(long)]
     use_g2: bool,
 
+    /// Pairing-friendly curve to prove over (for groth16)
+    #[structopt(long, default_value = "bls12377")]
+    curve: Curve,
+
+    /// Generate the groth16 parameters in MPC
+    #[structopt(long)]
+    mpc_setup: bool,
+
+    /// Open the groth16 proof only to this party
+    #[structopt(long)]
+    receiver: Option<usize>,
+
+    /// Take the groth16 witness (`a` and `b`) from an input provider, listening on this port
+    #[structopt(long)]
+    input_port: Option<u16>,
+
     /// Input a
     #[structopt()]
     args: Vec<u64>,
@@ -99,7 +145,7 @@ struct Opt {
 impl Opt {
     fn domain(&self) -> ComputationDomain {
         match &self.computation {
-            Computation::Dh => {
+            Computation::Dh | Computation::Msm | Computation::Cofactor => {
                 if self.use_g2 {
                     ComputationDomain::G2
                 } else {
@@ -107,34 +153,449 @@ impl Opt {
                 }
             }
 
-            Computation::PairingDh | Computation::PairingProd | Computation::PairingDiv => {
-                ComputationDomain::Pairing
-            }
-            Computation::Marlin | Computation::Groth16 | Computation::PcCom => ComputationDomain::BlsPairing,
+            Computation::PairingDh
+            | Computation::PairingProd
+            | Computation::PairingDiv
+            | Computation::PairingExp
+            | Computation::PairingMulti => ComputationDomain::Pairing,
+            Computation::Marlin
+            | Computation::Plonk
+            | Computation::Groth16
+            | Computation::Gadgets
+            | Computation::PcCom
+            | Computation::Kzg => ComputationDomain::BlsPairing,
             Computation::PolyEval => ComputationDomain::PolyField,
+            Computation::PolyArith => ComputationDomain::SharedPoly,
+            Computation::BitDecomp | Computation::BitOps => ComputationDomain::Boolean,
+            Computation::Schnorr => ComputationDomain::Edwards,
+            Computation::HashToCurve => ComputationDomain::BaseField,
             _ => ComputationDomain::Field,
         }
     }
 }
 
 impl Computation {
-    fn run_bls(&self, inputs: Vec<MFr>) -> Vec<MFr> {
+    fn run_bls(
+        &self,
+        curve: &Curve,
+        mpc_setup: bool,
+        receiver: Option<usize>,
+        provided: Option<SharedInputs<Fr>>,
+        inputs: Vec<MFr>,
+    ) -> Vec<MFr> {
         let outputs = match self {
+            Computation::Groth16 if provided.is_some() => {
+                assert_eq!(*curve, Curve::Bls12377, "Provided inputs are BLS12-377 scalars");
+                vec![mpc::groth::mpc_prove_inputs::<ark_bls12_377::Bls12_377>(
+                    &provided.unwrap(),
+                )
+                .unwrap()]
+            }
             Computation::Groth16 => {
-                mpc::groth::mpc_test_prove_and_verify(1);
+                match curve {
+                    Curve::Bls12377 => {
+                        mpc::groth::mpc_test_prove_and_verify::<ark_bls12_377::Bls12_377>(1, mpc_setup, receiver)
+                    }
+                    Curve::Bls12381 => {
+                        mpc::groth::mpc_test_prove_and_verify::<ark_bls12_381::Bls12_381>(1, mpc_setup, receiver)
+                    }
+                    Curve::Bn254 => mpc::groth::mpc_test_prove_and_verify::<ark_bn254::Bn254>(1, mpc_setup, receiver),
+                    Curve::Bw6761 => {
+                        mpc::groth::mpc_test_prove_and_verify::<ark_bw6_761::BW6_761>(1, mpc_setup, receiver)
+                    }
+                }
                 vec![]
             }
+            Computation::Gadgets => {
+                assert_eq!(*curve, Curve::Bls12377, "Inputs are BLS12-377 scalars");
+                assert_eq!(4, inputs.len());
+                mpc::r1cs_std::mpc_test_prove_and_verify::<ark_bls12_377::Bls12_377>(
+                    inputs[0], inputs[1], inputs[2], inputs[3],
+                )
+            }
             Computation::Marlin => {
-                mpc::marlin::local_test_prove_and_verify::<ark_bls12_377::Bls12_377>(1);
+                match curve {
+                    Curve::Bls12377 => {
+                        mpc::marlin::mpc_test_prove_and_verify::<ark_bls12_377::Bls12_377>(1)
+                    }
+                    Curve::Bn254 => mpc::marlin::mpc_test_prove_and_verify::<ark_bn254::Bn254>(1),
+                    c => unimplemented!("Marlin over {:?}", c),
+                }
+                vec![]
+            }
+            Computation::Plonk => {
+                // the verifier, against the plain prover, and then the MPC one
+                mpc::plonk::local_test_prove_and_verify(1);
+                mpc::plonk::mpc_test_prove_and_verify(1);
                 vec![]
             }
             Computation::PcCom => {
-                let poly = MP::from_coefficients_slice(&inputs);
+                type PC = MarlinKZG10<ark_bls12_377::Bls12_377, P>;
+                type MpcPc = mpc::poly::pc::MpcPolyCommit<Fr, P, PC>;
+                let rng = &mut ark_std::test_rng();
+                // the trapdoor and the hiding randomness are shared: each party samples its own
+                let party_rng = &mut mpc::party_rng();
+                let srs = MpcPc::setup(10, None, party_rng).unwrap();
+                let (ck, vk) = MpcPc::trim(&srs, 10, 1, Some(&[8])).unwrap();
+                // hiding, degree-bounded, and plain
+                let mut rev = inputs.clone();
+                rev.reverse();
+                let polys = vec![
+                    LabeledPolynomial::new(
+                        "a".into(),
+                        MP::from_coefficients_slice(&inputs),
+                        None,
+                        Some(1),
+                    ),
+                    LabeledPolynomial::new(
+                        "b".into(),
+                        MP::from_coefficients_vec(rev),
+                        Some(8),
+                        None,
+                    ),
+                    LabeledPolynomial::new(
+                        "c".into(),
+                        MP::from_coefficients_slice(&inputs[1..]),
+                        None,
+                        None,
+                    ),
+                ];
+                let (comms, rands) = MpcPc::commit(&ck, &polys, Some(party_rng)).unwrap();
+                let challenge = MFr::from(3u32);
                 let x = MFr::from(2u32);
+                let y = MFr::from(5u32);
+
+                // one point; the opened proof is a plain MarlinKZG10 proof
+                let proof =
+                    MpcPc::open(&ck, &polys, &comms, &x, challenge, &rands, Some(rng)).unwrap();
+                let values: Vec<MFr> = polys.iter().map(|p| p.evaluate(&x)).collect();
+                assert!(MpcPc::check(
+                    &vk,
+                    &comms,
+                    &x,
+                    values.clone(),
+                    &proof,
+                    challenge,
+                    Some(rng)
+                )
+                .unwrap());
+                let mut values: Vec<Fr> =
+                    values.into_iter().map(|v| v.publicize_unwrap()).collect();
+                assert!(PC::check(
+                    &vk,
+                    &comms,
+                    &x.publicize_unwrap(),
+                    values.clone(),
+                    &proof,
+                    challenge.publicize_unwrap(),
+                    Some(rng)
+                )
+                .unwrap());
+                values[0] += Fr::one();
+                assert!(!PC::check(
+                    &vk,
+                    &comms,
+                    &x.publicize_unwrap(),
+                    values,
+                    &proof,
+                    challenge.publicize_unwrap(),
+                    Some(rng)
+                )
+                .unwrap());
+
+                // a query set
+                let queries = vec![
+                    ("a", ("x", x)),
+                    ("b", ("x", x)),
+                    ("b", ("y", y)),
+                    ("c", ("y", y)),
+                ];
+                let query_set: QuerySet<MFr> = queries
+                    .iter()
+                    .map(|(p, (l, z))| (p.to_string(), (l.to_string(), *z)))
+                    .collect();
+                let proofs = MpcPc::batch_open(
+                    &ck,
+                    &polys,
+                    &comms,
+                    &query_set,
+                    challenge,
+                    &rands,
+                    Some(rng),
+                )
+                .unwrap();
+                let mut evals: Evaluations<Fr, Fr> = Evaluations::new();
+                let mut plain_query_set: QuerySet<Fr> = QuerySet::new();
+                for (p, (l, z)) in &queries {
+                    let poly = polys.iter().find(|q| q.label() == p).unwrap();
+                    evals.insert(
+                        (p.to_string(), z.publicize_unwrap()),
+                        poly.evaluate(z).publicize_unwrap(),
+                    );
+                    plain_query_set.insert((p.to_string(), (l.to_string(), z.publicize_unwrap())));
+                }
+                assert!(PC::batch_check(
+                    &vk,
+                    &comms,
+                    &plain_query_set,
+                    &evals,
+                    &proofs,
+                    challenge.publicize_unwrap(),
+                    rng
+                )
+                .unwrap());
+
+                // a linear combination: a + 2c
+                let lc =
+                    LinearCombination::new("lc", vec![(MFr::one(), "a"), (MFr::from(2u32), "c")]);
+                let plain_lc =
+                    LinearCombination::new("lc", vec![(Fr::one(), "a"), (Fr::from(2u32), "c")]);
+                let query_set: QuerySet<MFr> = vec![("lc".to_string(), ("x".to_string(), x))]
+                    .into_iter()
+                    .collect();
+                let plain_query_set: QuerySet<Fr> =
+                    vec![("lc".to_string(), ("x".to_string(), x.publicize_unwrap()))]
+                        .into_iter()
+                        .collect();
+                let lc_proof = MpcPc::open_combinations(
+                    &ck,
+                    &[lc],
+                    &polys,
+                    &comms,
+                    &query_set,
+                    challenge,
+                    &rands,
+                    Some(rng),
+                )
+                .unwrap();
+                let lc_val = polys[0].evaluate(&x) + polys[2].evaluate(&x) * MFr::from(2u32);
+                let evals: Evaluations<Fr, Fr> = vec![(
+                    ("lc".to_string(), x.publicize_unwrap()),
+                    lc_val.publicize_unwrap(),
+                )]
+                .into_iter()
+                .collect();
+                let plain_lc_proof = ark_poly_commit::BatchLCProof {
+                    proof: lc_proof.proof,
+                    evals: None,
+                };
+                assert!(PC::check_combinations(
+                    &vk,
+                    &[plain_lc],
+                    &comms,
+                    &plain_query_set,
+                    &evals,
+                    &plain_lc_proof,
+                    challenge.publicize_unwrap(),
+                    rng
+                )
+                .unwrap());
+                vec![lc_val]
+            }
+            Computation::Kzg => {
+                // the port of MarlinKZG10 to MPC types; its commitments and proofs are plain ones
+                use mpc::poly::marlin_pc::{comm_from_mpc, pp_from_mpc, proof_from_mpc};
+                type PC = MarlinKZG10<ark_bls12_377::Bls12_377, P>;
+                type MpcPc = mpc::poly::pc::MpcMarlinKZG10;
+                type MDense = mpc::poly::dense::MpcDensePolynomial<Fr>;
                 let rng = &mut ark_std::test_rng();
-                let srs = mpc::poly::pc::MpcPolyCommit::setup( 10, Some(1), rng);
-                println!("{:#?}", srs);
-                vec![]
+                let party_rng = &mut mpc::party_rng();
+                let pp = MpcPc::setup(10, None, party_rng).unwrap();
+                let (ck, vk) = MpcPc::trim(&pp, 10, 1, Some(&[8])).unwrap();
+                let (plain_ck, plain_vk) = PC::trim(&pp_from_mpc(&pp), 10, 1, Some(&[8])).unwrap();
+                // hiding, degree-bounded, and plain
+                let mut rev = inputs.clone();
+                rev.reverse();
+                let polys = vec![
+                    LabeledPolynomial::new(
+                        "a".into(),
+                        MDense::from_coefficients_slice(&inputs),
+                        None,
+                        Some(1),
+                    ),
+                    LabeledPolynomial::new("b".into(), MDense::from_coefficients_vec(rev), Some(8), None),
+                    LabeledPolynomial::new(
+                        "c".into(),
+                        MDense::from_coefficients_slice(&inputs[1..]),
+                        None,
+                        None,
+                    ),
+                ];
+                let (comms, rands) = MpcPc::commit(&ck, &polys, Some(party_rng)).unwrap();
+                let plain_comms: Vec<_> = comms.iter().map(comm_from_mpc).collect();
+
+                // the non-hiding commitments are the plain commitments to the opened polynomials
+                let plain_polys: Vec<_> = polys[1..]
+                    .iter()
+                    .map(|p| {
+                        LabeledPolynomial::new(
+                            p.label().clone(),
+                            p.polynomial().publicize(),
+                            p.degree_bound(),
+                            None,
+                        )
+                    })
+                    .collect();
+                let (expected_comms, _) = PC::commit(&plain_ck, &plain_polys, None).unwrap();
+                for (c, e) in plain_comms[1..].iter().zip(&expected_comms) {
+                    assert_eq!(c.commitment(), e.commitment());
+                }
+
+                let challenge = MFr::from(3u32);
+                let x = MFr::from(2u32);
+                let y = MFr::from(5u32);
+
+                // one point
+                let proof =
+                    MpcPc::open(&ck, &polys, &comms, &x, challenge, &rands, Some(rng)).unwrap();
+                let values: Vec<MFr> = polys.iter().map(|p| p.evaluate(&x)).collect();
+                assert!(MpcPc::check(
+                    &vk,
+                    &comms,
+                    &x,
+                    values.clone(),
+                    &proof,
+                    challenge,
+                    Some(rng)
+                )
+                .unwrap());
+                let mut values: Vec<Fr> =
+                    values.into_iter().map(|v| v.publicize_unwrap()).collect();
+                let plain_proof = proof_from_mpc(&proof);
+                assert!(PC::check(
+                    &plain_vk,
+                    &plain_comms,
+                    &x.publicize_unwrap(),
+                    values.clone(),
+                    &plain_proof,
+                    challenge.publicize_unwrap(),
+                    Some(rng)
+                )
+                .unwrap());
+                values[0] += Fr::one();
+                assert!(!PC::check(
+                    &plain_vk,
+                    &plain_comms,
+                    &x.publicize_unwrap(),
+                    values,
+                    &plain_proof,
+                    challenge.publicize_unwrap(),
+                    Some(rng)
+                )
+                .unwrap());
+
+                // a query set
+                let queries = vec![
+                    ("a", ("x", x)),
+                    ("b", ("x", x)),
+                    ("b", ("y", y)),
+                    ("c", ("y", y)),
+                ];
+                let query_set: QuerySet<MFr> = queries
+                    .iter()
+                    .map(|(p, (l, z))| (p.to_string(), (l.to_string(), *z)))
+                    .collect();
+                let proofs = MpcPc::batch_open(
+                    &ck,
+                    &polys,
+                    &comms,
+                    &query_set,
+                    challenge,
+                    &rands,
+                    Some(rng),
+                )
+                .unwrap();
+                let mut evals: Evaluations<MFr, MFr> = Evaluations::new();
+                let mut plain_evals: Evaluations<Fr, Fr> = Evaluations::new();
+                let mut plain_query_set: QuerySet<Fr> = QuerySet::new();
+                for (p, (l, z)) in &queries {
+                    let poly = polys.iter().find(|q| q.label() == p).unwrap();
+                    let v = poly.evaluate(z);
+                    evals.insert((p.to_string(), *z), v);
+                    plain_evals.insert((p.to_string(), z.publicize_unwrap()), v.publicize_unwrap());
+                    plain_query_set.insert((p.to_string(), (l.to_string(), z.publicize_unwrap())));
+                }
+                assert!(MpcPc::batch_check(
+                    &vk,
+                    &comms,
+                    &query_set,
+                    &evals,
+                    &proofs,
+                    challenge,
+                    rng
+                )
+                .unwrap());
+                let plain_proofs: Vec<_> = proofs.iter().map(proof_from_mpc).collect();
+                assert!(PC::batch_check(
+                    &plain_vk,
+                    &plain_comms,
+                    &plain_query_set,
+                    &plain_evals,
+                    &plain_proofs,
+                    challenge.publicize_unwrap(),
+                    rng
+                )
+                .unwrap());
+
+                // a linear combination: a + 2c
+                let lc =
+                    LinearCombination::new("lc", vec![(MFr::one(), "a"), (MFr::from(2u32), "c")]);
+                let plain_lc =
+                    LinearCombination::new("lc", vec![(Fr::one(), "a"), (Fr::from(2u32), "c")]);
+                let query_set: QuerySet<MFr> = vec![("lc".to_string(), ("x".to_string(), x))]
+                    .into_iter()
+                    .collect();
+                let plain_query_set: QuerySet<Fr> =
+                    vec![("lc".to_string(), ("x".to_string(), x.publicize_unwrap()))]
+                        .into_iter()
+                        .collect();
+                let lc_proof = MpcPc::open_combinations(
+                    &ck,
+                    std::slice::from_ref(&lc),
+                    &polys,
+                    &comms,
+                    &query_set,
+                    challenge,
+                    &rands,
+                    Some(rng),
+                )
+                .unwrap();
+                let lc_val = polys[0].evaluate(&x) + polys[2].evaluate(&x) * MFr::from(2u32);
+                let evals: Evaluations<MFr, MFr> =
+                    vec![(("lc".to_string(), x), lc_val)].into_iter().collect();
+                assert!(MpcPc::check_combinations(
+                    &vk,
+                    &[lc],
+                    &comms,
+                    &query_set,
+                    &evals,
+                    &lc_proof,
+                    challenge,
+                    rng
+                )
+                .unwrap());
+                let plain_evals: Evaluations<Fr, Fr> = vec![(
+                    ("lc".to_string(), x.publicize_unwrap()),
+                    lc_val.publicize_unwrap(),
+                )]
+                .into_iter()
+                .collect();
+                let plain_lc_proof = ark_poly_commit::BatchLCProof {
+                    proof: lc_proof.proof.iter().map(proof_from_mpc).collect(),
+                    evals: None,
+                };
+                assert!(PC::check_combinations(
+                    &plain_vk,
+                    &[plain_lc],
+                    &plain_comms,
+                    &plain_query_set,
+                    &plain_evals,
+                    &plain_lc_proof,
+                    challenge.publicize_unwrap(),
+                    rng
+                )
+                .unwrap());
+                vec![lc_val]
             }
             c => unimplemented!("Cannot run_pairing {:?}", c),
         };
@@ -147,7 +608,7 @@ impl Computation {
         outputs
 
     }
-    fn run_pairing<P: PairingEngine>(
+    fn run_pairing<P: mpc::TargetPow>(
         &self,
         inputs: Vec<<P as PairingEngine>::Fr>,
     ) -> Vec<<P as PairingEngine>::Fr>
@@ -233,6 +694,51 @@ impl Computation {
                 assert_eq!(gtabcdp, gtabcd2p);
                 vec![]
             }
+            Computation::PairingExp => {
+                // (a * g1, g2) ^ b = (g1, g2) ^ (a * b)
+                assert_eq!(2, inputs.len());
+                let a = inputs[0];
+                let b = inputs[1];
+                let ab = a * b;
+                let g1 = <P as PairingEngine>::G1Projective::prime_subgroup_generator();
+                let g2 = <P as PairingEngine>::G2Projective::prime_subgroup_generator();
+                let g1a = <<P as PairingEngine>::G1Projective as Group>::mul(&g1, &a);
+                let gt = P::pairing(g1, g2);
+                let gta = P::pairing(g1a, g2);
+                let gtab = P::target_pow(gta, b);
+                let gtab2 = P::target_pow(gt, ab);
+                let gtabp = gtab.publicize();
+                assert_eq!(gtabp, gtab2.publicize());
+                // gtab^2 / gtab = gtab
+                let gtab3 = gtab.square() * gtab.inverse().unwrap();
+                assert_eq!(gtabp, gtab3.publicize());
+                vec![ab / b]
+            }
+            Computation::PairingMulti => {
+                // (a * g1, b * g2) * (c * g1, g2) * (g1, d * g2) * (g1, g2)
+                //     = (g1, g2) ^ (a * b + c + d + 1)
+                assert_eq!(4, inputs.len());
+                let a = inputs[0];
+                let b = inputs[1];
+                let c = inputs[2];
+                let d = inputs[3];
+                let g1 = <P as PairingEngine>::G1Projective::prime_subgroup_generator();
+                let g2 = <P as PairingEngine>::G2Projective::prime_subgroup_generator();
+                let g1a = <<P as PairingEngine>::G1Projective as Group>::mul(&g1, &a);
+                let g2b = <<P as PairingEngine>::G2Projective as Group>::mul(&g2, &b);
+                let g1c = <<P as PairingEngine>::G1Projective as Group>::mul(&g1, &c);
+                let g2d = <<P as PairingEngine>::G2Projective as Group>::mul(&g2, &d);
+                let gt = P::product_of_pairings(&[
+                    (g1a.into_affine().into(), g2b.into_affine().into()),
+                    (g1c.into_affine().into(), g2.into_affine().into()),
+                    (g1.into_affine().into(), g2d.into_affine().into()),
+                    (g1.into_affine().into(), g2.into_affine().into()),
+                ]);
+                let e = a * b + c + d + <P as PairingEngine>::Fr::one();
+                let gt2 = P::target_pow(P::pairing(g1, g2), e);
+                assert_eq!(gt.publicize(), gt2.publicize());
+                vec![e]
+            }
             c => unimplemented!("Cannot run_pairing {:?}", c),
         };
         println!("Outputs:");
@@ -244,7 +750,11 @@ impl Computation {
     fn run_gp<G: ProjectiveCurve + mpc::MpcWire>(
         &self,
         inputs: Vec<<G as Group>::ScalarField>,
-    ) -> Vec<G> {
+    ) -> Vec<G>
+    where
+        <G as Group>::ScalarField: mpc::MpcWire,
+        G::Affine: mpc::MsmCurve,
+    {
         let outputs = match self {
             Computation::Dh => {
                 assert_eq!(3, inputs.len());
@@ -261,6 +771,85 @@ impl Computation {
                 assert_eq!(gc, gcc);
                 vec![]
             }
+            Computation::Schnorr => {
+                // sign with shared key x and nonce k: (R, s) = (k * g, k + H(R, pk) * x)
+                assert_eq!(2, inputs.len());
+                let x = inputs[0];
+                let k = inputs[1];
+                let g = G::prime_subgroup_generator();
+                let pk = <G as Group>::mul(&g, &x).publicize();
+                let r = <G as Group>::mul(&g, &k).publicize();
+                let mut bytes = Vec::new();
+                r.serialize(&mut bytes).unwrap();
+                pk.serialize(&mut bytes).unwrap();
+                bytes.extend_from_slice(b"message");
+                let e = sha2::Sha256::digest(&bytes)
+                    .iter()
+                    .fold(<G as Group>::ScalarField::zero(), |acc, b| {
+                        acc * <G as Group>::ScalarField::from(256u64)
+                            + <G as Group>::ScalarField::from(*b)
+                    });
+                let s = (k + e * x).publicize();
+                // s * g = R + H(R, pk) * pk
+                assert_eq!(<G as Group>::mul(&g, &s), r + <G as Group>::mul(&pk, &e));
+                vec![pk, r]
+            }
+            Computation::Msm => {
+                // every combination of shared/public bases and scalars
+                let g = G::prime_subgroup_generator();
+                let mut points = Vec::new();
+                let mut scalars = Vec::new();
+                let mut expected = G::zero();
+                for (i, s) in inputs.iter().enumerate() {
+                    let shared_base = <G as Group>::mul(&g, s);
+                    let public_base =
+                        <G as Group>::mul(&g, &<G as Group>::ScalarField::from(i as u64 + 1));
+                    let public_scalar = <G as Group>::ScalarField::from(i as u64 + 2);
+                    for (b, x) in [
+                        (shared_base, *s),
+                        (shared_base, public_scalar),
+                        (public_base, *s),
+                        (public_base, public_scalar),
+                    ] {
+                        expected += <G as Group>::mul(&b, &x);
+                        points.push(b);
+                        scalars.push(x);
+                    }
+                }
+                let bases = G::batch_normalization_into_affine(&points);
+                assert!(bases
+                    .iter()
+                    .zip(&points)
+                    .all(|(b, p)| b.into_projective().publicize() == p.publicize()));
+                let msm = <G::Affine as mpc::MsmCurve>::multi_scalar_mul(&bases, &scalars);
+                assert_eq!(msm.publicize(), expected.publicize());
+                vec![msm]
+            }
+            Computation::Cofactor => {
+                assert_eq!(1, inputs.len());
+                let s = inputs[0];
+                let g = G::prime_subgroup_generator();
+                // a public point from bytes, cleared into the prime-order subgroup, where
+                // multiplying by the cofactor and its inverse is the identity
+                let p = (0u8..)
+                    .find_map(|i| G::Affine::from_random_bytes(&[i; 256]))
+                    .unwrap()
+                    .mul_by_cofactor();
+                assert_eq!(p.mul_by_cofactor_inv().mul_by_cofactor(), p);
+                // a shared point
+                let sg = <G as Group>::mul(&g, &s).into_affine();
+                let expected = <G as Group>::mul(&g.into_affine().mul_by_cofactor_to_projective(), &s);
+                assert_eq!(
+                    sg.mul_by_cofactor_to_projective().publicize(),
+                    expected.publicize()
+                );
+                let sg_inv = sg.mul_by_cofactor_inv();
+                assert_eq!(
+                    sg_inv.mul_by_cofactor().into_projective().publicize(),
+                    sg.into_projective().publicize()
+                );
+                vec![p.into_projective(), sg_inv.into_projective()]
+            }
             c => unimplemented!("Cannot run_dh {:?}", c),
         };
         println!("Outputs:");
@@ -269,6 +858,55 @@ impl Computation {
         }
         outputs
     }
+    fn run_shared_poly(&self, inputs: Vec<MFr>) -> Vec<MFr> {
+        let outputs = match self {
+            Computation::PolyArith => {
+                assert!(inputs.len() >= 3);
+                let p = MPoly::from_coefficients_slice(&inputs);
+                let b = MPoly::from_coefficients_slice(&inputs[1..]);
+                let (p_pub, b_pub) = (p.publicize(), b.publicize());
+                // KZG opening quotients, at a public point and at a shared one
+                let mut outputs = Vec::new();
+                for z in [MFr::from(2u32), inputs[0] + MFr::from(3u32)] {
+                    let y = p.evaluate(&z);
+                    let x_minus_z = MPoly::from_coefficients_vec(vec![-z, MFr::one()]);
+                    let (q, r) = (&p - &MPoly::from_coefficients_vec(vec![y])).divide_with_q_and_r(&x_minus_z);
+                    assert!(r.publicize().is_zero());
+                    let q_pub = q.publicize();
+                    let z_pub = P::from_coefficients_vec(vec![-z.publicize_unwrap(), Fr::one()]);
+                    let y_pub = P::from_coefficients_vec(vec![y.publicize_unwrap()]);
+                    assert_eq!(&(&q_pub * &z_pub) + &y_pub, p_pub);
+                    outputs.push(y);
+                }
+                // products and scaled sums
+                let pb = &p * &b;
+                assert_eq!(pb.publicize(), &p_pub * &b_pub);
+                let s = inputs[1];
+                let mut c = p.clone();
+                c += (s, &b);
+                let mut c_pub = p_pub.clone();
+                c_pub += (s.publicize_unwrap(), &b_pub);
+                assert_eq!(c.publicize(), c_pub);
+                // division by a shared divisor, with a remainder
+                let (q, r) = (&pb + &c).divide_with_q_and_r(&b);
+                assert!(r.degree() < b.degree());
+                let pb_c_pub = &(&p_pub * &b_pub) + &c_pub;
+                assert_eq!(&(&q.publicize() * &b_pub) + &r.publicize(), pb_c_pub);
+                assert_eq!(q.publicize(), &pb_c_pub / &b_pub);
+                // and over the shares of a polynomial
+                let (mp, mb) = (MP::from(p.clone()), MP::from(b.clone()));
+                assert_eq!((&mp * &mb).publicize_unwrap(), &p_pub * &b_pub);
+                assert_eq!((&(&mp * &mb) / &mb).publicize_unwrap(), p_pub);
+                outputs
+            }
+            c => unimplemented!("Cannot run_shared_poly {:?}", c),
+        };
+        println!("Outputs:");
+        for (i, v) in outputs.iter().enumerate() {
+            println!("  {}: {}", i, v);
+        }
+        outputs
+    }
     fn run_uv_poly<F: Field, P: UVPolynomial<F>>(&self, inputs: Vec<F>) -> Vec<F> {
         let outputs = match self {
             Computation::PolyEval => {
@@ -284,11 +922,98 @@ impl Computation {
         }
         outputs
     }
-    fn run_field<F: ComField>(&self, mut inputs: Vec<F>) -> Vec<F> {
+    fn run_base_field(&self, inputs: Vec<MFq>) -> Vec<MG1> {
+        let outputs = match self {
+            Computation::HashToCurve => {
+                assert_eq!(2, inputs.len());
+                type Params = ark_bls12_377::g1::Parameters;
+                let h = mpc::hash_to_curve::hash_to_curve::<Params>(&inputs[..1], &inputs[1..])[0];
+                // public inputs are hashed locally, to the same point
+                let public_inputs: Vec<MFq> = inputs.iter().map(|u| u.publicize()).collect();
+                let expected =
+                    mpc::hash_to_curve::hash_to_curve::<Params>(&public_inputs[..1], &public_inputs[1..])[0];
+                assert_eq!(h.publicize_checked(), Some(expected));
+                assert_eq!(h.publicize_checked(), Some(expected));
+                // off the curve
+                let bad = MG1::from_public(
+                    ark_bls12_377::G1Affine::new(Fq::one(), Fq::one(), false).into_projective(),
+                );
+                assert_eq!(bad.publicize_checked(), None);
+                // on the curve, outside the subgroup, and split into shares
+                let bad = (1u64..)
+                    .find_map(|x| ark_bls12_377::G1Affine::get_point_from_x(Fq::from(x), false))
+                    .unwrap();
+                assert!(!bad.is_in_correct_subgroup_assuming_on_curve());
+                let bad = if channel::am_first() {
+                    bad.into_projective()
+                } else {
+                    G1::zero()
+                };
+                assert_eq!(MG1::from_shared(bad).publicize_checked(), None);
+                vec![h]
+            }
+            c => unimplemented!("Cannot run_base_field {:?}", c),
+        };
+        println!("Outputs:");
+        for (i, v) in outputs.iter().enumerate() {
+            println!("  {}: {}", i, v);
+        }
+        outputs
+    }
+    fn run_bool(&self, inputs: Vec<MFr>) -> Vec<MFr> {
+        let outputs = match self {
+            Computation::BitDecomp => {
+                // x = bits_to_fields(field_to_bits(x))
+                let bits = mpc::boolean::field_to_bits(&inputs);
+                let outputs = mpc::boolean::bits_to_fields::<Fr>(&bits);
+                for (x, y) in inputs.iter().zip(&outputs) {
+                    assert_eq!(x.publicize(), y.publicize());
+                }
+                // reprs round-trip, shared flag and all
+                for x in &inputs {
+                    assert_eq!(MFr::from_repr(x.into_repr()), Some(*x));
+                }
+                outputs
+            }
+            Computation::BitOps => {
+                assert_eq!(2, inputs.len());
+                let bits = mpc::boolean::field_to_bits(&inputs);
+                let and = mpc::boolean::and(&bits[0], &bits[1]);
+                let xor = mpc::boolean::xor(&bits[0], &bits[1]);
+                let outputs = mpc::boolean::bits_to_fields::<Fr>(&[and, xor]);
+                // and/xor of the opened inputs, in the clear
+                let [x, y] = [0, 1].map(|i| inputs[i].publicize_unwrap().into_repr().0);
+                let clear = |op: fn(u64, u64) -> u64| {
+                    let limbs = BigInteger256([0, 1, 2, 3].map(|i| op(x[i], y[i])));
+                    Fr::from_le_bytes_mod_order(&limbs.to_bytes_le())
+                };
+                assert_eq!(outputs[0].publicize_unwrap(), clear(|a, b| a & b));
+                assert_eq!(outputs[1].publicize_unwrap(), clear(|a, b| a ^ b));
+                outputs
+            }
+            c => unimplemented!("Cannot run_bool {:?}", c),
+        };
+        println!("Outputs:");
+        for (i, v) in outputs.iter().enumerate() {
+            println!("  {}: {}", i, v);
+        }
+        outputs
+    }
+    fn run_field<F: ComField + BatchFft>(&self, mut inputs: Vec<F>) -> Vec<F> {
         let outputs = match self {
             Computation::Fft => {
                 let d = Radix2EvaluationDomain::<F>::new(inputs.len()).unwrap();
+                // the batched transforms act on the shares, and agree with MpcVal arithmetic's
+                let mut batch = [inputs.clone(), inputs.clone()];
+                F::batch_coset_fft(&d, &mut batch[1..]).unwrap();
+                F::batch_coset_ifft(&d, &mut batch[1..]).unwrap();
+                F::batch_ifft(&d, &mut batch).unwrap();
                 d.ifft_in_place(&mut inputs);
+                for v in &batch {
+                    for (x, y) in inputs.iter().zip(v) {
+                        assert_eq!(x.publicize_cow(), y.publicize_cow());
+                    }
+                }
                 inputs
             }
             Computation::Sum => {
@@ -441,12 +1166,19 @@ impl Computation {
 }
 
 type MFr = MpcVal<Fr>;
+type Fq = ark_bls12_377::Fq;
+type MFq = MpcVal<Fq>;
 type G1 = ark_bls12_377::G1Projective;
 type MG1 = MpcCurve<G1>;
 type G2 = ark_bls12_377::G2Projective;
 type MG2 = MpcCurve2<G2>;
+type EdFr = ark_ed_on_bls12_377::Fr;
+type MEdFr = MpcVal<EdFr>;
+type Ed = ark_ed_on_bls12_377::EdwardsProjective;
+type MEd = MpcCurve<Ed>;
 type P = ark_poly::univariate::DensePolynomial<Fr>;
 type MP = MpcVal<P>;
+type MPoly = mpc::poly::dense::MpcDensePolynomial<Fr>;
 
 fn main() -> () {
     let opt = Opt::from_args();
@@ -472,6 +1204,9 @@ fn main() -> () {
         .unwrap();
     channel::init(self_addr, peer_addr, opt.party == 0);
     debug!("Start");
+    let provided = opt.input_port.map(|port| {
+        input::receive_shares::<ark_bls12_377::G1Projective, _>((self_addr.ip(), port)).into_shared()
+    });
     let inputs = opt
         .args
         .iter()
@@ -504,6 +1239,38 @@ fn main() -> () {
                 println!("  {}: {}", i, v);
             }
         }
+        ComputationDomain::Edwards => {
+            let inputs = opt
+                .args
+                .iter()
+                .map(|i| MEdFr::from_shared(EdFr::from(*i)))
+                .collect::<Vec<MEdFr>>();
+            let outputs = opt.computation.run_gp::<MEd>(inputs);
+            let public_outputs = outputs
+                .into_iter()
+                .map(|c: MEd| c.publicize())
+                .collect::<Vec<_>>();
+            println!("Public Outputs:");
+            for (i, v) in public_outputs.iter().enumerate() {
+                println!("  {}: {}", i, v);
+            }
+        }
+        ComputationDomain::BaseField => {
+            let inputs = opt
+                .args
+                .iter()
+                .map(|i| MFq::from_shared(Fq::from(*i)))
+                .collect::<Vec<MFq>>();
+            let outputs = opt.computation.run_base_field(inputs);
+            let public_outputs = outputs
+                .into_iter()
+                .map(|c: MG1| c.publicize())
+                .collect::<Vec<_>>();
+            println!("Public Outputs:");
+            for (i, v) in public_outputs.iter().enumerate() {
+                println!("  {}: {}", i, v);
+            }
+        }
         ComputationDomain::G2 => {
             let outputs = opt.computation.run_gp::<MG2>(inputs);
             let public_outputs = outputs
@@ -529,7 +1296,7 @@ fn main() -> () {
             }
         }
         ComputationDomain::BlsPairing => {
-            let outputs = opt.computation.run_bls(inputs);
+            let outputs = opt.computation.run_bls(&opt.curve, opt.mpc_setup, opt.receiver, provided, inputs);
             let public_outputs = outputs
                 .into_iter()
                 .map(|c: MFr| c.publicize())
@@ -539,8 +1306,30 @@ fn main() -> () {
                 println!("  {}: {}", i, v);
             }
         }
+        ComputationDomain::Boolean => {
+            let outputs = opt.computation.run_bool(inputs);
+            let public_outputs = outputs
+                .into_iter()
+                .map(|c| c.publicize())
+                .collect::<Vec<_>>();
+            println!("Public Outputs:");
+            for (i, v) in public_outputs.iter().enumerate() {
+                println!("  {}: {}", i, v);
+            }
+        }
+        ComputationDomain::SharedPoly => {
+            let outputs = opt.computation.run_shared_poly(inputs);
+            let public_outputs = outputs
+                .into_iter()
+                .map(|c| c.publicize())
+                .collect::<Vec<_>>();
+            println!("Public Outputs:");
+            for (i, v) in public_outputs.iter().enumerate() {
+                println!("  {}: {}", i, v);
+            }
+        }
         ComputationDomain::PolyField => {
-            let outputs = opt.computation.run_uv_poly::<MFr, MP>(inputs);
+            let outputs = opt.computation.run_uv_poly::<MFr, MPoly>(inputs);
             let public_outputs = outputs
                 .into_iter()
                 .map(|c| c.publicize())
@@ -0,0 +1,118 @@
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use clap::arg_enum;
+use structopt::StructOpt;
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+mod mpc;
+use mpc::circom::{read_wtns, split_witness, write_wtns};
+use mpc::input::{send_shares, share_inputs};
+
+/// A named input, `name=value`
+#[derive(Debug)]
+struct Input(String, String);
+
+impl FromStr for Input {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected name=value, got {}", s))?;
+        Ok(Input(name.to_string(), value.to_string()))
+    }
+}
+
+arg_enum! {
+    #[derive(PartialEq, Debug)]
+    pub enum Curve {
+        Bls12377,
+        Bn254,
+    }
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "input", about = "Secret-share inputs to the proving parties")]
+struct Opt {
+    /// Party 0's input address
+    #[structopt(long, default_value = "localhost:8010")]
+    party0: String,
+
+    /// Party 1's input address
+    #[structopt(long, default_value = "localhost:8011")]
+    party1: String,
+
+    /// Also send Pedersen commitments to the inputs, for the parties to check
+    #[structopt(long)]
+    commit: bool,
+
+    /// The curve whose scalars the inputs are (circom uses bn254)
+    #[structopt(long, default_value = "bls12377")]
+    curve: Curve,
+
+    /// Share the wires of a circom witness (.wtns), named by index, instead
+    #[structopt(long, parse(from_os_str))]
+    wtns: Option<PathBuf>,
+
+    /// With --wtns, write per-party share files (<prefix>.0.wtns and <prefix>.1.wtns) instead of
+    /// sending them
+    #[structopt(long)]
+    split: Option<String>,
+
+    /// Inputs, as name=value
+    #[structopt()]
+    inputs: Vec<Input>,
+}
+
+impl Opt {
+    fn run<G: ProjectiveCurve>(&self) {
+        let mut seed = [0u8; 32];
+        getrandom::getrandom(&mut seed).unwrap();
+        let rng = &mut StdRng::from_seed(seed);
+        let inputs: Vec<(String, G::ScalarField)> = match &self.wtns {
+            Some(path) => {
+                let values = read_wtns(&std::fs::read(path).unwrap()).unwrap();
+                if let Some(prefix) = &self.split {
+                    for (i, shares) in split_witness(&values, rng).iter().enumerate() {
+                        std::fs::write(format!("{}.{}.wtns", prefix, i), write_wtns(shares))
+                            .unwrap();
+                    }
+                    return;
+                }
+                values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, x)| (i.to_string(), x))
+                    .collect()
+            }
+            None => self
+                .inputs
+                .iter()
+                .map(|Input(n, x)| {
+                    let x = G::ScalarField::from_str(x)
+                        .unwrap_or_else(|_| panic!("bad field element: {}", x));
+                    (n.clone(), x)
+                })
+                .collect(),
+        };
+        let [shares0, shares1] = share_inputs::<G, _>(&inputs, self.commit, rng);
+        send_shares(&self.party0, &shares0);
+        send_shares(&self.party1, &shares1);
+        if self.commit {
+            println!("Commitments:");
+            for ((name, _), c) in inputs.iter().zip(shares0.commitments()) {
+                println!("  {}: {}", name, c.into_projective());
+            }
+        }
+    }
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    env_logger::init();
+    match opt.curve {
+        Curve::Bls12377 => opt.run::<ark_bls12_377::G1Projective>(),
+        Curve::Bn254 => opt.run::<ark_bn254::G1Projective>(),
+    }
+}
@@ -0,0 +1,202 @@
+use ark_ff::{BigInteger, FftParameters, FpParameters, FromBytes, ToBytes};
+use std::cmp::Ordering;
+use std::io::{Read, Result as IoResult, Write};
+
+use super::*;
+
+impl<B> MpcBigInt<B> {
+    pub fn new(val: B, shared: bool) -> Self {
+        Self { val, shared }
+    }
+    pub fn from_public(val: B) -> Self {
+        Self::new(val, false)
+    }
+    pub fn from_shared(val: B) -> Self {
+        Self::new(val, true)
+    }
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+    /// The integer, which must be public.
+    pub fn public(&self, op: &str) -> &B {
+        assert!(
+            !self.shared,
+            "{} on a shared BigInt: the integer of a share is not a share of the integer",
+            op
+        );
+        &self.val
+    }
+    fn public_mut(&mut self, op: &str) -> &mut B {
+        self.public(op);
+        &mut self.val
+    }
+}
+
+impl<B: std::fmt::Display> std::fmt::Display for MpcBigInt<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.val)?;
+        if self.shared {
+            write!(f, " (shared)")
+        } else {
+            write!(f, " (public)")
+        }
+    }
+}
+
+impl<B: Ord> PartialOrd for MpcBigInt<B> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<B: Ord> Ord for MpcBigInt<B> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.public("cmp").cmp(other.public("cmp"))
+    }
+}
+
+impl<B: From<u64>> From<u64> for MpcBigInt<B> {
+    fn from(x: u64) -> Self {
+        Self::from_public(B::from(x))
+    }
+}
+
+impl<B: AsRef<[u64]>> AsRef<[u64]> for MpcBigInt<B> {
+    fn as_ref(&self) -> &[u64] {
+        self.public("as_ref").as_ref()
+    }
+}
+
+impl<B: AsMut<[u64]>> AsMut<[u64]> for MpcBigInt<B> {
+    fn as_mut(&mut self) -> &mut [u64] {
+        self.public_mut("as_mut").as_mut()
+    }
+}
+
+impl<B: ToBytes> ToBytes for MpcBigInt<B> {
+    fn write<W: Write>(&self, writer: W) -> IoResult<()> {
+        self.public("ToBytes").write(writer)
+    }
+}
+
+impl<B: FromBytes> FromBytes for MpcBigInt<B> {
+    fn read<R: Read>(reader: R) -> IoResult<Self> {
+        B::read(reader).map(Self::from_public)
+    }
+}
+
+impl<B: CanonicalSerialize> CanonicalSerialize for MpcBigInt<B> {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.public("serialize").serialize(writer)
+    }
+    fn serialized_size(&self) -> usize {
+        self.val.serialized_size()
+    }
+}
+
+impl<B: CanonicalDeserialize> CanonicalDeserialize for MpcBigInt<B> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        B::deserialize(reader).map(Self::from_public)
+    }
+}
+
+// Like `MpcVal`'s: the integer of a random share is a random share.
+impl<B: UniformRand> Distribution<MpcBigInt<B>> for rand::distributions::Standard {
+    fn sample<R: ?Sized + Rng>(&self, r: &mut R) -> MpcBigInt<B> {
+        MpcBigInt::from_shared(B::rand(r))
+    }
+}
+
+impl<B: zeroize::Zeroize> zeroize::Zeroize for MpcBigInt<B> {
+    fn zeroize(&mut self) {
+        self.val.zeroize();
+    }
+}
+
+impl<B: BigInteger> BigInteger for MpcBigInt<B> {
+    const NUM_LIMBS: usize = B::NUM_LIMBS;
+    fn add_nocarry(&mut self, other: &Self) -> bool {
+        self.public_mut("add_nocarry")
+            .add_nocarry(other.public("add_nocarry"))
+    }
+    fn sub_noborrow(&mut self, other: &Self) -> bool {
+        self.public_mut("sub_noborrow")
+            .sub_noborrow(other.public("sub_noborrow"))
+    }
+    fn mul2(&mut self) {
+        self.public_mut("mul2").mul2()
+    }
+    fn muln(&mut self, amt: u32) {
+        self.public_mut("muln").muln(amt)
+    }
+    fn div2(&mut self) {
+        self.public_mut("div2").div2()
+    }
+    fn divn(&mut self, amt: u32) {
+        self.public_mut("divn").divn(amt)
+    }
+    fn is_odd(&self) -> bool {
+        self.public("is_odd").is_odd()
+    }
+    fn is_even(&self) -> bool {
+        self.public("is_even").is_even()
+    }
+    fn is_zero(&self) -> bool {
+        self.public("is_zero").is_zero()
+    }
+    fn num_bits(&self) -> u32 {
+        self.public("num_bits").num_bits()
+    }
+    fn get_bit(&self, i: usize) -> bool {
+        self.public("get_bit").get_bit(i)
+    }
+    fn from_bits_be(bits: &[bool]) -> Self {
+        Self::from_public(B::from_bits_be(bits))
+    }
+    fn from_bits_le(bits: &[bool]) -> Self {
+        Self::from_public(B::from_bits_le(bits))
+    }
+    fn to_bytes_be(&self) -> Vec<u8> {
+        self.public("to_bytes_be").to_bytes_be()
+    }
+    fn to_bytes_le(&self) -> Vec<u8> {
+        self.public("to_bytes_le").to_bytes_le()
+    }
+    fn find_wnaf(&self) -> Vec<i64> {
+        self.public("find_wnaf").find_wnaf()
+    }
+}
+
+/// The parameters of a prime field `MpcVal`: its field's, with public `MpcBigInt` constants.
+pub struct MpcFpParams<P>(std::marker::PhantomData<P>);
+
+const fn public<B>(val: B) -> MpcBigInt<B> {
+    MpcBigInt { val, shared: false }
+}
+
+impl<P: FftParameters> FftParameters for MpcFpParams<P> {
+    type BigInt = MpcBigInt<P::BigInt>;
+    const TWO_ADICITY: u32 = P::TWO_ADICITY;
+    const TWO_ADIC_ROOT_OF_UNITY: Self::BigInt = public(P::TWO_ADIC_ROOT_OF_UNITY);
+    const SMALL_SUBGROUP_BASE: Option<u32> = P::SMALL_SUBGROUP_BASE;
+    const SMALL_SUBGROUP_BASE_ADICITY: Option<u32> = P::SMALL_SUBGROUP_BASE_ADICITY;
+    const LARGE_SUBGROUP_ROOT_OF_UNITY: Option<Self::BigInt> =
+        match P::LARGE_SUBGROUP_ROOT_OF_UNITY {
+            Some(r) => Some(public(r)),
+            None => None,
+        };
+}
+
+impl<P: FpParameters> FpParameters for MpcFpParams<P> {
+    const MODULUS: Self::BigInt = public(P::MODULUS);
+    const MODULUS_BITS: u32 = P::MODULUS_BITS;
+    const REPR_SHAVE_BITS: u32 = P::REPR_SHAVE_BITS;
+    const R: Self::BigInt = public(P::R);
+    const R2: Self::BigInt = public(P::R2);
+    const INV: u64 = P::INV;
+    const GENERATOR: Self::BigInt = public(P::GENERATOR);
+    const CAPACITY: u32 = P::CAPACITY;
+    const T: Self::BigInt = public(P::T);
+    const T_MINUS_ONE_DIV_TWO: Self::BigInt = public(P::T_MINUS_ONE_DIV_TWO);
+    const MODULUS_MINUS_ONE_DIV_TWO: Self::BigInt = public(P::MODULUS_MINUS_ONE_DIV_TWO);
+}
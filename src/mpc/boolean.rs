@@ -0,0 +1,250 @@
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use std::ops::{BitAnd, BitAndAssign, BitXor, BitXorAssign, Not};
+
+use super::*;
+
+impl std::fmt::Display for MpcBool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.val)?;
+        if self.shared {
+            write!(f, " (shared)")
+        } else {
+            write!(f, " (public)")
+        }
+    }
+}
+
+impl MpcBool {
+    pub fn new(val: bool, shared: bool) -> Self {
+        Self { val, shared }
+    }
+    pub fn from_public(val: bool) -> Self {
+        Self::new(val, false)
+    }
+    pub fn from_shared(val: bool) -> Self {
+        Self::new(val, true)
+    }
+}
+
+impl MpcWire for MpcBool {
+    type Base = bool;
+    fn publicize(self) -> Self {
+        if self.shared {
+            channel::bool_batch_publicize(vec![self])[0]
+        } else {
+            self
+        }
+    }
+    fn publicize_unwrap(self) -> Self::Base {
+        self.publicize().val
+    }
+    fn cast_to_shared(self) -> Self {
+        Self::from_shared(self.val)
+    }
+    fn publicize_cow<'b>(&'b self) -> Cow<'b, Self> {
+        if self.shared {
+            Cow::Owned(self.publicize())
+        } else {
+            Cow::Borrowed(self)
+        }
+    }
+}
+
+impl BitXorAssign for MpcBool {
+    fn bitxor_assign(&mut self, other: MpcBool) {
+        match (self.shared, other.shared) {
+            (true, false) => {
+                if channel::am_first() {
+                    self.val ^= other.val;
+                }
+            }
+            (false, true) => {
+                if channel::am_first() {
+                    self.val ^= other.val;
+                } else {
+                    self.val = other.val;
+                }
+            }
+            _ => {
+                self.val ^= other.val;
+            }
+        }
+        self.shared = self.shared || other.shared;
+    }
+}
+
+impl BitXor for MpcBool {
+    type Output = MpcBool;
+    fn bitxor(mut self, other: MpcBool) -> Self::Output {
+        self ^= other;
+        self
+    }
+}
+
+impl BitAndAssign for MpcBool {
+    fn bitand_assign(&mut self, other: MpcBool) {
+        if self.shared && other.shared {
+            *self = channel::bool_batch_and(vec![*self], vec![other])[0];
+        } else {
+            self.val &= other.val;
+            self.shared = self.shared || other.shared;
+        }
+    }
+}
+
+impl BitAnd for MpcBool {
+    type Output = MpcBool;
+    fn bitand(mut self, other: MpcBool) -> Self::Output {
+        self &= other;
+        self
+    }
+}
+
+impl Not for MpcBool {
+    type Output = MpcBool;
+    fn not(mut self) -> Self::Output {
+        if !self.shared || channel::am_first() {
+            self.val = !self.val;
+        }
+        self
+    }
+}
+
+/// Elementwise XOR of two bit vectors
+pub fn xor(a: &[MpcBool], b: &[MpcBool]) -> Vec<MpcBool> {
+    assert_eq!(a.len(), b.len());
+    a.iter().zip(b).map(|(a, b)| *a ^ *b).collect()
+}
+
+/// Elementwise AND of two bit vectors, in one round
+pub fn and(a: &[MpcBool], b: &[MpcBool]) -> Vec<MpcBool> {
+    channel::bool_batch_and(a.to_vec(), b.to_vec())
+}
+
+/// Convert bits to (0/1) elements of `F` (B2A), in one round.
+///
+/// Uses daBits: random bits `r` known both as XOR shares and as shares over `F`. Opening
+/// `e = b ^ r` reveals nothing, and then `b = e + r - 2er`.
+pub fn bits_to_field<F: PrimeField>(bits: &[MpcBool]) -> Vec<MpcVal<F>> {
+    if bits.is_empty() {
+        return Vec::new();
+    }
+    let (rbs, rfs) = channel::field_dabits::<F>(bits.len());
+    let es = channel::bool_batch_publicize(xor(bits, &rbs));
+    es.into_iter()
+        .zip(rfs)
+        .map(|(e, rf)| if e.val { MpcVal::one() - rf } else { rf })
+        .collect()
+}
+
+/// Recompose little-endian bit vectors into elements of `F` (B2A), in one round.
+pub fn bits_to_fields<F: PrimeField>(bit_vecs: &[Vec<MpcBool>]) -> Vec<MpcVal<F>> {
+    let flat: Vec<MpcBool> = bit_vecs.iter().flatten().cloned().collect();
+    let mut fs = bits_to_field::<F>(&flat).into_iter();
+    bit_vecs
+        .iter()
+        .map(|bits| {
+            let mut acc = MpcVal::<F>::zero();
+            let mut pow = F::one();
+            for b in fs.by_ref().take(bits.len()) {
+                acc += b * MpcVal::from_public(pow);
+                pow.double_in_place();
+            }
+            acc
+        })
+        .collect()
+}
+
+/// Decompose elements of `F` into their canonical little-endian bits (A2B).
+///
+/// Uses edaBits: random `r < p` known both as an element of `F` and bitwise. We open `c = x + r`,
+/// compute `c - r` with a borrow chain, and add `p` back if that underflowed. This takes two
+/// rounds per bit of `p`, batched across all inputs.
+pub fn field_to_bits<F: PrimeField>(xs: &[MpcVal<F>]) -> Vec<Vec<MpcBool>> {
+    if xs.is_empty() {
+        return Vec::new();
+    }
+    let n_bits = F::Params::MODULUS_BITS as usize;
+    let p_bits = F::Params::MODULUS.to_bits_le();
+    let (r_bits, rs) = channel::field_edabits::<F>(xs.len());
+    let cs = channel::field_batch_publicize(
+        xs.iter()
+            .zip(&rs)
+            .map(|(x, r)| *x + *r)
+            .collect(),
+    );
+    let c_bits: Vec<Vec<bool>> = cs.iter().map(|c| c.val.into_repr().to_bits_le()).collect();
+
+    // d = c - r, tracking the borrow out of each bit
+    let mut ds = vec![Vec::with_capacity(n_bits); xs.len()];
+    let mut borrows = vec![MpcBool::from_public(false); xs.len()];
+    for i in 0..n_bits {
+        let not_cs: Vec<MpcBool> = c_bits.iter().map(|c| MpcBool::from_public(!c[i])).collect();
+        let mut lhs = Vec::new();
+        let mut rhs = Vec::new();
+        for j in 0..xs.len() {
+            ds[j].push(r_bits[j][i] ^ borrows[j] ^ MpcBool::from_public(c_bits[j][i]));
+            lhs.push(not_cs[j] ^ r_bits[j][i]);
+            rhs.push(not_cs[j] ^ borrows[j]);
+        }
+        // borrow = maj(!c, r, borrow)
+        let ands = and(&lhs, &rhs);
+        for j in 0..xs.len() {
+            borrows[j] = not_cs[j] ^ ands[j];
+        }
+    }
+
+    // x = d + borrow * p
+    let mut outs = vec![Vec::with_capacity(n_bits); xs.len()];
+    let mut carries = vec![MpcBool::from_public(false); xs.len()];
+    for i in 0..n_bits {
+        let mut lhs = Vec::new();
+        let mut rhs = Vec::new();
+        for j in 0..xs.len() {
+            let y = if p_bits[i] {
+                borrows[j]
+            } else {
+                MpcBool::from_public(false)
+            };
+            outs[j].push(ds[j][i] ^ y ^ carries[j]);
+            lhs.push(ds[j][i] ^ y);
+            rhs.push(ds[j][i] ^ carries[j]);
+        }
+        // carry = maj(d, y, carry)
+        let ands = and(&lhs, &rhs);
+        for j in 0..xs.len() {
+            carries[j] = ds[j][i] ^ ands[j];
+        }
+    }
+    outs
+}
+
+/// The AND of each bit vector, in `log n` rounds, batched across vectors.
+pub fn all(bit_vecs: Vec<Vec<MpcBool>>) -> Vec<MpcBool> {
+    let mut vecs = bit_vecs;
+    while vecs.iter().any(|v| v.len() > 1) {
+        let (mut lhs, mut rhs) = (Vec::new(), Vec::new());
+        for v in &vecs {
+            let half = v.len() / 2;
+            lhs.extend_from_slice(&v[..half]);
+            rhs.extend_from_slice(&v[half..2 * half]);
+        }
+        let mut ands = and(&lhs, &rhs).into_iter();
+        for v in &mut vecs {
+            let odd = if v.len() % 2 == 1 { v.last().cloned() } else { None };
+            *v = ands.by_ref().take(v.len() / 2).collect();
+            v.extend(odd);
+        }
+    }
+    vecs.into_iter()
+        .map(|v| v.first().cloned().unwrap_or(MpcBool::from_public(true)))
+        .collect()
+}
+
+/// `[x == 0]` for each `x`: whether all of its bits are zero.
+pub fn is_zero<F: PrimeField>(xs: &[MpcVal<F>]) -> Vec<MpcBool> {
+    all(field_to_bits(xs)
+        .into_iter()
+        .map(|bits| bits.into_iter().map(|b| !b).collect())
+        .collect())
+}
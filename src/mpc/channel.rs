@@ -2,11 +2,15 @@ use lazy_static::lazy_static;
 use log::debug;
 use std::io::{Read, Write};
 use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::ops::AddAssign;
 use std::sync::Mutex;
 
-use super::MpcVal;
-use ark_ec::{PairingEngine, ProjectiveCurve};
-use ark_ff::Field;
+use super::{MpcBool, MpcVal};
+use ark_ec::models::{ModelParameters, SWModelParameters};
+use ark_ec::msm::VariableBaseMSM;
+use ark_ec::short_weierstrass_jacobian::{GroupAffine, GroupProjective};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{BigInteger, Field, FpParameters, One, PrimeField, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{start_timer, end_timer};
 
@@ -265,7 +269,6 @@ impl FieldChannel {
         }
     }
 
-    #[allow(dead_code)]
     fn field_sub<F: Field>(&mut self, mut a: MpcVal<F>, b: &MpcVal<F>) -> MpcVal<F> {
         match (a.shared, b.shared) {
             (true, true) | (false, false) => {
@@ -311,6 +314,137 @@ impl FieldChannel {
         a
     }
 
+    /// Each party samples its shares from its own entropy, so they're uniform if either party is
+    /// honest. No communication.
+    fn field_rands<F: Field>(&self, n: usize) -> Vec<MpcVal<F>> {
+        let rng = &mut super::party_rng();
+        (0..n).map(|_| MpcVal::from_shared(F::rand(rng))).collect()
+    }
+
+    fn field_batch_inv<F: Field>(&mut self, a: Vec<MpcVal<F>>) -> Vec<Option<MpcVal<F>>> {
+        debug!("batch field inverse: {}", a.len());
+        assert!(a.iter().all(|a| a.shared));
+        // open a * r for random r; then a^-1 = r * (a * r)^-1
+        let rs = self.field_rands(a.len());
+        let ars = self.field_batch_mul(a, rs.clone());
+        let ars = self.field_batch_publicize(ars);
+        ars.into_iter()
+            .zip(rs)
+            .map(|(ar, mut r)| {
+                r.val *= ar.val.inverse()?;
+                Some(r)
+            })
+            .collect()
+    }
+
+    fn field_batch_powers<F: Field>(&mut self, t: MpcVal<F>, n: usize) -> Vec<MpcVal<F>> {
+        debug!("field powers: {}", n);
+        assert!(t.shared);
+        // t^1..=t^k; each round doubles k
+        let mut powers = vec![t];
+        while powers.len() < n {
+            let k = powers.len();
+            let higher = self.field_batch_mul(powers.clone(), vec![powers[k - 1]; k]);
+            powers.extend(higher);
+        }
+        powers.truncate(n);
+        powers.insert(0, MpcVal::from_public(F::one()));
+        powers
+    }
+
+    fn bool_triple(&self) -> (MpcBool, MpcBool, MpcBool) {
+        //TODO
+        (
+            MpcBool::from_shared(self.talk_first),
+            MpcBool::from_shared(self.talk_first),
+            MpcBool::from_shared(self.talk_first),
+        )
+    }
+
+    fn bool_triples(&self, n: usize) -> (Vec<MpcBool>, Vec<MpcBool>, Vec<MpcBool>) {
+        let mut a = Vec::new();
+        let mut b = Vec::new();
+        let mut c = Vec::new();
+        for _ in 0..n {
+            let (x, y, z) = self.bool_triple();
+            a.push(x);
+            b.push(y);
+            c.push(z);
+        }
+        (a, b, c)
+    }
+
+    fn bool_batch_and(&mut self, mut a: Vec<MpcBool>, b: Vec<MpcBool>) -> Vec<MpcBool> {
+        debug!("batch bool & bool: {}", a.len());
+        assert_eq!(a.len(), b.len());
+        let shared: Vec<usize> = (0..a.len())
+            .filter(|&i| a[i].shared && b[i].shared)
+            .collect();
+        if !shared.is_empty() {
+            // x & y = z
+            let (xs, ys, zs) = self.bool_triples(shared.len());
+            // xa = x ^ a, yb = y ^ b
+            let mut xas = Vec::new();
+            let mut ybs = Vec::new();
+            for (j, &i) in shared.iter().enumerate() {
+                xas.push(MpcBool::from_shared(xs[j].val ^ a[i].val));
+                ybs.push(MpcBool::from_shared(ys[j].val ^ b[i].val));
+            }
+            xas.extend(ybs);
+            let opened = self.bool_batch_publicize(xas);
+            let (xas, ybs) = opened.split_at(shared.len());
+            // z ^ (x^a)y ^ x(y^b) ^ (x^a)(y^b) = ab
+            for (j, &i) in shared.iter().enumerate() {
+                let mut z = zs[j].val ^ (xas[j].val & ys[j].val) ^ (ybs[j].val & xs[j].val);
+                if self.talk_first {
+                    z ^= xas[j].val & ybs[j].val;
+                }
+                a[i].val = z;
+            }
+        }
+        for (a, b) in a.iter_mut().zip(b.iter()) {
+            if !(a.shared && b.shared) {
+                a.val &= b.val;
+                a.shared = a.shared || b.shared;
+            }
+        }
+        a
+    }
+
+    fn bool_batch_publicize(&mut self, mut a: Vec<MpcBool>) -> Vec<MpcBool> {
+        assert!(a.iter().all(|a| a.shared));
+        let mut bytes_out = vec![0u8; a.len().div_ceil(8)];
+        for (i, a) in a.iter().enumerate() {
+            bytes_out[i / 8] |= (a.val as u8) << (i % 8);
+        }
+        let bytes_in = self.exchange_bytes(bytes_out);
+        for (i, a) in a.iter_mut().enumerate() {
+            a.shared = false;
+            a.val ^= (bytes_in[i / 8] >> (i % 8)) & 1 == 1;
+        }
+        a
+    }
+
+    /// Random bits, shared both by XOR and over `F`.
+    fn field_dabits<F: Field>(&self, n: usize) -> (Vec<MpcBool>, Vec<MpcVal<F>>) {
+        //TODO
+        (
+            vec![MpcBool::from_shared(self.talk_first); n],
+            vec![MpcVal::from_shared(F::from(self.talk_first)); n],
+        )
+    }
+
+    /// Random elements of `F`, shared both over `F` and bitwise by XOR.
+    fn field_edabits<F: PrimeField>(&self, n: usize) -> (Vec<Vec<MpcBool>>, Vec<MpcVal<F>>) {
+        //TODO
+        let r = F::from(self.talk_first);
+        let bits = r.into_repr().to_bits_le()[..F::Params::MODULUS_BITS as usize]
+            .iter()
+            .map(|b| MpcBool::from_shared(*b))
+            .collect::<Vec<_>>();
+        (vec![bits; n], vec![MpcVal::from_shared(r); n])
+    }
+
     fn curve_scalar_triple<G: ProjectiveCurve>(&self) -> Triple<G, G::ScalarField, G> {
         let (fa, fb, fc) = self.field_triple();
         let mut ca = MpcVal::from_shared(G::prime_subgroup_generator());
@@ -355,6 +489,106 @@ impl FieldChannel {
         }
     }
 
+    fn curve_scalar_triples<G: ProjectiveCurve>(
+        &self,
+        n: usize,
+    ) -> (Vec<MpcVal<G>>, Vec<MpcVal<G::ScalarField>>, Vec<MpcVal<G>>) {
+        let mut a = Vec::new();
+        let mut b = Vec::new();
+        let mut c = Vec::new();
+        for _ in 0..n {
+            let (x, y, z) = self.curve_scalar_triple();
+            a.push(x);
+            b.push(y);
+            c.push(z);
+        }
+        (a, b, c)
+    }
+
+    /// sum_i a_i * b_i, with every masked term opened in one exchange.
+    fn curve_msm<G: ProjectiveCurve>(
+        &mut self,
+        a: Vec<MpcVal<G>>,
+        b: Vec<MpcVal<G::ScalarField>>,
+    ) -> MpcVal<G> {
+        debug!("curve msm: {}", a.len());
+        assert_eq!(a.len(), b.len());
+        assert!(a.iter().all(|a| a.shared));
+        assert!(b.iter().all(|b| b.shared));
+        // x_i * y_i = z_i
+        let (xs, ys, zs) = self.curve_scalar_triples::<G>(a.len());
+        // x + a, y + b
+        let xas: Vec<MpcVal<G>> = a
+            .iter()
+            .zip(&xs)
+            .map(|(a, x)| MpcVal::from_shared(a.val + x.val))
+            .collect();
+        let ybs: Vec<MpcVal<G::ScalarField>> = b
+            .iter()
+            .zip(&ys)
+            .map(|(b, y)| MpcVal::from_shared(b.val + y.val))
+            .collect();
+        let (xas, ybs) = self.batch_publicize2(xas, ybs);
+        let xas: Vec<G> = xas.into_iter().map(|xa| xa.val).collect();
+        let ybs: Vec<_> = ybs.into_iter().map(|yb| yb.val.into_repr()).collect();
+        // sum_i z_i - (x_i + a_i) * y_i - x_i * (y_i + b_i) + (x_i + a_i) * (y_i + b_i)
+        let xas = G::batch_normalization_into_affine(&xas);
+        let xs = G::batch_normalization_into_affine(&xs.iter().map(|x| x.val).collect::<Vec<_>>());
+        let ys: Vec<_> = ys.iter().map(|y| y.val.into_repr()).collect();
+        let mut out = zs.iter().fold(G::zero(), |acc, z| acc + z.val);
+        out -= VariableBaseMSM::multi_scalar_mul(&xas, &ys);
+        out -= VariableBaseMSM::multi_scalar_mul(&xs, &ybs);
+        if self.talk_first {
+            out += VariableBaseMSM::multi_scalar_mul(&xas, &ybs);
+        }
+        MpcVal::from_shared(out)
+    }
+
+    fn curve_batch_mul<G: ProjectiveCurve>(
+        &mut self,
+        mut a: Vec<MpcVal<G>>,
+        b: Vec<MpcVal<G::ScalarField>>,
+    ) -> Vec<MpcVal<G>> {
+        debug!("batch field * curve: {}", a.len());
+        assert_eq!(a.len(), b.len());
+        // Products with a public factor are local; the rest share one batch of triples
+        let (both, local): (Vec<usize>, Vec<usize>) =
+            (0..a.len()).partition(|&i| a[i].shared && b[i].shared);
+        for &i in &local {
+            a[i].val *= b[i].val;
+            a[i].shared = a[i].shared || b[i].shared;
+        }
+        if both.is_empty() {
+            return a;
+        }
+        // x * y = z
+        let (xs, ys, zs) = self.curve_scalar_triples::<G>(both.len());
+        // xa = x + a
+        let xas: Vec<MpcVal<G>> = both
+            .iter()
+            .zip(xs.iter())
+            .map(|(&i, x)| MpcVal::from_shared(a[i].val + x.val))
+            .collect();
+        // yb = y + b
+        let ybs: Vec<MpcVal<G::ScalarField>> = both
+            .iter()
+            .zip(ys.iter())
+            .map(|(&i, y)| MpcVal::from_shared(b[i].val + y.val))
+            .collect();
+        let (xas, ybs) = self.batch_publicize2(xas, ybs);
+        // xy - (x+a)y - x(y+b) + (x+a)(y+b) = ab
+        for (j, &i) in both.iter().enumerate() {
+            let mut z = zs[j];
+            z.val -= xas[j].val.mul(ys[j].val.into_repr());
+            z.val -= xs[j].val.mul(ybs[j].val.into_repr());
+            if self.talk_first {
+                z.val += xas[j].val.mul(ybs[j].val.into_repr());
+            }
+            a[i] = z;
+        }
+        a
+    }
+
     fn curve_add<F: ProjectiveCurve>(&mut self, mut a: MpcVal<F>, b: &MpcVal<F>) -> MpcVal<F> {
         match (a.shared, b.shared) {
             (true, true) | (false, false) => {
@@ -407,6 +641,114 @@ impl FieldChannel {
         MpcVal::from_public(other_val)
     }
 
+    fn curve_batch_publicize<G: ProjectiveCurve>(&mut self, a: Vec<MpcVal<G>>) -> Vec<MpcVal<G>> {
+        self.batch_publicize2::<G, G>(a, Vec::new()).0
+    }
+
+    /// Open a SS point without validating the other party's share; the caller checks the result.
+    /// `None` (at both parties) if either share does not even deserialize.
+    fn curve_publicize_unchecked<G: ProjectiveCurve>(&mut self, a: MpcVal<G>) -> Option<MpcVal<G>> {
+        assert!(a.shared);
+        let mut bytes_out = Vec::new();
+        a.val.serialize_unchecked(&mut bytes_out).unwrap();
+        let bytes_in = self.exchange_bytes(bytes_out);
+        let other_val = G::deserialize_unchecked(&bytes_in[..]).ok();
+        // Agree on whether both shares deserialized, so that the parties return the same thing
+        let other_ok = self.exchange(other_val.is_some());
+        let mut other_val = other_val.filter(|_| other_ok)?;
+        other_val += a.val;
+        Some(MpcVal::from_public(other_val))
+    }
+
+    /// Random points, shared both by their coordinates and as group elements.
+    fn curve_coord_rands<P: SWModelParameters>(
+        &self,
+        n: usize,
+    ) -> CoordRands<P> {
+        //TODO
+        let (x, y, p) = if self.talk_first {
+            let g = GroupAffine::<P>::prime_subgroup_generator();
+            (g.x, g.y, g.into_projective())
+        } else {
+            (P::BaseField::zero(), P::BaseField::zero(), GroupProjective::zero())
+        };
+        (
+            vec![(MpcVal::from_shared(x), MpcVal::from_shared(y)); n],
+            vec![MpcVal::from_shared(p); n],
+        )
+    }
+
+    /// Open two batches of SS values (e.g., points in two different groups), in one exchange.
+    fn batch_publicize2<
+        F: CanonicalSerialize + CanonicalDeserialize + AddAssign,
+        G: CanonicalSerialize + CanonicalDeserialize + AddAssign,
+    >(
+        &mut self,
+        mut a: Vec<MpcVal<F>>,
+        mut b: Vec<MpcVal<G>>,
+    ) -> (Vec<MpcVal<F>>, Vec<MpcVal<G>>) {
+        assert!(a.iter().all(|a| a.shared));
+        assert!(b.iter().all(|b| b.shared));
+        let mut bytes_out = Vec::new();
+        for a in &a {
+            a.val.serialize(&mut bytes_out).unwrap();
+        }
+        for b in &b {
+            b.val.serialize(&mut bytes_out).unwrap();
+        }
+        let bytes_in = self.exchange_bytes(bytes_out);
+        let mut bytes_in = &bytes_in[..];
+        for a in &mut a {
+            a.shared = false;
+            a.val += F::deserialize(&mut bytes_in).unwrap();
+        }
+        for b in &mut b {
+            b.shared = false;
+            b.val += G::deserialize(&mut bytes_in).unwrap();
+        }
+        (a, b)
+    }
+
+    /// Open two batches of SS values to party `receiver` (0 is the first party) only, in one
+    /// message. `None` for the other party.
+    fn batch_open_to2<
+        F: CanonicalSerialize + CanonicalDeserialize + AddAssign,
+        G: CanonicalSerialize + CanonicalDeserialize + AddAssign,
+    >(
+        &mut self,
+        receiver: usize,
+        mut a: Vec<MpcVal<F>>,
+        mut b: Vec<MpcVal<G>>,
+    ) -> Option<Batch2<F, G>> {
+        assert!(receiver < 2, "There are only two parties");
+        assert!(a.iter().all(|a| a.shared));
+        assert!(b.iter().all(|b| b.shared));
+        debug!("Open to {}: {} + {}", receiver, a.len(), b.len());
+        self.exchanges += 1;
+        if receiver != usize::from(!self.talk_first) {
+            let mut bytes_out = Vec::new();
+            for a in &a {
+                a.val.serialize(&mut bytes_out).unwrap();
+            }
+            for b in &b {
+                b.val.serialize(&mut bytes_out).unwrap();
+            }
+            self.send_slice(&bytes_out[..]);
+            return None;
+        }
+        let bytes_in = self.recv_vec();
+        let mut bytes_in = &bytes_in[..];
+        for a in &mut a {
+            a.shared = false;
+            a.val += F::deserialize(&mut bytes_in).unwrap();
+        }
+        for b in &mut b {
+            b.shared = false;
+            b.val += G::deserialize(&mut bytes_in).unwrap();
+        }
+        Some((a, b))
+    }
+
     fn pairing_triple<E: PairingEngine>(&self) -> Triple<E::G1Projective, E::G2Projective, E::Fqk> {
         let (fa, fb, fc) = self.field_triple();
         let mut g1a = MpcVal::from_public(E::G1Projective::prime_subgroup_generator());
@@ -453,6 +795,113 @@ impl FieldChannel {
         }
     }
 
+    fn miller_triple<E: PairingEngine>(&self) -> Triple<E::G1Projective, E::G2Projective, E::Fqk> {
+        let (fa, fb, fc) = self.field_triple::<E::Fr>();
+        let g1 = E::G1Projective::prime_subgroup_generator();
+        let g2 = E::G2Projective::prime_subgroup_generator();
+        let g1c = g1.mul(fc.val.into_repr());
+        (
+            MpcVal::from_shared(g1.mul(fa.val.into_repr())),
+            MpcVal::from_shared(g2.mul(fb.val.into_repr())),
+            MpcVal::from_shared(E::miller_loop(&[(
+                g1c.into_affine().into(),
+                g2.into_affine().into(),
+            )])),
+        )
+    }
+
+    /// The product of miller loops, with every shared pair masked and opened in one exchange.
+    fn miller_loop<E: PairingEngine>(
+        &mut self,
+        pairs: Vec<(MpcVal<E::G1Affine>, MpcVal<E::G2Affine>)>,
+    ) -> MpcVal<E::Fqk> {
+        debug!("miller loop: {}", pairs.len());
+        let shared = pairs.iter().any(|(a, b)| a.shared || b.shared);
+        let mut out = E::Fqk::one();
+        let mut terms: Vec<(E::G1Prepared, E::G2Prepared)> = Vec::new();
+        let mut masked = Vec::new();
+        let mut bytes_out = Vec::new();
+        for (a, b) in pairs {
+            match (a.shared, b.shared) {
+                (true, true) => {
+                    // e(x, y) = z
+                    let (x, y, z) = self.miller_triple::<E>();
+                    out *= z.val;
+                    // x + a, y + b
+                    let xa = (x.val + a.val.into_projective()).into_affine();
+                    let yb = (y.val + b.val.into_projective()).into_affine();
+                    xa.serialize_uncompressed(&mut bytes_out).unwrap();
+                    yb.serialize_uncompressed(&mut bytes_out).unwrap();
+                    masked.push((x.val, y.val, xa, yb));
+                }
+                (false, false) => {
+                    if self.talk_first || !shared {
+                        terms.push((a.val.into(), b.val.into()));
+                    }
+                }
+                _ => terms.push((a.val.into(), b.val.into())),
+            }
+        }
+        if !masked.is_empty() {
+            let bytes_in = self.exchange_bytes(bytes_out);
+            let mut bytes_in = &bytes_in[..];
+            for (x, y, xa, yb) in masked {
+                let xa = xa.into_projective()
+                    + E::G1Affine::deserialize_uncompressed(&mut bytes_in)
+                        .unwrap()
+                        .into_projective();
+                let yb = yb.into_projective()
+                    + E::G2Affine::deserialize_uncompressed(&mut bytes_in)
+                        .unwrap()
+                        .into_projective();
+                // e(x + a, y + b) / e(x + a, y) / e(x, y + b) * e(x, y) = e(a, b)
+                terms.push(((-xa).into_affine().into(), y.into_affine().into()));
+                terms.push(((-x).into_affine().into(), yb.into_affine().into()));
+                if self.talk_first {
+                    terms.push((xa.into_affine().into(), yb.into_affine().into()));
+                }
+            }
+        }
+        out *= E::miller_loop(&terms);
+        MpcVal::new(out, shared)
+    }
+
+    fn gt_scalar_triple<E: PairingEngine>(&self) -> Triple<E::Fqk, E::Fr, E::Fqk> {
+        let (fa, fb, fc) = self.field_triple::<E::Fr>();
+        let gt = E::pairing(
+            E::G1Projective::prime_subgroup_generator(),
+            E::G2Projective::prime_subgroup_generator(),
+        );
+        (
+            MpcVal::from_shared(gt.pow(fa.val.into_repr())),
+            fb,
+            MpcVal::from_shared(gt.pow(fc.val.into_repr())),
+        )
+    }
+
+    fn gt_exp<E: PairingEngine>(&mut self, a: MpcVal<E::Fqk>, b: MpcVal<E::Fr>) -> MpcVal<E::Fqk> {
+        debug!("gt ^ field");
+        if a.shared && b.shared {
+            // x ^ y = z
+            let (x, y, mut z) = self.gt_scalar_triple::<E>();
+            // a / x
+            let ax = a.val / x.val;
+            let ax = self.exchange(ax) * ax;
+            // b - y
+            let by = self.field_sub(b, &y);
+            let by = self.field_publicize(by).val.into_repr();
+            // z * (a/x)^y * x^(b-y) * (a/x)^(b-y) = a^b
+            z.val *= ax.pow(y.val.into_repr());
+            z.val *= x.val.pow(by);
+            if self.talk_first {
+                z.val *= ax.pow(by);
+            }
+            z
+        } else {
+            MpcVal::new(a.val.pow(b.val.into_repr()), a.shared || b.shared)
+        }
+    }
+
     fn stats(&self) -> ChannelStats {
         ChannelStats {
             bytes_recv: self.bytes_recv,
@@ -494,6 +943,15 @@ pub fn am_first() -> bool {
 
 pub type Triple<F, G, H> = (MpcVal<F>, MpcVal<G>, MpcVal<H>);
 
+/// Two batches of values (e.g., points in two different groups)
+pub type Batch2<F, G> = (Vec<MpcVal<F>>, Vec<MpcVal<G>>);
+
+/// Random points, as SS coordinates and as SS group elements
+pub type CoordRands<P> = (
+    Vec<(MpcVal<<P as ModelParameters>::BaseField>, MpcVal<<P as ModelParameters>::BaseField>)>,
+    Vec<MpcVal<GroupProjective<P>>>,
+);
+
 /// Get a field triple
 #[allow(dead_code)]
 pub fn field_triple<F: Field>() -> Triple<F, F, F> {
@@ -510,11 +968,112 @@ pub fn field_batch_mul<F: Field>(a: Vec<MpcVal<F>>, b: Vec<MpcVal<F>>) -> Vec<Mp
     get_ch!().field_batch_mul(a, b)
 }
 
+/// Open a batch of SS field elements
+pub fn field_batch_publicize<F: Field>(a: Vec<MpcVal<F>>) -> Vec<MpcVal<F>> {
+    get_ch!().field_batch_publicize(a)
+}
+
+/// Get random SS field elements
+pub fn field_rands<F: Field>(n: usize) -> Vec<MpcVal<F>> {
+    get_ch!().field_rands(n)
+}
+
+/// Invert an SS field element, or `None` if it is zero
+pub fn field_inv<F: Field>(a: MpcVal<F>) -> Option<MpcVal<F>> {
+    get_ch!().field_batch_inv(vec![a]).pop().unwrap()
+}
+
+/// Invert a batch of SS field elements, in one multiplication and one opening
+pub fn field_batch_inv<F: Field>(a: Vec<MpcVal<F>>) -> Vec<Option<MpcVal<F>>> {
+    get_ch!().field_batch_inv(a)
+}
+
+/// The powers `t^0..=t^n` of an SS field element, in `log n` rounds
+pub fn field_batch_powers<F: Field>(t: MpcVal<F>, n: usize) -> Vec<MpcVal<F>> {
+    get_ch!().field_batch_powers(t, n)
+}
+
+/// Compute a batch of ANDs over XOR-shared bits
+pub fn bool_batch_and(a: Vec<MpcBool>, b: Vec<MpcBool>) -> Vec<MpcBool> {
+    get_ch!().bool_batch_and(a, b)
+}
+
+/// Open a batch of XOR-shared bits
+pub fn bool_batch_publicize(a: Vec<MpcBool>) -> Vec<MpcBool> {
+    get_ch!().bool_batch_publicize(a)
+}
+
+/// Get random bits shared both by XOR and over `F`
+pub fn field_dabits<F: Field>(n: usize) -> (Vec<MpcBool>, Vec<MpcVal<F>>) {
+    get_ch!().field_dabits(n)
+}
+
+/// Get random elements of `F` shared both over `F` and bitwise by XOR
+pub fn field_edabits<F: PrimeField>(n: usize) -> (Vec<Vec<MpcBool>>, Vec<MpcVal<F>>) {
+    get_ch!().field_edabits(n)
+}
+
 /// Copute a field-curve product over SS data
 pub fn curve_mul<G: ProjectiveCurve>(a: MpcVal<G>, b: MpcVal<G::ScalarField>) -> MpcVal<G> {
     get_ch!().curve_mul(a, b)
 }
 
+/// Copute a batch of field-curve products over SS data
+pub fn curve_batch_mul<G: ProjectiveCurve>(
+    a: Vec<MpcVal<G>>,
+    b: Vec<MpcVal<G::ScalarField>>,
+) -> Vec<MpcVal<G>> {
+    get_ch!().curve_batch_mul(a, b)
+}
+
+/// Open a batch of SS curve points
+pub fn curve_batch_publicize<G: ProjectiveCurve>(a: Vec<MpcVal<G>>) -> Vec<MpcVal<G>> {
+    get_ch!().curve_batch_publicize(a)
+}
+
+/// Open a SS curve point without validating the other party's share
+pub fn curve_publicize_unchecked<G: ProjectiveCurve>(a: MpcVal<G>) -> Option<MpcVal<G>> {
+    get_ch!().curve_publicize_unchecked(a)
+}
+
+/// Get random points, shared both by their coordinates and as group elements
+pub fn curve_coord_rands<P: SWModelParameters>(
+    n: usize,
+) -> CoordRands<P> {
+    get_ch!().curve_coord_rands(n)
+}
+
+/// Open SS points from two groups, in one exchange
+pub fn curve_batch_publicize2<G: ProjectiveCurve, H: ProjectiveCurve>(
+    a: Vec<MpcVal<G>>,
+    b: Vec<MpcVal<H>>,
+) -> (Vec<MpcVal<G>>, Vec<MpcVal<H>>) {
+    get_ch!().batch_publicize2(a, b)
+}
+
+/// Open SS points in two groups to party `receiver` (0 is the first party) only, in one message.
+/// `None` for the other party.
+pub fn curve_batch_open_to2<G: ProjectiveCurve, H: ProjectiveCurve>(
+    receiver: usize,
+    a: Vec<MpcVal<G>>,
+    b: Vec<MpcVal<H>>,
+) -> Option<Batch2<G, H>> {
+    get_ch!().batch_open_to2(receiver, a, b)
+}
+
+/// Open SS points and SS field elements, in one exchange
+pub fn curve_field_batch_publicize<G: ProjectiveCurve, F: Field>(
+    a: Vec<MpcVal<G>>,
+    b: Vec<MpcVal<F>>,
+) -> (Vec<MpcVal<G>>, Vec<MpcVal<F>>) {
+    get_ch!().batch_publicize2(a, b)
+}
+
+/// Compute a multi-scalar multiplication over SS points and scalars, in one round
+pub fn curve_msm<G: ProjectiveCurve>(a: Vec<MpcVal<G>>, b: Vec<MpcVal<G::ScalarField>>) -> MpcVal<G> {
+    get_ch!().curve_msm(a, b)
+}
+
 /// Copute a pairing over SS data
 pub fn pairing<E: PairingEngine>(
     a: MpcVal<E::G1Projective>,
@@ -523,6 +1082,18 @@ pub fn pairing<E: PairingEngine>(
     get_ch!().pairing::<E>(a, b)
 }
 
+/// Compute a product of miller loops over SS data, in one round
+pub fn miller_loop<E: PairingEngine>(
+    pairs: Vec<(MpcVal<E::G1Affine>, MpcVal<E::G2Affine>)>,
+) -> MpcVal<E::Fqk> {
+    get_ch!().miller_loop::<E>(pairs)
+}
+
+/// Raise a (multiplicatively) SS target group element to an SS scalar power
+pub fn gt_exp<E: PairingEngine>(a: MpcVal<E::Fqk>, b: MpcVal<E::Fr>) -> MpcVal<E::Fqk> {
+    get_ch!().gt_exp::<E>(a, b)
+}
+
 //impl<F: Field, C: AffineCurve<ScalarField=F>> Triple<F, C> for C {
 //    fn triple() -> (MpcVal<Self>, MpcVal<F>, MpcVal<F>) {
 //        //TODO: fix
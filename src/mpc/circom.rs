@@ -0,0 +1,243 @@
+//! Circuits from circom: `.r1cs` constraint systems, and `.wtns` witnesses.
+//!
+//! See the `r1csfile` spec (`doc/r1cs_bin_format.md`) and snarkjs's `wtns` files. Both are
+//! sequences of typed sections, and hold field elements as little-endian integers (not in
+//! Montgomery form).
+//!
+//! Wire 0 is the constant one. Then come the public outputs and the public inputs, which become
+//! instance variables, and then the private inputs and internal signals, which become witness
+//! variables.
+use ark_ff::{BigInteger, FpParameters, FromBytes, PrimeField};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable},
+};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::io::{Error, ErrorKind, Result as IoResult};
+
+use super::*;
+
+/// A linear combination of wires
+pub type Terms<F> = Vec<(usize, F)>;
+
+/// `a * b = c`
+pub type Constraint<F> = [Terms<F>; 3];
+
+/// A circom constraint system.
+#[derive(Clone, Debug)]
+pub struct R1cs<F> {
+    /// Including the constant one
+    pub num_wires: usize,
+    /// Public outputs and public inputs
+    pub num_public: usize,
+    pub constraints: Vec<Constraint<F>>,
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Reads the integers and field elements of a file, in order
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> IoResult<&'a [u8]> {
+        if self.bytes.len() < n {
+            return Err(invalid("unexpected end of file"));
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+    fn u32(&mut self) -> IoResult<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+    fn u64(&mut self) -> IoResult<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+    fn usize(&mut self) -> IoResult<usize> {
+        Ok(self.u32()? as usize)
+    }
+    fn field<F: PrimeField>(&mut self) -> IoResult<F> {
+        let n8 = F::Params::MODULUS.to_bytes_le().len();
+        let repr = F::BigInt::read(self.take(n8)?)?;
+        F::from_repr(repr).ok_or_else(|| invalid("field element out of range"))
+    }
+    /// Checks that the file's prime is the modulus of `F`
+    fn prime<F: PrimeField>(&mut self) -> IoResult<()> {
+        let n8 = self.usize()?;
+        if self.take(n8)? != &F::Params::MODULUS.to_bytes_le()[..] {
+            return Err(invalid("the file is over a different field"));
+        }
+        Ok(())
+    }
+}
+
+/// The sections of a file with magic `magic`, by type. The first of each type wins.
+fn sections<'a>(bytes: &'a [u8], magic: &[u8; 4]) -> IoResult<HashMap<u32, Reader<'a>>> {
+    let mut r = Reader { bytes };
+    if r.take(4)? != magic {
+        return Err(invalid("bad magic number"));
+    }
+    let _version = r.u32()?;
+    let n_sections = r.u32()?;
+    let mut sections = HashMap::new();
+    for _ in 0..n_sections {
+        let ty = r.u32()?;
+        let size = r.u64()? as usize;
+        let bytes = r.take(size)?;
+        sections.entry(ty).or_insert(Reader { bytes });
+    }
+    Ok(sections)
+}
+
+fn section<'a, 'b>(
+    sections: &'b mut HashMap<u32, Reader<'a>>,
+    ty: u32,
+) -> IoResult<&'b mut Reader<'a>> {
+    sections
+        .get_mut(&ty)
+        .ok_or_else(|| invalid("missing section"))
+}
+
+/// Parse a `.r1cs` file.
+pub fn read_r1cs<F: PrimeField>(bytes: &[u8]) -> IoResult<R1cs<F>> {
+    let mut sections = sections(bytes, b"r1cs")?;
+    let header = section(&mut sections, 1)?;
+    header.prime::<F>()?;
+    let num_wires = header.usize()?;
+    let num_public = header.usize()? + header.usize()?;
+    let _num_private_inputs = header.u32()?;
+    let _num_labels = header.u64()?;
+    let num_constraints = header.usize()?;
+    // wire 0 is the constant one, and the public wires follow it
+    if num_public >= num_wires {
+        return Err(invalid("more public wires than wires"));
+    }
+
+    let body = section(&mut sections, 2)?;
+    let mut terms = || -> IoResult<Terms<F>> {
+        (0..body.u32()?)
+            .map(|_| {
+                let wire = body.usize()?;
+                if wire >= num_wires {
+                    return Err(invalid("no such wire"));
+                }
+                Ok((wire, body.field()?))
+            })
+            .collect()
+    };
+    let constraints = (0..num_constraints)
+        .map(|_| Ok([terms()?, terms()?, terms()?]))
+        .collect::<IoResult<_>>()?;
+    Ok(R1cs {
+        num_wires,
+        num_public,
+        constraints,
+    })
+}
+
+/// Parse a `.wtns` file: a value for each wire.
+pub fn read_wtns<F: PrimeField>(bytes: &[u8]) -> IoResult<Vec<F>> {
+    let mut sections = sections(bytes, b"wtns")?;
+    let header = section(&mut sections, 1)?;
+    header.prime::<F>()?;
+    let n = header.usize()?;
+    let values = section(&mut sections, 2)?;
+    (0..n).map(|_| values.field()).collect()
+}
+
+/// Write a `.wtns` file (version 2).
+pub fn write_wtns<F: PrimeField>(values: &[F]) -> Vec<u8> {
+    let modulus = F::Params::MODULUS.to_bytes_le();
+    let mut header = (modulus.len() as u32).to_le_bytes().to_vec();
+    header.extend_from_slice(&modulus);
+    header.extend_from_slice(&(values.len() as u32).to_le_bytes());
+    let body: Vec<u8> = values
+        .iter()
+        .flat_map(|v| v.into_repr().to_bytes_le())
+        .collect();
+
+    let mut bytes = b"wtns".to_vec();
+    bytes.extend_from_slice(&2u32.to_le_bytes());
+    bytes.extend_from_slice(&2u32.to_le_bytes());
+    for (ty, section) in [(1u32, header), (2, body)] {
+        bytes.extend_from_slice(&ty.to_le_bytes());
+        bytes.extend_from_slice(&(section.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&section);
+    }
+    bytes
+}
+
+/// Split a witness into additive shares for two parties, e.g. to write per-party `.wtns` files.
+pub fn split_witness<F: PrimeField, R: Rng>(values: &[F], rng: &mut R) -> [Vec<F>; 2] {
+    let (a, b) = values
+        .iter()
+        .map(|v| {
+            let s = F::rand(rng);
+            (s, *v - s)
+        })
+        .unzip();
+    [a, b]
+}
+
+impl<F: PrimeField> R1cs<F> {
+    /// The same constraints, for an MPC proof
+    pub fn to_mpc(&self) -> R1cs<MpcVal<F>> {
+        let terms = |t: &Terms<F>| t.iter().map(|(i, c)| (*i, MpcVal::from_public(*c))).collect();
+        R1cs {
+            num_wires: self.num_wires,
+            num_public: self.num_public,
+            constraints: self
+                .constraints
+                .iter()
+                .map(|[a, b, c]| [terms(a), terms(b), terms(c)])
+                .collect(),
+        }
+    }
+}
+
+/// A circom circuit, with (when proving) a value for each wire.
+#[derive(Clone)]
+pub struct CircomCircuit<F: Field> {
+    pub r1cs: R1cs<F>,
+    pub witness: Option<Vec<F>>,
+}
+
+impl<F: Field> CircomCircuit<F> {
+    /// The values of the public wires (not including the constant one)
+    pub fn public_inputs(&self) -> Option<Vec<F>> {
+        self.witness
+            .as_ref()
+            .map(|w| w[1..=self.r1cs.num_public].to_vec())
+    }
+}
+
+impl<F: Field> ConstraintSynthesizer<F> for CircomCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let witness = self.witness.as_ref();
+        if witness.is_some_and(|w| w.len() != self.r1cs.num_wires) {
+            return Err(SynthesisError::AssignmentMissing);
+        }
+        let value = |i: usize| witness.map(|w| w[i]).ok_or(SynthesisError::AssignmentMissing);
+
+        let mut vars = vec![Variable::One];
+        for i in 1..=self.r1cs.num_public {
+            vars.push(cs.new_input_variable(|| value(i))?);
+        }
+        for i in self.r1cs.num_public + 1..self.r1cs.num_wires {
+            vars.push(cs.new_witness_variable(|| value(i))?);
+        }
+
+        let lc = |terms: &Terms<F>| -> LinearCombination<F> {
+            terms.iter().fold(lc!(), |lc, (i, c)| lc + (*c, vars[*i]))
+        };
+        for [a, b, c] in &self.r1cs.constraints {
+            cs.enforce_constraint(lc(a), lc(b), lc(c))?;
+        }
+        Ok(())
+    }
+}
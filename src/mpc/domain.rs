@@ -0,0 +1,203 @@
+//! Evaluation domains over secret-shared vectors.
+//!
+//! FFTs are linear, so each party can transform its shares of a shared vector with the plain
+//! field's FFT (parallel, with the `parallel` feature), rather than one `MpcVal` operation at a
+//! time. `MpcDomain` does that over radix-2 domains only, so it supports at most `2^TWO_ADICITY`
+//! points (e.g. `2^28` for BN254). Mixed-radix domains are not supported: none of the curve
+//! crates' scalar fields set a `large_subgroup_root_of_unity` to build them from.
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::cfg_iter_mut;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::*;
+
+/// This party's shares of `v`, and whether it is shared. A vector with both shared and public
+/// entries is shared, with the first party holding the public ones.
+pub(crate) fn shares<F: Field>(v: &[MpcVal<F>]) -> (Vec<F>, bool) {
+    if v.iter().all(|x| x.shared == v[0].shared) {
+        return (
+            v.iter().map(|x| x.val).collect(),
+            !v.is_empty() && v[0].shared,
+        );
+    }
+    let first = channel::am_first();
+    let shares = v
+        .iter()
+        .map(|x| if x.shared || first { x.val } else { F::zero() })
+        .collect();
+    (shares, true)
+}
+
+/// An evaluation domain for vectors of `MpcVal<F>`s, which transforms their shares.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MpcDomain<F: FftField> {
+    base: GeneralEvaluationDomain<F>,
+}
+
+impl<F: FftField> MpcDomain<F> {
+    /// The radix-2 domain for `num_coeffs` coefficients, if the field has one.
+    pub fn new(num_coeffs: usize) -> Option<Self> {
+        match GeneralEvaluationDomain::new(num_coeffs)? {
+            base @ GeneralEvaluationDomain::Radix2(_) => Some(Self { base }),
+            GeneralEvaluationDomain::MixedRadix(_) => None,
+        }
+    }
+
+    /// The domain with the same points as `domain`, in the same order. `None` if `F` has no such
+    /// radix-2 domain.
+    pub fn from_domain<D: EvaluationDomain<MpcVal<F>>>(domain: &D) -> Option<Self>
+    where
+        MpcVal<F>: FftField,
+    {
+        Self::new(domain.size()).filter(|d| {
+            d.size() == domain.size() && MpcVal::from_public(d.base.element(1)) == domain.element(1)
+        })
+    }
+
+    /// The domain over `F`, which transforms the shares.
+    pub fn base(&self) -> &GeneralEvaluationDomain<F> {
+        &self.base
+    }
+
+    pub fn size(&self) -> usize {
+        self.base.size()
+    }
+
+    /// Apply a linear map to each vector in `vs`, by applying it to their shares.
+    fn on_shares(
+        &self,
+        vs: &mut [Vec<MpcVal<F>>],
+        map: impl Fn(&GeneralEvaluationDomain<F>, &mut Vec<F>) + Send + Sync,
+    ) {
+        cfg_iter_mut!(vs).for_each(|v| {
+            let (mut shares, shared) = shares(v);
+            map(&self.base, &mut shares);
+            *v = shares.into_iter().map(|s| MpcVal::new(s, shared)).collect();
+        });
+    }
+
+    /// Evaluate each polynomial (by coefficients) in `vs` over the domain.
+    pub fn batch_fft_in_place(&self, vs: &mut [Vec<MpcVal<F>>]) {
+        self.on_shares(vs, |d, v| d.fft_in_place(v));
+    }
+
+    /// Interpolate each vector of evaluations in `vs` over the domain.
+    pub fn batch_ifft_in_place(&self, vs: &mut [Vec<MpcVal<F>>]) {
+        self.on_shares(vs, |d, v| d.ifft_in_place(v));
+    }
+
+    /// Evaluate each polynomial in `vs` over the domain's coset.
+    pub fn batch_coset_fft_in_place(&self, vs: &mut [Vec<MpcVal<F>>]) {
+        self.on_shares(vs, |d, v| d.coset_fft_in_place(v));
+    }
+
+    /// Interpolate each vector of evaluations in `vs` over the domain's coset.
+    pub fn batch_coset_ifft_in_place(&self, vs: &mut [Vec<MpcVal<F>>]) {
+        self.on_shares(vs, |d, v| d.coset_ifft_in_place(v));
+    }
+
+    /// Divide each vector of coset evaluations in `vs` by the vanishing polynomial, which is the
+    /// constant `g^n - 1` over the coset: one inversion, then local scaling.
+    pub fn batch_divide_by_vanishing_poly_on_coset_in_place(&self, vs: &mut [Vec<MpcVal<F>>]) {
+        let z_inv = self
+            .base
+            .evaluate_vanishing_polynomial(F::multiplicative_generator())
+            .inverse()
+            .unwrap();
+        cfg_iter_mut!(vs).for_each(|v| v.iter_mut().for_each(|x| x.val *= z_inv));
+    }
+}
+
+/// Fields whose vectors an evaluation domain transforms in batches. `PolynomialDegreeTooLarge` if
+/// the domain cannot transform them (for `MpcVal`s, see `MpcDomain::from_domain`).
+pub trait BatchFft: FftField {
+    fn batch_fft<D: EvaluationDomain<Self>>(
+        domain: &D,
+        vs: &mut [Vec<Self>],
+    ) -> Result<(), SynthesisError> {
+        vs.iter_mut().for_each(|v| domain.fft_in_place(v));
+        Ok(())
+    }
+    fn batch_ifft<D: EvaluationDomain<Self>>(
+        domain: &D,
+        vs: &mut [Vec<Self>],
+    ) -> Result<(), SynthesisError> {
+        vs.iter_mut().for_each(|v| domain.ifft_in_place(v));
+        Ok(())
+    }
+    fn batch_coset_fft<D: EvaluationDomain<Self>>(
+        domain: &D,
+        vs: &mut [Vec<Self>],
+    ) -> Result<(), SynthesisError> {
+        vs.iter_mut().for_each(|v| domain.coset_fft_in_place(v));
+        Ok(())
+    }
+    fn batch_coset_ifft<D: EvaluationDomain<Self>>(
+        domain: &D,
+        vs: &mut [Vec<Self>],
+    ) -> Result<(), SynthesisError> {
+        vs.iter_mut().for_each(|v| domain.coset_ifft_in_place(v));
+        Ok(())
+    }
+    fn batch_divide_by_vanishing_poly_on_coset<D: EvaluationDomain<Self>>(
+        domain: &D,
+        vs: &mut [Vec<Self>],
+    ) -> Result<(), SynthesisError> {
+        vs.iter_mut()
+            .for_each(|v| domain.divide_by_vanishing_poly_on_coset_in_place(v));
+        Ok(())
+    }
+}
+
+fn mpc_domain<F: FftField, D: EvaluationDomain<MpcVal<F>>>(
+    domain: &D,
+) -> Result<MpcDomain<F>, SynthesisError>
+where
+    MpcVal<F>: FftField,
+{
+    MpcDomain::from_domain(domain).ok_or(SynthesisError::PolynomialDegreeTooLarge)
+}
+
+impl<F: FftField> BatchFft for MpcVal<F>
+where
+    MpcVal<F>: FftField,
+{
+    fn batch_fft<D: EvaluationDomain<Self>>(
+        domain: &D,
+        vs: &mut [Vec<Self>],
+    ) -> Result<(), SynthesisError> {
+        mpc_domain(domain)?.batch_fft_in_place(vs);
+        Ok(())
+    }
+    fn batch_ifft<D: EvaluationDomain<Self>>(
+        domain: &D,
+        vs: &mut [Vec<Self>],
+    ) -> Result<(), SynthesisError> {
+        mpc_domain(domain)?.batch_ifft_in_place(vs);
+        Ok(())
+    }
+    fn batch_coset_fft<D: EvaluationDomain<Self>>(
+        domain: &D,
+        vs: &mut [Vec<Self>],
+    ) -> Result<(), SynthesisError> {
+        mpc_domain(domain)?.batch_coset_fft_in_place(vs);
+        Ok(())
+    }
+    fn batch_coset_ifft<D: EvaluationDomain<Self>>(
+        domain: &D,
+        vs: &mut [Vec<Self>],
+    ) -> Result<(), SynthesisError> {
+        mpc_domain(domain)?.batch_coset_ifft_in_place(vs);
+        Ok(())
+    }
+    fn batch_divide_by_vanishing_poly_on_coset<D: EvaluationDomain<Self>>(
+        domain: &D,
+        vs: &mut [Vec<Self>],
+    ) -> Result<(), SynthesisError> {
+        mpc_domain(domain)?.batch_divide_by_vanishing_poly_on_coset_in_place(vs);
+        Ok(())
+    }
+}
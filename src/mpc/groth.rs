@@ -1,15 +1,24 @@
 use super::*;
 use ark_std::{test_rng, start_timer, end_timer};
 use ark_groth16::{
-    generate_random_parameters, prepare_verifying_key, verify_proof, Proof,
+    generate_random_parameters, prepare_verifying_key, verify_proof, PreparedVerifyingKey, Proof,
     ProvingKey, VerifyingKey,
 };
+use super::domain::BatchFft;
+use super::input::SharedInputs;
 use super::silly::MySillyCircuit;
+use ark_relations::r1cs::{ConstraintSynthesizer, Result as R1CSResult, SynthesisError};
+use ark_std::rand::Rng;
 
+pub mod generator;
 pub mod prover;
 pub mod r1cs_to_qap;
 
-pub fn vk_to_mpc(k: VerifyingKey<Bls12_377>) -> VerifyingKey<MpcPairingEngine<Bls12_377>> {
+pub fn vk_to_mpc<E: PairingEngine>(k: VerifyingKey<E>) -> VerifyingKey<MpcPairingEngine<E>>
+where
+    MpcPairingEngine<E>:
+        PairingEngine<G1Affine = MpcCurve<E::G1Affine>, G2Affine = MpcCurve2<E::G2Affine>>,
+{
     VerifyingKey {
         alpha_g1: MpcCurve::from_public(k.alpha_g1),
         beta_g2: MpcCurve2::from_public(k.beta_g2),
@@ -23,7 +32,11 @@ pub fn vk_to_mpc(k: VerifyingKey<Bls12_377>) -> VerifyingKey<MpcPairingEngine<Bl
     }
 }
 
-pub fn pk_to_mpc(k: ProvingKey<Bls12_377>) -> ProvingKey<MpcPairingEngine<Bls12_377>> {
+pub fn pk_to_mpc<E: PairingEngine>(k: ProvingKey<E>) -> ProvingKey<MpcPairingEngine<E>>
+where
+    MpcPairingEngine<E>:
+        PairingEngine<G1Affine = MpcCurve<E::G1Affine>, G2Affine = MpcCurve2<E::G2Affine>>,
+{
     ProvingKey {
         vk: vk_to_mpc(k.vk),
         beta_g1: MpcCurve::from_public(k.beta_g1),
@@ -44,47 +57,346 @@ pub fn pk_to_mpc(k: ProvingKey<Bls12_377>) -> ProvingKey<MpcPairingEngine<Bls12_
     }
 }
 
-pub fn pf_publicize(k: Proof<MpcPairingEngine<Bls12_377>>) -> Proof<Bls12_377> {
+/// Our shares of a proof's points, as (G1, G2) batches
+fn pf_shares<E: PairingEngine>(
+    k: Proof<MpcPairingEngine<E>>,
+) -> channel::Batch2<E::G1Projective, E::G2Projective>
+where
+    MpcPairingEngine<E>:
+        PairingEngine<G1Affine = MpcCurve<E::G1Affine>, G2Affine = MpcCurve2<E::G2Affine>>,
+{
+    (
+        vec![
+            MpcVal::new(k.a.val.into_projective(), k.a.shared),
+            MpcVal::new(k.c.val.into_projective(), k.c.shared),
+        ],
+        vec![MpcVal::new(k.b.val.into_projective(), k.b.shared)],
+    )
+}
+
+fn pf_from_opened<E: PairingEngine>(
+    g1s: Vec<MpcVal<E::G1Projective>>,
+    g2s: Vec<MpcVal<E::G2Projective>>,
+) -> Proof<E> {
+    Proof {
+        a: g1s[0].val.into_affine(),
+        b: g2s[0].val.into_affine(),
+        c: g1s[1].val.into_affine(),
+    }
+}
+
+pub fn pf_publicize<E: PairingEngine>(k: Proof<MpcPairingEngine<E>>) -> Proof<E>
+where
+    MpcPairingEngine<E>:
+        PairingEngine<G1Affine = MpcCurve<E::G1Affine>, G2Affine = MpcCurve2<E::G2Affine>>,
+{
     let pf_timer = start_timer!(|| "publicize proof");
-    let r = Proof {
-        a: k.a.publicize_unwrap(),
-        b: k.b.publicize_unwrap(),
-        c: k.c.publicize_unwrap(),
-    };
+    let (g1s, g2s) = pf_shares(k);
+    let (g1s, g2s) = channel::curve_batch_publicize2(g1s, g2s);
+    let r = pf_from_opened(g1s, g2s);
+    end_timer!(pf_timer);
+    r
+}
+
+/// Open a proof to party `receiver` (0 is the first party) only. `None` for the other party.
+pub fn pf_open_to<E: PairingEngine>(k: Proof<MpcPairingEngine<E>>, receiver: usize) -> Option<Proof<E>>
+where
+    MpcPairingEngine<E>:
+        PairingEngine<G1Affine = MpcCurve<E::G1Affine>, G2Affine = MpcCurve2<E::G2Affine>>,
+{
+    let pf_timer = start_timer!(|| "open proof");
+    let (g1s, g2s) = pf_shares(k);
+    let r = channel::curve_batch_open_to2(receiver, g1s, g2s)
+        .map(|(g1s, g2s)| pf_from_opened(g1s, g2s));
     end_timer!(pf_timer);
     r
 }
 
-pub fn mpc_test_prove_and_verify(n_iters: usize) {
+/// Create a zero-knowledge proof over shared data.
+///
+/// The prover randomness `r` and `s` is sampled jointly (`channel::field_rands`: each party
+/// samples its share from its own entropy), so it's uniform if either party is honest. They're
+/// checked to be nonzero (so that `r * s` is) with a masked inversion, which doesn't open them,
+/// and resampled if not.
+pub fn create_shared_random_proof<E, C>(
+    circuit: C,
+    pk: &ProvingKey<MpcPairingEngine<E>>,
+) -> R1CSResult<Proof<MpcPairingEngine<E>>>
+where
+    E: PairingEngine,
+    MpcPairingEngine<E>: PairingEngine<
+        Fr = MpcVal<E::Fr>,
+        G1Projective = MpcCurve<E::G1Projective>,
+        G1Affine = MpcCurve<E::G1Affine>,
+        G2Affine = MpcCurve2<E::G2Affine>,
+    >,
+    MpcCurve<E::G1Projective>: BatchScalarMul,
+    MpcCurve<E::G1Affine>: MsmCurve,
+    MpcCurve2<E::G2Affine>: MsmCurve,
+    MpcVal<E::Fr>: BatchProd + BatchFft,
+    C: ConstraintSynthesizer<MpcVal<E::Fr>>,
+{
+    let (r, s) = loop {
+        let rs = channel::field_rands::<E::Fr>(2);
+        if channel::field_batch_inv(rs.clone()).iter().all(Option::is_some) {
+            break (rs[0], rs[1]);
+        }
+    };
+    prover::create_proof::<MpcPairingEngine<E>, C>(circuit, pk, r, s)
+}
+
+/// Create a proof over shared data, and open it: to both parties if `receiver` is `None`, and
+/// otherwise only to party `receiver` (0 is the first party). `None` for parties that don't get
+/// the proof.
+pub fn create_random_proof_for<E, C>(
+    circuit: C,
+    pk: &ProvingKey<MpcPairingEngine<E>>,
+    receiver: Option<usize>,
+) -> R1CSResult<Option<Proof<E>>>
+where
+    E: PairingEngine,
+    MpcPairingEngine<E>: PairingEngine<
+        Fr = MpcVal<E::Fr>,
+        G1Projective = MpcCurve<E::G1Projective>,
+        G1Affine = MpcCurve<E::G1Affine>,
+        G2Affine = MpcCurve2<E::G2Affine>,
+    >,
+    MpcCurve<E::G1Projective>: BatchScalarMul,
+    MpcCurve<E::G1Affine>: MsmCurve,
+    MpcCurve2<E::G2Affine>: MsmCurve,
+    MpcVal<E::Fr>: BatchProd + BatchFft,
+    C: ConstraintSynthesizer<MpcVal<E::Fr>>,
+{
+    let mpc_proof = create_shared_random_proof::<E, _>(circuit, pk)?;
+    Ok(match receiver {
+        None => Some(pf_publicize(mpc_proof)),
+        Some(receiver) => pf_open_to(mpc_proof, receiver),
+    })
+}
+
+/// Rerandomize a public proof once for each of `n_recipients` verifiers, so that they can't link
+/// their copies (see [prover::rerandomize_proof]).
+pub fn rerandomize_for_recipients<E: PairingEngine, R: Rng>(
+    vk: &VerifyingKey<E>,
+    proof: &Proof<E>,
+    n_recipients: usize,
+    rng: &mut R,
+) -> Vec<Proof<E>> {
+    (0..n_recipients)
+        .map(|_| prover::rerandomize_proof(rng, vk, proof))
+        .collect()
+}
+
+/// `[x == 1]` for a multiplicatively shared `x`, as a shared bit.
+///
+/// `x_0 x_1 = 1` iff `x_0 = x_1^-1`: each party hashes its side into `F`, and we test whether the
+/// (additively shared) difference of the hashes is zero.
+fn mul_shared_is_one<F: PrimeField, K: Field>(x: MpcMulVal<K>) -> MpcBool {
+    if !x.shared {
+        return MpcBool::from_public(x.val.is_one());
+    }
+    let side = if channel::am_first() {
+        x.val
+    } else {
+        x.val.inverse().expect("shares of a unit are units")
+    };
+    let mut bytes = Vec::new();
+    side.serialize(&mut bytes).unwrap();
+    let h = F::from_le_bytes_mod_order(&sha2::Sha256::digest(&bytes));
+    let d = MpcVal::from_shared(if channel::am_first() { h } else { -h });
+    boolean::is_zero(&[d])[0]
+}
+
+/// Verify a proof whose points, and public inputs, may be shared. The result is a shared bit.
+///
+/// `pvk` comes from `prepare_verifying_key(&vk_to_mpc(vk))`. The three pairings are one batched
+/// miller loop (with one opening), and the result is compared with `e(alpha, beta)` without being
+/// opened.
+pub fn verify_shared_proof<E: PairingEngine>(
+    pvk: &PreparedVerifyingKey<MpcPairingEngine<E>>,
+    proof: &Proof<MpcPairingEngine<E>>,
+    public_inputs: &[MpcVal<E::Fr>],
+) -> R1CSResult<MpcBool>
+where
+    MpcPairingEngine<E>: PairingEngine<
+        Fr = MpcVal<E::Fr>,
+        G1Affine = MpcCurve<E::G1Affine>,
+        G2Affine = MpcCurve2<E::G2Affine>,
+        Fqk = MpcMulVal<E::Fqk>,
+    >,
+{
+    if (public_inputs.len() + 1) != pvk.vk.gamma_abc_g1.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+    // The input points are public, so this is local
+    let mut g_ic = MpcVal::from_public(pvk.vk.gamma_abc_g1[0].val.into_projective());
+    for (x, b) in public_inputs.iter().zip(&pvk.vk.gamma_abc_g1[1..]) {
+        g_ic += MpcVal::new(b.val.mul(x.val.into_repr()), x.shared);
+    }
+    let g_ic = MpcCurve::new(g_ic.val.into_affine(), g_ic.shared);
+
+    let qap = MpcPairingEngine::<E>::miller_loop(
+        [
+            (proof.a.into(), proof.b.into()),
+            (g_ic.into(), pvk.gamma_g2_neg_pc.clone()),
+            (proof.c.into(), pvk.delta_g2_neg_pc.clone()),
+        ]
+        .iter(),
+    );
+    let test = MpcPairingEngine::<E>::final_exponentiation(&qap)
+        .ok_or(SynthesisError::UnexpectedIdentity)?;
+    Ok(mul_shared_is_one::<E::Fr, _>(test / pvk.alpha_g1_beta_g2))
+}
+
+/// With `mpc_setup`, the parameters are generated in MPC; otherwise, one party could know the
+/// toxic waste. With a `receiver`, only that party gets (and checks) the proof, as two
+/// rerandomized copies.
+///
+/// Each witness is proven twice, with prover randomness from each party's own entropy, and each
+/// proof is rerandomized into two copies. All six proofs must verify, and no two may agree on any
+/// of `A`, `B` or `C`: for the same witness, any two `A`s (`B`s) differ by `(r - r')` (`(s - s')`)
+/// times delta, which is nonzero unless the randomness repeats. This is only a distinctness check;
+/// it doesn't show that the proofs are unlinkable. A third proof is checked without being opened,
+/// against shared inputs, and a fourth is made without zero-knowledge (public `r = s = 0`).
+pub fn mpc_test_prove_and_verify<E: PairingEngine>(
+    n_iters: usize,
+    mpc_setup: bool,
+    receiver: Option<usize>,
+) where
+    MpcPairingEngine<E>: PairingEngine<
+        Fr = MpcVal<E::Fr>,
+        G1Projective = MpcCurve<E::G1Projective>,
+        G1Affine = MpcCurve<E::G1Affine>,
+        G2Affine = MpcCurve2<E::G2Affine>,
+        Fqk = MpcMulVal<E::Fqk>,
+    >,
+    MpcCurve<E::G1Projective>: BatchScalarMul,
+    MpcCurve<E::G1Affine>: MsmCurve,
+    MpcCurve2<E::G2Affine>: MsmCurve,
+    MpcVal<E::Fr>: BatchProd + BatchFft,
+{
     let rng = &mut test_rng();
 
-    let params =
-        generate_random_parameters::<Bls12_377, _, _>(MySillyCircuit { a: None, b: None }, rng)
-            .unwrap();
+    let params = if mpc_setup {
+        generator::generate_random_parameters::<E, _, _>(
+            MySillyCircuit { a: None, b: None },
+            &mut party_rng(),
+        )
+    } else {
+        generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng)
+    }
+    .unwrap();
 
-    let pvk = prepare_verifying_key::<Bls12_377>(&params.vk);
+    let pvk = prepare_verifying_key::<E>(&params.vk);
+    let mpc_pvk = prepare_verifying_key::<MpcPairingEngine<E>>(&vk_to_mpc(params.vk.clone()));
+    let vk = params.vk.clone();
     let mpc_params = pk_to_mpc(params);
 
+    let prover_rng = &mut party_rng();
+
     for _ in 0..n_iters {
-        let a = MpcVal::<ark_bls12_377::Fr>::rand(rng);
-        let b = MpcVal::<ark_bls12_377::Fr>::rand(rng);
+        let a = MpcVal::<E::Fr>::rand(rng);
+        let b = MpcVal::<E::Fr>::rand(rng);
         let mut c = a;
         c.mul_assign(&b);
 
-        let mpc_proof = prover::create_random_proof::<MpcPairingEngine<Bls12_377>, _, _>(
+        let prove = || {
+            create_random_proof_for::<E, _>(
+                MySillyCircuit {
+                    a: Some(a),
+                    b: Some(b),
+                },
+                &mpc_params,
+                receiver,
+            )
+            .unwrap()
+        };
+        let proof = prove();
+        let other_proof = prove();
+        let shared_proof = create_shared_random_proof::<E, _>(
             MySillyCircuit {
                 a: Some(a),
                 b: Some(b),
             },
             &mpc_params,
-            rng
         )
         .unwrap();
-        let proof = pf_publicize(mpc_proof);
+        let no_zk_proof = pf_publicize(
+            prover::create_proof_no_zk::<MpcPairingEngine<E>, _>(
+                MySillyCircuit {
+                    a: Some(a),
+                    b: Some(b),
+                },
+                &mpc_params,
+            )
+            .unwrap(),
+        );
+        let valid = verify_shared_proof(&mpc_pvk, &shared_proof, &[c]).unwrap();
+        let invalid = verify_shared_proof(&mpc_pvk, &shared_proof, &[a]).unwrap();
+        assert!(valid.shared && invalid.shared);
+        assert!(valid.publicize_unwrap());
+        assert!(!invalid.publicize_unwrap());
+
         let pub_a = a.publicize_unwrap();
         let pub_c = c.publicize_unwrap();
+        assert!(verify_proof(&pvk, &no_zk_proof, &[pub_c]).unwrap());
 
-        assert!(verify_proof(&pvk, &proof, &[pub_c]).unwrap());
-        assert!(!verify_proof(&pvk, &proof, &[pub_a]).unwrap());
+        let me = usize::from(!channel::am_first());
+        match receiver {
+            Some(r) if r != me => assert!(proof.is_none() && other_proof.is_none()),
+            _ => {
+                let proof = proof.unwrap();
+                let other_proof = other_proof.unwrap();
+                assert!(verify_proof(&pvk, &proof, &[pub_c]).unwrap());
+                assert!(verify_proof(&pvk, &other_proof, &[pub_c]).unwrap());
+                assert!(!verify_proof(&pvk, &proof, &[pub_a]).unwrap());
+                let mut proofs = rerandomize_for_recipients(&vk, &proof, 2, prover_rng);
+                proofs.extend(rerandomize_for_recipients(&vk, &other_proof, 2, prover_rng));
+                proofs.push(proof);
+                proofs.push(other_proof);
+                for (i, p) in proofs.iter().enumerate() {
+                    assert!(verify_proof(&pvk, p, &[pub_c]).unwrap());
+                    for q in &proofs[..i] {
+                        assert!(p.a != q.a, "two proofs share A");
+                        assert!(p.b != q.b, "two proofs share B");
+                        assert!(p.c != q.c, "two proofs share C");
+                    }
+                }
+            }
+        }
     }
 }
+
+/// Prove `a b = c` for `a` and `b` from an input provider (see `super::input`), and return `c`.
+/// `AssignmentMissing` if the provider did not send `a` or `b`.
+pub fn mpc_prove_inputs<E: PairingEngine>(
+    inputs: &SharedInputs<E::Fr>,
+) -> R1CSResult<MpcVal<E::Fr>>
+where
+    MpcPairingEngine<E>: PairingEngine<
+        Fr = MpcVal<E::Fr>,
+        G1Projective = MpcCurve<E::G1Projective>,
+        G1Affine = MpcCurve<E::G1Affine>,
+        G2Affine = MpcCurve2<E::G2Affine>,
+        Fqk = MpcMulVal<E::Fqk>,
+    >,
+    MpcCurve<E::G1Projective>: BatchScalarMul,
+    MpcCurve<E::G1Affine>: MsmCurve,
+    MpcCurve2<E::G2Affine>: MsmCurve,
+    MpcVal<E::Fr>: BatchProd + BatchFft,
+{
+    let rng = &mut test_rng();
+    let params =
+        generate_random_parameters::<E, _, _>(MySillyCircuit { a: None, b: None }, rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&params.vk);
+    let mpc_params = pk_to_mpc(params);
+
+    let circuit = MySillyCircuit {
+        a: inputs.get("a"),
+        b: inputs.get("b"),
+    };
+    let c = channel::field_mul(inputs.assignment("a")?, inputs.assignment("b")?).publicize_unwrap();
+    let proof = create_random_proof_for::<E, _>(circuit, &mpc_params, None)?.unwrap();
+    assert!(verify_proof(&pvk, &proof, &[c])?);
+    Ok(MpcVal::from_public(c))
+}
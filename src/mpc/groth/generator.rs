@@ -0,0 +1,171 @@
+use super::r1cs_to_qap::R1CStoQAP;
+use crate::mpc::{channel, fixed_base_mul_shares, MpcVal};
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::UniformRand;
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, Result as R1CSResult,
+    SynthesisError, SynthesisMode,
+};
+use ark_std::rand::Rng;
+use ark_std::{end_timer, start_timer, vec::Vec};
+
+// Changelog:
+// 1. The toxic waste (alpha, beta, gamma, delta and the evaluation point t) is shared: each party
+//    samples its own share, so no party knows it.
+// 2. The QAP is evaluated at t with shared arithmetic (see `R1CStoQAP`); powers of t take
+//    `log n` rounds, and the products with alpha, beta and the inverses two more.
+// 3. Generators are the fixed (public) subgroup generators, not sampled.
+// 4. Each party multiplies the generators by its shares, and all points are opened in one round.
+//    The result is an ordinary, public `ProvingKey<E>`.
+
+/// Generates a random common reference string for a circuit, with shared toxic waste.
+///
+/// `rng` must be this party's own (see `crate::mpc::party_rng`): parties sampling with the same
+/// rng (e.g. `test_rng`) hold equal shares, so each knows the toxic waste.
+#[inline]
+pub fn generate_random_parameters<E, C, R>(circuit: C, rng: &mut R) -> R1CSResult<ProvingKey<E>>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+    R: Rng,
+{
+    let alpha = MpcVal::<E::Fr>::rand(rng);
+    let beta = MpcVal::<E::Fr>::rand(rng);
+    let gamma = MpcVal::<E::Fr>::rand(rng);
+    let delta = MpcVal::<E::Fr>::rand(rng);
+    let t = MpcVal::<E::Fr>::rand(rng);
+
+    generate_parameters::<E, C>(circuit, alpha, beta, gamma, delta, t)
+}
+
+/// Create parameters for a circuit, given some shared toxic waste.
+pub fn generate_parameters<E, C>(
+    circuit: C,
+    alpha: MpcVal<E::Fr>,
+    beta: MpcVal<E::Fr>,
+    gamma: MpcVal<E::Fr>,
+    delta: MpcVal<E::Fr>,
+    t: MpcVal<E::Fr>,
+) -> R1CSResult<ProvingKey<E>>
+where
+    E: PairingEngine,
+    C: ConstraintSynthesizer<E::Fr>,
+{
+    type D<F> = GeneralEvaluationDomain<F>;
+
+    let setup_time = start_timer!(|| "Groth16::Generator");
+    let cs = ConstraintSystem::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    cs.set_mode(SynthesisMode::Setup);
+
+    // Synthesize the circuit.
+    let synthesis_time = start_timer!(|| "Constraint synthesis");
+    circuit.generate_constraints(cs.clone())?;
+    end_timer!(synthesis_time);
+
+    let lc_time = start_timer!(|| "Inlining LCs");
+    cs.finalize();
+    end_timer!(lc_time);
+
+    ///////////////////////////////////////////////////////////////////////////
+    let domain_time = start_timer!(|| "Constructing evaluation domain");
+
+    let domain_size = cs.num_constraints() + cs.num_instance_variables();
+    let domain = D::<E::Fr>::new(domain_size).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let t_powers = channel::field_batch_powers(t, domain.size());
+
+    end_timer!(domain_time);
+    ///////////////////////////////////////////////////////////////////////////
+
+    let reduction_time = start_timer!(|| "R1CS to QAP Instance Map with Evaluation");
+    let num_instance_variables = cs.num_instance_variables();
+    let (a, b, c, zt, qap_num_variables, m_raw) =
+        R1CStoQAP::instance_map_with_evaluation::<E::Fr, D<E::Fr>>(cs, &t_powers)?;
+    end_timer!(reduction_time);
+
+    let inverses = channel::field_batch_inv(vec![gamma, delta]);
+    let gamma_inverse = inverses[0].ok_or(SynthesisError::UnexpectedIdentity)?;
+    let delta_inverse = inverses[1].ok_or(SynthesisError::UnexpectedIdentity)?;
+
+    // beta * a + alpha * b + c, and zt / delta
+    let n_vars = qap_num_variables + 1;
+    let mut products = channel::field_batch_mul(
+        [vec![beta; n_vars], vec![alpha; n_vars], vec![zt]].concat(),
+        [a.clone(), b.clone(), vec![delta_inverse]].concat(),
+    );
+    let zt_delta_inverse = products.pop().unwrap();
+    let abc: Vec<_> = products[..n_vars]
+        .iter()
+        .zip(&products[n_vars..])
+        .zip(&c)
+        .map(|((beta_a, alpha_b), c)| *beta_a + *alpha_b + *c)
+        .collect();
+    drop(c);
+
+    // gamma_abc, l and h (but for its first element, zt / delta)
+    let num_l = n_vars - num_instance_variables;
+    let mut products = channel::field_batch_mul(
+        [abc, t_powers[1..m_raw - 1].to_vec()].concat(),
+        [
+            vec![gamma_inverse; num_instance_variables],
+            vec![delta_inverse; num_l],
+            vec![zt_delta_inverse; m_raw - 2],
+        ]
+        .concat(),
+    );
+    let mut h = products.split_off(n_vars);
+    h.insert(0, zt_delta_inverse);
+    let l = products.split_off(num_instance_variables);
+    let gamma_abc = products;
+
+    let g1_generator = E::G1Projective::prime_subgroup_generator();
+    let g2_generator = E::G2Projective::prime_subgroup_generator();
+
+    // Generate the R1CS proving key
+    let proving_key_time = start_timer!(|| "Generate the R1CS proving key");
+    let g1_scalars = [vec![alpha, beta, delta], a, b.clone(), h, l, gamma_abc].concat();
+    let g2_scalars = [vec![beta, delta, gamma], b].concat();
+    let (g1s, g2s) = channel::curve_batch_publicize2(
+        fixed_base_mul_shares(g1_generator, &g1_scalars),
+        fixed_base_mul_shares(g2_generator, &g2_scalars),
+    );
+    let g1s: Vec<_> = g1s.into_iter().map(|p| p.val).collect();
+    let g2s: Vec<_> = g2s.into_iter().map(|p| p.val).collect();
+    end_timer!(proving_key_time);
+
+    let batch_normalization_time = start_timer!(|| "Convert proving key elements to affine");
+    let g1s = E::G1Projective::batch_normalization_into_affine(&g1s);
+    let g2s = E::G2Projective::batch_normalization_into_affine(&g2s);
+    let (alpha_g1, beta_g1, delta_g1) = (g1s[0], g1s[1], g1s[2]);
+    let mut g1s = g1s[3..].iter().cloned();
+    let a_query: Vec<_> = g1s.by_ref().take(n_vars).collect();
+    let b_g1_query: Vec<_> = g1s.by_ref().take(n_vars).collect();
+    let h_query: Vec<_> = g1s.by_ref().take(m_raw - 1).collect();
+    let l_query: Vec<_> = g1s.by_ref().take(num_l).collect();
+    let gamma_abc_g1: Vec<_> = g1s.collect();
+    let (beta_g2, delta_g2, gamma_g2) = (g2s[0], g2s[1], g2s[2]);
+    let b_g2_query = g2s[3..].to_vec();
+    end_timer!(batch_normalization_time);
+    end_timer!(setup_time);
+
+    let vk = VerifyingKey::<E> {
+        alpha_g1,
+        beta_g2,
+        gamma_g2,
+        delta_g2,
+        gamma_abc_g1,
+    };
+
+    Ok(ProvingKey {
+        vk,
+        beta_g1,
+        delta_g1,
+        a_query,
+        b_g1_query,
+        b_g2_query,
+        h_query,
+        l_query,
+    })
+}
@@ -1,7 +1,8 @@
 #![allow(dead_code)]
-use crate::mpc::{MsmCurve, BatchProd};
+use crate::mpc::domain::BatchFft;
+use crate::mpc::{BatchProd, BatchScalarMul, MsmCurve};
 use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_ff::{Field, UniformRand, Zero};
 use super::r1cs_to_qap::R1CStoQAP;
 use ark_groth16::{Proof, ProvingKey, VerifyingKey};
 use ark_poly::GeneralEvaluationDomain;
@@ -13,10 +14,18 @@ use ark_std::{end_timer, start_timer, vec::Vec};
 use log::debug;
 
 // Changelog:
-// 1. Specialized to Bls12_377 (our MPC lifting machinery cannot be written fully generically b/c
-//    of Rust type system/ ark design limitations).
+// 1. Run over the lifted engines, `MpcPairingEngine<E>` (our MPC lifting machinery cannot be written
+//    fully generically b/c of Rust type system/ ark design limitations, so it is stamped out per
+//    curve in `mpc/mod.rs`).
 // 2. Lift to MsmCurve.
-// 3. Remove zero-check for prover randomness r.
+// 3. Remove zero-check for prover randomness r. Over MPC types, use
+//    `super::create_shared_random_proof`, which checks r and s without opening them.
+// 4. Batch the (shared) scalar multiplications for C into one round.
+// 5. Skip the debug check that the constraints are satisfied over MPC types (`BatchProd::SHARED`):
+//    it compares shares, so it fails for witnesses that were shared rather than computed in MPC
+//    (e.g. circom witnesses).
+// 6. Multiply by r as a scalar, not as words: the words of a shared scalar's `BigInt` are not
+//    available.
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -33,7 +42,8 @@ where
     E: PairingEngine,
     E::G1Affine: MsmCurve,
     E::G2Affine: MsmCurve,
-    E::Fr: BatchProd,
+    E::Fr: BatchProd + BatchFft,
+    E::G1Projective: BatchScalarMul,
     C: ConstraintSynthesizer<<E as PairingEngine>::Fr>,
     R: Rng,
 {
@@ -44,13 +54,17 @@ where
 }
 
 /// Create a Groth16 proof that is *not* zero-knowledge.
+///
+/// Over MPC types, `r` and `s` are public zeros, so the proof is a deterministic function of the
+/// witness.
 #[inline]
 pub fn create_proof_no_zk<E, C>(circuit: C, pk: &ProvingKey<E>) -> R1CSResult<Proof<E>>
 where
     E: PairingEngine,
     E::G1Affine: MsmCurve,
     E::G2Affine: MsmCurve,
-    E::Fr: BatchProd,
+    E::Fr: BatchProd + BatchFft,
+    E::G1Projective: BatchScalarMul,
     C: ConstraintSynthesizer<<E as PairingEngine>::Fr>,
 {
     create_proof::<E, C>(
@@ -73,7 +87,8 @@ where
     E: PairingEngine,
     E::G1Affine: MsmCurve,
     E::G2Affine: MsmCurve,
-    E::Fr: BatchProd,
+    E::Fr: BatchProd + BatchFft,
+    E::G1Projective: BatchScalarMul,
     C: ConstraintSynthesizer<<E as PairingEngine>::Fr>,
 {
     debug!("r: {}", r);
@@ -89,13 +104,16 @@ where
     // Synthesize the circuit.
     let synthesis_time = start_timer!(|| "Constraint synthesis");
     circuit.generate_constraints(cs.clone())?;
-    debug_assert!(cs.is_satisfied().unwrap());
     end_timer!(synthesis_time);
 
     let lc_time = start_timer!(|| "Inlining LCs");
     cs.finalize();
     end_timer!(lc_time);
 
+    if !<E::Fr as BatchProd>::SHARED {
+        debug_assert!(cs.is_satisfied().unwrap());
+    }
+
     let witness_map_time = start_timer!(|| "R1CS to QAP witness map");
     let h = R1CStoQAP::witness_map::<<E as PairingEngine>::Fr, D<<E as PairingEngine>::Fr>>(
         cs.clone(),
@@ -109,13 +127,6 @@ where
     let prover = cs.borrow().unwrap();
     let l_aux_acc = <<E as PairingEngine>::G1Affine as MsmCurve>::multi_scalar_mul(&pk.l_query, &prover.witness_assignment);
 
-    let r_s_delta_g1 = pk
-        .delta_g1
-        .into_projective()
-        .mul(r.into_repr())
-        .mul(s.into_repr());
-    debug!("r_s_delta_g1: {}", r_s_delta_g1);
-
     end_timer!(c_acc_time);
 
     let assignment: Vec<<E as PairingEngine>::Fr> = prover.instance_assignment[1..].iter().chain(prover.witness_assignment.iter()).cloned().collect();
@@ -129,8 +140,6 @@ where
 
     let g_a = calculate_coeff(r_g1, &pk.a_query, pk.vk.alpha_g1, &assignment);
 
-    let s_g_a = g_a.mul(s.into_repr());
-    debug!("s_g_a: {}", s_g_a);
     end_timer!(a_acc_time);
 
     // Compute B in G1 if needed
@@ -150,12 +159,21 @@ where
     let b_g2_acc_time = start_timer!(|| "Compute B in G2");
     let s_g2 = pk.vk.delta_g2.mul(s);
     let g2_b = calculate_coeff(s_g2, &pk.b_g2_query, pk.vk.beta_g2, &assignment);
-    let r_g1_b = g1_b.mul(r.into_repr());
-    debug!("r_g1_b: {}", r_g1_b);
     drop(assignment);
 
     end_timer!(b_g2_acc_time);
 
+    let batch_time = start_timer!(|| "Batch scalar multiplications");
+    let products = <E::G1Projective as BatchScalarMul>::batch_scalar_mul(
+        vec![g_a, g1_b, r_g1],
+        vec![s, r, s],
+    );
+    let (s_g_a, r_g1_b, r_s_delta_g1) = (products[0], products[1], products[2]);
+    debug!("s_g_a: {}", s_g_a);
+    debug!("r_g1_b: {}", r_g1_b);
+    debug!("r_s_delta_g1: {}", r_s_delta_g1);
+    end_timer!(batch_time);
+
     let c_time = start_timer!(|| "Finish C");
     let mut g_c = s_g_a;
     g_c += &r_g1_b;
@@ -1,7 +1,8 @@
 use ark_ff::{One, PrimeField, Zero};
 use ark_poly::EvaluationDomain;
-use ark_std::{cfg_iter, cfg_iter_mut, vec};
-use crate::mpc::BatchProd;
+use ark_std::{cfg_iter, cfg_iter_mut, end_timer, start_timer, vec};
+use crate::mpc::domain::BatchFft;
+use crate::mpc::{channel, BatchProd, MpcVal};
 
 use ark_relations::r1cs::{ConstraintSystemRef, Result as R1CSResult, SynthesisError};
 use core::ops::{AddAssign, Deref};
@@ -43,9 +44,80 @@ where
 
 pub struct R1CStoQAP;
 
+/// The QAP's shared `a`, `b` and `c` evaluations, `Z_H(t)`, the number of variables and the
+/// domain size
+type SharedInstanceMap<F> = (Vec<MpcVal<F>>, Vec<MpcVal<F>>, Vec<MpcVal<F>>, MpcVal<F>, usize, usize);
+
 impl R1CStoQAP {
+    /// Evaluate the QAP at a shared point, given its shared powers `t^0..=t^n` (`n` the domain
+    /// size).
+    ///
+    /// Changed from ark: the Lagrange coefficients are `Z_H(t) w^i / (n (t - w^i))`, with one
+    /// batched inversion and one batched multiplication.
     #[inline]
-    pub fn witness_map<F: PrimeField + BatchProd, D: EvaluationDomain<F>>(
+    pub fn instance_map_with_evaluation<F: PrimeField, D: EvaluationDomain<F>>(
+        cs: ConstraintSystemRef<F>,
+        t_powers: &[MpcVal<F>],
+    ) -> R1CSResult<SharedInstanceMap<F>> {
+        let matrices = cs.to_matrices().unwrap();
+        let domain_size = cs.num_constraints() + cs.num_instance_variables();
+        let domain = D::new(domain_size).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let domain_size = domain.size();
+        assert!(t_powers.len() > domain_size);
+        let t = t_powers[1];
+
+        let zt = t_powers[domain_size] - MpcVal::from_public(F::one());
+
+        // Evaluate all Lagrange polynomials
+        let coefficients_time = start_timer!(|| "Evaluate Lagrange coefficients");
+        let size_inv = domain.size_as_field_element().inverse().unwrap();
+        let t_minus_w = domain.elements().map(|w| t - MpcVal::from_public(w)).collect();
+        let t_minus_w_inv = channel::field_batch_inv(t_minus_w)
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or(SynthesisError::UnexpectedIdentity)?;
+        let u: Vec<MpcVal<F>> = channel::field_batch_mul(vec![zt; domain_size], t_minus_w_inv)
+            .into_iter()
+            .zip(domain.elements())
+            .map(|(u, w)| u * MpcVal::from_public(w * size_inv))
+            .collect();
+        end_timer!(coefficients_time);
+
+        let qap_num_variables = (cs.num_instance_variables() - 1) + cs.num_witness_variables();
+
+        let zero = MpcVal::from_shared(F::zero());
+        let mut a = vec![zero; qap_num_variables + 1];
+        let mut b = vec![zero; qap_num_variables + 1];
+        let mut c = vec![zero; qap_num_variables + 1];
+
+        {
+            let start = 0;
+            let end = cs.num_instance_variables();
+            let num_constraints = cs.num_constraints();
+            a[start..end].copy_from_slice(&u[(start + num_constraints)..(end + num_constraints)]);
+        }
+
+        for (i, u_i) in u.iter().enumerate().take(cs.num_constraints()) {
+            for &(ref coeff, index) in &matrices.a[i] {
+                a[index] += &(*u_i * MpcVal::from_public(*coeff));
+            }
+            for &(ref coeff, index) in &matrices.b[i] {
+                b[index] += &(*u_i * MpcVal::from_public(*coeff));
+            }
+            for &(ref coeff, index) in &matrices.c[i] {
+                c[index] += &(*u_i * MpcVal::from_public(*coeff));
+            }
+        }
+
+        Ok((a, b, c, zt, qap_num_variables, domain_size))
+    }
+
+    /// Compute the coefficients of `h`.
+    ///
+    /// Changed from ark: `a`, `b` and `c` are transformed as one batch, which over MPC types
+    /// transforms their shares (see `mpc::domain`).
+    #[inline]
+    pub fn witness_map<F: PrimeField + BatchProd + BatchFft, D: EvaluationDomain<F>>(
         prover: ConstraintSystemRef<F>,
     ) -> R1CSResult<Vec<F>> {
         let matrices = prover.to_matrices().unwrap();
@@ -83,13 +155,6 @@ impl R1CStoQAP {
             a[start..end].clone_from_slice(&full_assignment[..num_inputs]);
         }
 
-        domain.ifft_in_place(&mut a);
-        domain.ifft_in_place(&mut b);
-
-        domain.coset_fft_in_place(&mut a);
-        domain.coset_fft_in_place(&mut b);
-        let mut ab = F::batch_product(a, b);
-
         let mut c = vec![zero; domain_size];
         cfg_iter_mut!(c[..prover.num_constraints])
             .enumerate()
@@ -97,15 +162,20 @@ impl R1CStoQAP {
                 *c = evaluate_constraint(&matrices.c[i], &full_assignment);
             });
 
-        domain.ifft_in_place(&mut c);
-        domain.coset_fft_in_place(&mut c);
+        let mut abc = [a, b, c];
+        F::batch_ifft(&domain, &mut abc)?;
+        F::batch_coset_fft(&domain, &mut abc)?;
+        let [a, b, c] = abc;
+        let mut ab = F::batch_product(a, b);
 
         cfg_iter_mut!(ab)
             .zip(c)
             .for_each(|(ab_i, c_i)| *ab_i -= &c_i);
 
-        domain.divide_by_vanishing_poly_on_coset_in_place(&mut ab);
-        domain.coset_ifft_in_place(&mut ab);
+        let mut ab = [ab];
+        F::batch_divide_by_vanishing_poly_on_coset(&domain, &mut ab)?;
+        F::batch_coset_ifft(&domain, &mut ab)?;
+        let [ab] = ab;
 
         Ok(ab)
     }
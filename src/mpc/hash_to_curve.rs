@@ -0,0 +1,304 @@
+//! Hashing shared field elements to curve points.
+//!
+//! We use the Shallue-van de Woestijne map (RFC 9380, section 6.6.1), which works for any short
+//! Weierstrass curve. Simplified SWU would need `A != 0`, which curves like BLS12-377 only get
+//! through an isogeny.
+//!
+//! The map is a straight-line program of multiplications, an inversion, square tests, a square
+//! root and conditional moves. We run it on shares, batched across inputs. Its output has shared
+//! coordinates, so we then turn it into a shared point: we add a random point that we know both
+//! ways, open the sum, and subtract.
+use ark_ec::models::SWModelParameters;
+use ark_ec::short_weierstrass_jacobian::{GroupAffine, GroupProjective};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, Field, One, PrimeField, SquareRootField, Zero};
+
+use super::*;
+
+/// The constants of the map: `(Z, c1, c2, c3, c4)`, following the RFC.
+fn svdw_constants<P: SWModelParameters>() -> [P::BaseField; 5]
+where
+    P::BaseField: PrimeField,
+{
+    let g = |x: P::BaseField| x.square() * x + P::COEFF_A * x + P::COEFF_B;
+    let three = P::BaseField::from(3u64);
+    let four = P::BaseField::from(4u64);
+    let z = (1u64..)
+        .flat_map(|i| vec![P::BaseField::from(i), -P::BaseField::from(i)])
+        .find(|&z| {
+            let gz = g(z);
+            let t = three * z.square() + four * P::COEFF_A;
+            if gz.is_zero() || t.is_zero() {
+                return false;
+            }
+            let h = -t / (four * gz);
+            h.legendre().is_qr()
+                && (gz.legendre().is_qr() || g(-z / P::BaseField::from(2u64)).legendre().is_qr())
+        })
+        .unwrap();
+    let gz = g(z);
+    let t = three * z.square() + four * P::COEFF_A;
+    let mut c3 = (-gz * t).sqrt().unwrap();
+    if sgn0(&c3) {
+        c3 = -c3;
+    }
+    [z, gz, -z / P::BaseField::from(2u64), c3, -four * gz / t]
+}
+
+fn sgn0<F: PrimeField>(x: &F) -> bool {
+    x.into_repr().is_odd()
+}
+
+/// Map each public `u` to a point on the curve, before clearing the cofactor.
+fn svdw<P: SWModelParameters>(us: &[P::BaseField]) -> Vec<GroupAffine<P>>
+where
+    P::BaseField: PrimeField,
+{
+    let [z, c1, c2, c3, c4] = svdw_constants::<P>();
+    let g = |x: P::BaseField| x.square() * x + P::COEFF_A * x + P::COEFF_B;
+    us.iter()
+        .map(|u| {
+            let tv1 = u.square() * c1;
+            let tv2 = P::BaseField::one() + tv1;
+            let tv1 = P::BaseField::one() - tv1;
+            let tv3 = (tv1 * tv2).inverse().unwrap_or_else(P::BaseField::zero);
+            let tv4 = *u * tv1 * tv3 * c3;
+            let x1 = c2 - tv4;
+            let x2 = c2 + tv4;
+            let x3 = (tv2.square() * tv3).square() * c4 + z;
+            let x = if g(x1).legendre().is_qr() {
+                x1
+            } else if g(x2).legendre().is_qr() {
+                x2
+            } else {
+                x3
+            };
+            let mut y = g(x).sqrt().unwrap();
+            if sgn0(u) != sgn0(&y) {
+                y = -y;
+            }
+            GroupAffine::new(x, y, false)
+        })
+        .collect()
+}
+
+type Factors<'a, F> = (&'a [MpcVal<F>], &'a [MpcVal<F>]);
+
+/// Several batches of elementwise products, in one round.
+fn mul_many<F: Field>(pairs: &[Factors<F>]) -> Vec<Vec<MpcVal<F>>> {
+    let a = pairs.iter().flat_map(|(a, _)| a.iter().cloned()).collect();
+    let b = pairs.iter().flat_map(|(_, b)| b.iter().cloned()).collect();
+    let mut c = channel::field_batch_mul(a, b).into_iter();
+    pairs
+        .iter()
+        .map(|(a, _)| c.by_ref().take(a.len()).collect())
+        .collect()
+}
+
+/// A public constant, as a share.
+fn shared_const<F: Field>(c: F) -> MpcVal<F> {
+    MpcVal::from_shared(if channel::am_first() { c } else { F::zero() })
+}
+
+/// Whether each shared `x` is a square (zero counts), as a shared 0/1 value.
+///
+/// With a random `r`, a known non-residue `n`, and a random bit `b`, we open
+/// `t = x * r^2 * n^b`. Its Legendre symbol is that of `x`, flipped if `b` is set.
+pub fn is_square<F: PrimeField + SquareRootField>(xs: &[MpcVal<F>]) -> Vec<MpcVal<F>> {
+    if xs.is_empty() {
+        return Vec::new();
+    }
+    let n = (2u64..)
+        .map(F::from)
+        .find(|n| n.legendre().is_qnr())
+        .unwrap();
+    let (_, bs) = channel::field_dabits::<F>(xs.len());
+    let rs = channel::field_rands::<F>(xs.len());
+    let nbs: Vec<MpcVal<F>> = bs
+        .iter()
+        .map(|b| MpcVal::one() + *b * MpcVal::from_public(n - F::one()))
+        .collect();
+    let prods = mul_many(&[(xs, &nbs), (&rs, &rs)]);
+    let ts = channel::field_batch_publicize(channel::field_batch_mul(
+        prods[0].clone(),
+        prods[1].clone(),
+    ));
+    ts.into_iter()
+        .zip(bs)
+        .map(|(t, b)| match t.val.legendre() {
+            LegendreSymbol::Zero => shared_const(F::one()),
+            LegendreSymbol::QuadraticResidue => MpcVal::one() - b,
+            LegendreSymbol::QuadraticNonResidue => b,
+        })
+        .collect()
+}
+
+/// Square roots of shared `x`s, or `None` for non-squares.
+///
+/// We open `x * r^2` for a random `r` and divide its root by `r`. This reveals whether each `x`
+/// is a square, but not which root we get.
+pub fn sqrt<F: PrimeField + SquareRootField>(xs: &[MpcVal<F>]) -> Vec<Option<MpcVal<F>>> {
+    if xs.is_empty() {
+        return Vec::new();
+    }
+    let rs = channel::field_rands::<F>(xs.len());
+    let rrs = channel::field_batch_mul(rs.clone(), rs.clone());
+    let ts = channel::field_batch_publicize(channel::field_batch_mul(xs.to_vec(), rrs));
+    let r_invs = channel::field_batch_inv(rs);
+    ts.into_iter()
+        .zip(r_invs)
+        .map(|(t, r_inv)| Some(r_inv.expect("zero mask") * MpcVal::from_public(t.val.sqrt()?)))
+        .collect()
+}
+
+/// Map each `u` into the prime-order subgroup.
+///
+/// This is the RFC's `encode_to_curve` without the hash to the field: it is not a random
+/// oracle, see [hash_to_curve] for that. Public inputs are mapped locally, shared inputs all
+/// need to be shared.
+pub fn map_to_curve<P: SWModelParameters>(
+    us: &[MpcVal<P::BaseField>],
+) -> Vec<MpcCurve<GroupProjective<P>>>
+where
+    P::BaseField: PrimeField + SquareRootField,
+{
+    if us.iter().all(|u| !u.shared) {
+        let us: Vec<P::BaseField> = us.iter().map(|u| u.val).collect();
+        return svdw::<P>(&us)
+            .into_iter()
+            .map(|p| MpcCurve::from_public(p.mul_by_cofactor_to_projective()))
+            .collect();
+    }
+    assert!(us.iter().all(|u| u.shared));
+    let n = us.len();
+    let [z, c1, c2, c3, c4] = svdw_constants::<P>().map(MpcVal::from_public);
+    let a = MpcVal::from_public(P::COEFF_A);
+    let b = MpcVal::from_public(P::COEFF_B);
+    let one = MpcVal::<P::BaseField>::one();
+
+    let uus = channel::field_batch_mul(us.to_vec(), us.to_vec());
+    let tv1: Vec<_> = uus.iter().map(|uu| one - *uu * c1).collect();
+    let tv2: Vec<_> = uus.iter().map(|uu| one + *uu * c1).collect();
+    let prods = mul_many(&[(&tv1, &tv2), (us, &tv1)]);
+    let tv3: Vec<_> = channel::field_batch_inv(prods[0].clone())
+        .into_iter()
+        .map(|i| i.unwrap_or_else(|| shared_const(P::BaseField::zero())))
+        .collect();
+    let prods = mul_many(&[(&prods[1], &tv3), (&tv2, &tv2)]);
+    let tv4: Vec<_> = prods[0].iter().map(|t| *t * c3).collect();
+    let x1: Vec<_> = tv4.iter().map(|t| c2 - *t).collect();
+    let x2: Vec<_> = tv4.iter().map(|t| c2 + *t).collect();
+    let prods = mul_many(&[(&x1, &x1), (&x2, &x2), (&prods[1], &tv3)]);
+    let x1a: Vec<_> = prods[0].iter().map(|t| *t + a).collect();
+    let x2a: Vec<_> = prods[1].iter().map(|t| *t + a).collect();
+    let prods = mul_many(&[(&x1a, &x1), (&x2a, &x2), (&prods[2], &prods[2])]);
+    let gxs: Vec<_> = prods[0].iter().chain(&prods[1]).map(|t| *t + b).collect();
+    let x3: Vec<_> = prods[2].iter().map(|t| *t * c4 + z).collect();
+
+    // x = x1 if g(x1) is square, else x2 if g(x2) is, else x3
+    let squares = is_square(&gxs);
+    let (e1, sq2) = squares.split_at(n);
+    let not_e1: Vec<_> = e1.iter().map(|e| one - *e).collect();
+    let x1_x3: Vec<_> = x1.iter().zip(&x3).map(|(x1, x3)| *x1 - *x3).collect();
+    let prods = mul_many(&[(sq2, &not_e1), (e1, &x1_x3)]);
+    let x: Vec<_> = x3.iter().zip(&prods[1]).map(|(x3, t)| *x3 + *t).collect();
+    let x2_x: Vec<_> = x2.iter().zip(&x).map(|(x2, x)| *x2 - *x).collect();
+    let x: Vec<_> = channel::field_batch_mul(prods[0].clone(), x2_x)
+        .into_iter()
+        .zip(&x)
+        .map(|(t, x)| *x + t)
+        .collect();
+    let xxs = channel::field_batch_mul(x.clone(), x.clone());
+    let xxa: Vec<_> = xxs.iter().map(|t| *t + a).collect();
+    let gx: Vec<_> = channel::field_batch_mul(xxa, x.clone())
+        .into_iter()
+        .map(|t| t + b)
+        .collect();
+    let y: Vec<_> = sqrt(&gx)
+        .into_iter()
+        .map(|y| y.expect("SvdW: g(x) is not square"))
+        .collect();
+
+    // fix the sign of y: sgn0(y) = sgn0(u)
+    let mut inputs = us.to_vec();
+    inputs.extend_from_slice(&y);
+    let bits = boolean::field_to_bits(&inputs);
+    let same_sign: Vec<MpcBool> = (0..n).map(|i| !(bits[i][0] ^ bits[n + i][0])).collect();
+    let same_sign = boolean::bits_to_field::<P::BaseField>(&same_sign);
+    let two_y: Vec<_> = y.iter().map(|y| *y + *y).collect();
+    let y: Vec<_> = channel::field_batch_mul(same_sign, two_y)
+        .into_iter()
+        .zip(&y)
+        .map(|(t, y)| t - *y)
+        .collect();
+
+    // open (x, y) + R, then subtract R's shares
+    let (r_coords, r_points) = channel::curve_coord_rands::<P>(n);
+    let dxs: Vec<_> = r_coords
+        .iter()
+        .zip(&x)
+        .map(|((rx, _), x)| *rx - *x)
+        .collect();
+    let dys: Vec<_> = r_coords
+        .iter()
+        .zip(&y)
+        .map(|((_, ry), y)| *ry - *y)
+        .collect();
+    let dx_invs: Vec<_> = channel::field_batch_inv(dxs)
+        .into_iter()
+        .map(|i| i.expect("SvdW: hit the random point"))
+        .collect();
+    let lambdas = channel::field_batch_mul(dys, dx_invs);
+    let lambda_sqs = channel::field_batch_mul(lambdas.clone(), lambdas.clone());
+    let sum_xs: Vec<_> = lambda_sqs
+        .iter()
+        .zip(&x)
+        .zip(&r_coords)
+        .map(|((l, x), (rx, _))| *l - *x - *rx)
+        .collect();
+    let x_diffs: Vec<_> = x.iter().zip(&sum_xs).map(|(x, sx)| *x - *sx).collect();
+    let sum_ys: Vec<_> = channel::field_batch_mul(lambdas, x_diffs)
+        .into_iter()
+        .zip(&y)
+        .map(|(t, y)| t - *y)
+        .collect();
+    let mut opened = sum_xs;
+    opened.extend(sum_ys);
+    let opened = channel::field_batch_publicize(opened);
+    r_points
+        .into_iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let sum = GroupAffine::<P>::new(opened[i].val, opened[n + i].val, false);
+            let share = if channel::am_first() {
+                sum.into_projective() - r.val
+            } else {
+                -r.val
+            };
+            // cofactor clearing is linear
+            MpcCurve::from_shared(share.into_affine().mul_by_cofactor_to_projective())
+        })
+        .collect()
+}
+
+/// Hash pairs of field elements into the prime-order subgroup.
+///
+/// This is the RFC's random-oracle construction `map(u0) + map(u1)`, minus the hash to the
+/// field.
+pub fn hash_to_curve<P: SWModelParameters>(
+    u0s: &[MpcVal<P::BaseField>],
+    u1s: &[MpcVal<P::BaseField>],
+) -> Vec<MpcCurve<GroupProjective<P>>>
+where
+    P::BaseField: PrimeField + SquareRootField,
+{
+    assert_eq!(u0s.len(), u1s.len());
+    let mut us = u0s.to_vec();
+    us.extend_from_slice(u1s);
+    let ps = map_to_curve::<P>(&us);
+    let (p0s, p1s) = ps.split_at(u0s.len());
+    p0s.iter()
+        .zip(p1s)
+        .map(|(p0, p1)| MpcCurve::new(p0.val + p1.val, p0.shared))
+        .collect()
+}
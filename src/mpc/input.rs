@@ -0,0 +1,191 @@
+//! Inputs from external data owners.
+//!
+//! An input provider splits its inputs (field elements, each for a named circuit variable) into
+//! one additive share per proving party, and sends each party its shares over TCP. It can also
+//! send Pedersen commitments to the inputs; the parties then check their shares against them, in
+//! one opening, before using them.
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand};
+use ark_relations::r1cs::SynthesisError;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use super::*;
+
+/// One party's shares of some named inputs, as sent by the input provider.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct InputShares<G: ProjectiveCurve> {
+    /// Variable names, in UTF-8
+    names: Vec<Vec<u8>>,
+    shares: Vec<G::ScalarField>,
+    /// Shares of the commitments' randomness; empty without commitments
+    blinds: Vec<G::ScalarField>,
+    /// `x G + r H` for each input `x`, with randomness `r`; the same for every party
+    commitments: Vec<G::Affine>,
+}
+
+/// Shared inputs, by the name of the circuit variable they are for.
+pub struct SharedInputs<F: Field> {
+    vals: HashMap<String, MpcVal<F>>,
+}
+
+/// The second Pedersen generator, `H`: a hash to the curve (by try-and-increment), so that no one
+/// knows its discrete log.
+fn blinding_generator<G: ProjectiveCurve>() -> G {
+    (0u64..)
+        .filter_map(|i| {
+            let digest = sha2::Sha512::new()
+                .chain(b"mpc-algebra input commitments")
+                .chain(i.to_le_bytes())
+                .finalize();
+            G::Affine::from_random_bytes(&digest)
+        })
+        .map(|p| p.mul_by_cofactor_to_projective())
+        .find(|p| !p.is_zero())
+        .unwrap()
+}
+
+/// `x G + r H`
+fn pedersen<G: ProjectiveCurve>(x: G::ScalarField, r: G::ScalarField) -> G {
+    G::prime_subgroup_generator().mul(x.into_repr()) + blinding_generator::<G>().mul(r.into_repr())
+}
+
+/// Split named inputs into shares for two parties; with `commit`, also commit to each input.
+pub fn share_inputs<G: ProjectiveCurve, R: Rng>(
+    inputs: &[(String, G::ScalarField)],
+    commit: bool,
+    rng: &mut R,
+) -> [InputShares<G>; 2] {
+    let names: Vec<Vec<u8>> = inputs.iter().map(|(n, _)| n.as_bytes().to_vec()).collect();
+    let split = |x: G::ScalarField, rng: &mut R| {
+        let s = G::ScalarField::rand(rng);
+        (s, x - s)
+    };
+    let (shares0, shares1): (Vec<_>, Vec<_>) = inputs.iter().map(|(_, x)| split(*x, rng)).unzip();
+    let (blinds0, blinds1, commitments) = if commit {
+        let blinds: Vec<G::ScalarField> = inputs.iter().map(|_| G::ScalarField::rand(rng)).collect();
+        let commitments: Vec<G> = inputs
+            .iter()
+            .zip(&blinds)
+            .map(|((_, x), r)| pedersen::<G>(*x, *r))
+            .collect();
+        let (blinds0, blinds1) = blinds.into_iter().map(|r| split(r, rng)).unzip();
+        (blinds0, blinds1, G::batch_normalization_into_affine(&commitments))
+    } else {
+        (Vec::new(), Vec::new(), Vec::new())
+    };
+    [
+        InputShares {
+            names: names.clone(),
+            shares: shares0,
+            blinds: blinds0,
+            commitments: commitments.clone(),
+        },
+        InputShares {
+            names,
+            shares: shares1,
+            blinds: blinds1,
+            commitments,
+        },
+    ]
+}
+
+/// Send a party its shares. The party should be waiting in `receive_shares`.
+pub fn send_shares<G: ProjectiveCurve, A: ToSocketAddrs>(party: A, shares: &InputShares<G>) {
+    let addrs: Vec<_> = party.to_socket_addrs().unwrap().collect();
+    let mut ms_waited = 0;
+    let mut stream = loop {
+        match TcpStream::connect(&addrs[..]) {
+            Ok(s) => break s,
+            Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                ms_waited += 100;
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                if ms_waited > 30_000 {
+                    panic!("Could not find party {:?} in 30s", addrs);
+                }
+            }
+            Err(e) => panic!("Error during send_shares: {}", e),
+        }
+    };
+    let mut bytes = Vec::new();
+    shares.serialize(&mut bytes).unwrap();
+    stream.write_all(&(bytes.len() as u64).to_le_bytes()).unwrap();
+    stream.write_all(&bytes).unwrap();
+}
+
+/// Wait for an input provider to send us our shares.
+pub fn receive_shares<G: ProjectiveCurve, A: ToSocketAddrs>(self_addr: A) -> InputShares<G> {
+    let listener = TcpListener::bind(self_addr).unwrap();
+    debug!("Waiting for the input provider");
+    let (mut stream, _addr) = listener.accept().unwrap();
+    let mut len = [0u8; 8];
+    stream.read_exact(&mut len).unwrap();
+    let len = u64::from_le_bytes(len);
+    // Read through `take`, so memory grows with the bytes actually sent rather than the length
+    let mut bytes = Vec::new();
+    stream.take(len).read_to_end(&mut bytes).unwrap();
+    assert_eq!(bytes.len() as u64, len, "The input provider sent too few bytes");
+    InputShares::deserialize(&bytes[..]).unwrap()
+}
+
+impl<G: ProjectiveCurve> InputShares<G> {
+    /// Bind our shares to their variables.
+    ///
+    /// The parties first check that they were sent the same variables and commitments, and then
+    /// (if there are commitments) that their shares open them. Panics if either check fails.
+    pub fn into_shared(self) -> SharedInputs<G::ScalarField> {
+        assert_eq!(self.names.len(), self.shares.len());
+        assert!(self.commitments.is_empty() || self.commitments.len() == self.shares.len());
+        assert_eq!(self.blinds.len(), self.commitments.len());
+
+        let mut public = Vec::new();
+        self.names.serialize(&mut public).unwrap();
+        self.commitments.serialize(&mut public).unwrap();
+        let digest = sha2::Sha256::digest(&public).to_vec();
+        assert!(
+            channel::exchange_bytes(digest.clone()) == digest,
+            "The parties were sent different inputs"
+        );
+
+        if !self.commitments.is_empty() {
+            let openings = channel::curve_batch_publicize(
+                self.shares
+                    .iter()
+                    .zip(&self.blinds)
+                    .map(|(x, r)| MpcVal::from_shared(pedersen::<G>(*x, *r)))
+                    .collect(),
+            );
+            for (opening, commitment) in openings.iter().zip(&self.commitments) {
+                assert!(
+                    opening.val == commitment.into_projective(),
+                    "Input shares do not match their commitment"
+                );
+            }
+        }
+
+        let vals = self
+            .names
+            .into_iter()
+            .map(|n| String::from_utf8(n).expect("Input names must be UTF-8"))
+            .zip(self.shares.into_iter().map(MpcVal::from_shared))
+            .collect();
+        SharedInputs { vals }
+    }
+
+    /// The commitments to the inputs, if any.
+    pub fn commitments(&self) -> &[G::Affine] {
+        &self.commitments
+    }
+}
+
+impl<F: Field> SharedInputs<F> {
+    pub fn get(&self, name: &str) -> Option<MpcVal<F>> {
+        self.vals.get(name).copied()
+    }
+
+    /// The value of variable `name`, for use in `new_witness_variable` and friends.
+    pub fn assignment(&self, name: &str) -> Result<MpcVal<F>, SynthesisError> {
+        self.get(name).ok_or(SynthesisError::AssignmentMissing)
+    }
+}
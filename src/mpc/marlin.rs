@@ -11,6 +11,8 @@ use std::marker::PhantomData;
 
 use super::*;
 
+pub mod prover;
+
 type MultiPC<E, Fr> = MarlinKZG10<E, DensePolynomial<Fr>>;
 type MarlinPair<E, Fr> = Marlin<Fr, MultiPC<E, Fr>, Blake2s>;
 
@@ -134,24 +136,33 @@ pub fn local_test_prove_and_verify<E: PairingEngine>(n_iters: usize) {
 //    }
 //}
 
-pub fn mpc_test_prove_and_verify<E: PairingEngine>(n_iters: usize) {
+pub fn mpc_test_prove_and_verify<E: PairingEngine>(n_iters: usize)
+where
+    MpcVal<E::Fr>: PrimeField + MpcWire<Base = E::Fr>,
+    MpcVal<DensePolynomial<E::Fr>>: Polynomial<MpcVal<E::Fr>, Point = MpcVal<E::Fr>>,
+{
+    type Fr<E> = <E as PairingEngine>::Fr;
     let rng = &mut test_rng();
 
-    let srs = &MarlinPair::<E, E::Fr>::universal_setup(100, 50, 100, rng).unwrap();
+    let srs = &prover::universal_setup::<E, _>(100, 50, 100, &mut party_rng()).unwrap();
+    let (index_pk, index_vk) =
+        MarlinPair::<E, Fr<E>>::index(srs, MySillyCircuit { a: None, b: None }).unwrap();
 
     for _ in 0..n_iters {
-        let a = E::Fr::rand(rng);
-        let b = E::Fr::rand(rng);
+        let a = MpcVal::<Fr<E>>::rand(rng);
+        let b = MpcVal::<Fr<E>>::rand(rng);
+        let mut c = a;
+        c *= &b;
         let circ = MySillyCircuit {
             a: Some(a),
             b: Some(b),
         };
-        let mut c = a;
-        c *= &b;
-        let inputs = vec![c];
-        let (index_pk, index_vk) = MarlinPair::<E, E::Fr>::index(srs, circ.clone()).unwrap();
-        let proof = MarlinPair::<E, E::Fr>::prove(&index_pk, circ, rng).unwrap();
-        let is_valid = MarlinPair::<E, E::Fr>::verify(&index_vk, &inputs, &proof, rng).unwrap();
+        let proof = prover::prove::<E, Blake2s, _>(&index_pk, circ).unwrap();
+        let pub_a = a.publicize_unwrap();
+        let pub_c = c.publicize_unwrap();
+        let is_valid = MarlinPair::<E, Fr<E>>::verify(&index_vk, &[pub_c], &proof, rng).unwrap();
         assert!(is_valid);
+        let is_valid = MarlinPair::<E, Fr<E>>::verify(&index_vk, &[pub_a], &proof, rng).unwrap();
+        assert!(!is_valid);
     }
 }
@@ -0,0 +1,991 @@
+#![allow(non_snake_case)]
+use super::super::poly::pc::{publicize_vals, MpcPolyCommit};
+use super::super::*;
+use ark_ff::{to_bytes, One, UniformRand, Zero};
+use ark_marlin::ahp::indexer::{Index, Matrix};
+use ark_marlin::ahp::prover::ProverMsg;
+use ark_marlin::ahp::verifier::{VerifierFirstMsg, VerifierSecondMsg};
+use ark_marlin::ahp::{Error as AHPError, EvaluationsProvider, UnnormalizedBivariateLagrangePoly};
+use ark_marlin::rng::FiatShamirRng;
+use ark_marlin::{AHPForR1CS, Error, IndexProverKey, Marlin, Proof, UniversalSRS};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{
+    EvaluationDomain, Evaluations as EvaluationsOnDomain, GeneralEvaluationDomain, Polynomial,
+    UVPolynomial,
+};
+use ark_poly_commit::marlin_pc::MarlinKZG10;
+use ark_poly_commit::{
+    BatchLCProof, Error as PCError, LCTerm, LabeledCommitment, LabeledPolynomial,
+    LinearCombination, PolynomialCommitment, QuerySet,
+};
+use ark_relations::lc;
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, OptimizationGoal, SynthesisError,
+    SynthesisMode,
+};
+use ark_std::rand::RngCore;
+use ark_std::{end_timer, start_timer};
+use blake2::Digest;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+// Changelog:
+// 1. Copied from `ark_marlin::Marlin::prove` and `ark_marlin::ahp::prover`, over `MarlinKZG10` on
+//    any engine whose scalars have MPC polynomials (see `super::super::poly`).
+// 2. The circuit is synthesized over `MpcVal<F>`. The statement is opened; the witness stays
+//    shared, and the prover's polynomials are shared (`MpcVal<DensePolynomial<F>>`).
+// 3. Public constants (the formatted input, `LCTerm::One`) are only added by the first party.
+// 4. `z_a * z_b` is computed over a domain, with one batched multiplication round.
+// 5. Domain sizes come from degree bounds, not from the (party-dependent) lengths of shares.
+// 6. The third round only depends on public data, so it is computed in the clear.
+// 7. Commit and open with `MpcPolyCommit`; the commitments (and so the Fiat-Shamir challenges)
+//    are public.
+// 8. Evaluations are opened as the linear combinations ask for them.
+// 9. The zero-knowledge randomness (blinding scalars, the mask polynomial, and the commitments'
+//    hiding randomness) is shared, from each party's own entropy; the caller passes no rng.
+
+type P<F> = DensePolynomial<F>;
+type PC<E> = MarlinKZG10<E, P<<E as PairingEngine>::Fr>>;
+type MpcPC<E> = MpcPolyCommit<<E as PairingEngine>::Fr, P<<E as PairingEngine>::Fr>, PC<E>>;
+type SharedPolynomial<F> = LabeledPolynomial<MpcVal<F>, MpcVal<P<F>>>;
+
+/// Our share of `x`; public values are held by the first party
+fn share<F: Field>(x: MpcVal<F>) -> F {
+    if x.shared || channel::am_first() {
+        x.val
+    } else {
+        F::zero()
+    }
+}
+
+/// Label our share of a polynomial
+fn shared_poly<F: PrimeField>(
+    label: &str,
+    poly: P<F>,
+    degree_bound: Option<usize>,
+    hiding_bound: Option<usize>,
+) -> SharedPolynomial<F>
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    LabeledPolynomial::new(
+        label.to_string(),
+        MpcVal::from_shared(poly),
+        degree_bound,
+        hiding_bound,
+    )
+}
+
+/// Share a public polynomial: the first party holds all of it
+fn share_public_poly<F: PrimeField>(p: &LabeledPolynomial<F, P<F>>) -> SharedPolynomial<F>
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    let poly = if channel::am_first() {
+        p.polynomial().clone()
+    } else {
+        P::<F>::zero()
+    };
+    shared_poly(p.label(), poly, p.degree_bound(), p.hiding_bound())
+}
+
+/// Multiply two shared polynomials by multiplying their evaluations over `domain`, which must
+/// be large enough for the product. One round.
+fn mul_shared<F: PrimeField>(a: &P<F>, b: &P<F>, domain: GeneralEvaluationDomain<F>) -> P<F> {
+    let evals = |p: &P<F>| {
+        p.evaluate_over_domain_by_ref(domain)
+            .evals
+            .into_iter()
+            .map(MpcVal::from_shared)
+            .collect()
+    };
+    let products = channel::field_batch_mul(evals(a), evals(b))
+        .into_iter()
+        .map(|x| x.val)
+        .collect();
+    EvaluationsOnDomain::from_vec_and_domain(products, domain).interpolate()
+}
+
+fn pad_input_for_indexer_and_prover<F: PrimeField>(cs: ConstraintSystemRef<MpcVal<F>>)
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    let formatted_input_size = cs.num_instance_variables();
+
+    let domain_x = GeneralEvaluationDomain::<F>::new(formatted_input_size);
+    assert!(domain_x.is_some());
+
+    let padded_size = domain_x.unwrap().size();
+
+    if padded_size > formatted_input_size {
+        for _ in 0..(padded_size - formatted_input_size) {
+            cs.new_input_variable(|| Ok(MpcVal::zero())).unwrap();
+        }
+    }
+}
+
+fn make_matrices_square_for_prover<F: PrimeField>(cs: ConstraintSystemRef<MpcVal<F>>)
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    let num_variables = cs.num_instance_variables() + cs.num_witness_variables();
+    let num_constraints = cs.num_constraints();
+    let matrix_padding = ((num_variables as isize) - (num_constraints as isize)).abs();
+
+    if num_variables > num_constraints {
+        // Add dummy constraints of the form 0 * 0 == 0
+        for _ in 0..matrix_padding {
+            cs.enforce_constraint(lc!(), lc!(), lc!())
+                .expect("enforce 0 * 0 == 0 failed");
+        }
+    } else {
+        // Add dummy unconstrained variables
+        for _ in 0..matrix_padding {
+            let _ = cs
+                .new_witness_variable(|| Ok(MpcVal::one()))
+                .expect("alloc failed");
+        }
+    }
+    assert_eq!(
+        cs.num_instance_variables() + cs.num_witness_variables(),
+        cs.num_constraints(),
+        "padding failed!"
+    );
+}
+
+/// State for the AHP prover.
+struct ProverState<'a, F: PrimeField>
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    /// public
+    formatted_input_assignment: Vec<F>,
+    /// our shares
+    witness_assignment: Vec<F>,
+    /// Az (shares)
+    z_a: Option<Vec<F>>,
+    /// Bz (shares)
+    z_b: Option<Vec<F>>,
+    /// query bound b
+    zk_bound: usize,
+
+    w_poly: Option<SharedPolynomial<F>>,
+    mz_polys: Option<(SharedPolynomial<F>, SharedPolynomial<F>)>,
+
+    index: &'a Index<F>,
+
+    /// the random values sent by the verifier in the first round
+    verifier_first_msg: Option<VerifierFirstMsg<F>>,
+
+    /// the blinding polynomial for the first round
+    mask_poly: Option<SharedPolynomial<F>>,
+
+    /// domain X, sized for the public input
+    domain_x: GeneralEvaluationDomain<F>,
+
+    /// domain H, sized for constraints
+    domain_h: GeneralEvaluationDomain<F>,
+
+    /// domain K, sized for matrix nonzero elements
+    domain_k: GeneralEvaluationDomain<F>,
+}
+
+impl<'a, F: PrimeField> ProverState<'a, F>
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    /// Get the public input.
+    fn public_input(&self) -> Vec<F> {
+        self.formatted_input_assignment[1..].to_vec()
+    }
+}
+
+/// Initialize the AHP prover.
+fn prover_init<F: PrimeField, C: ConstraintSynthesizer<MpcVal<F>>>(
+    index: &Index<F>,
+    c: C,
+) -> Result<ProverState<'_, F>, AHPError>
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    let init_time = start_timer!(|| "AHP::Prover::Init");
+
+    let constraint_time = start_timer!(|| "Generating constraints and witnesses");
+    let pcs = ConstraintSystem::new_ref();
+    pcs.set_optimization_goal(OptimizationGoal::Weight);
+    pcs.set_mode(SynthesisMode::Prove {
+        construct_matrices: true,
+    });
+    c.generate_constraints(pcs.clone())?;
+    end_timer!(constraint_time);
+
+    let padding_time = start_timer!(|| "Padding matrices to make them square");
+    pad_input_for_indexer_and_prover(pcs.clone());
+    pcs.finalize();
+    make_matrices_square_for_prover(pcs.clone());
+    end_timer!(padding_time);
+
+    let num_non_zero = index.index_info.num_non_zero;
+
+    let (formatted_input_assignment, witness_assignment, num_constraints) = {
+        let pcs = pcs.borrow().unwrap();
+        (
+            pcs.instance_assignment.as_slice().to_vec(),
+            pcs.witness_assignment.as_slice().to_vec(),
+            pcs.num_constraints,
+        )
+    };
+
+    // The statement is public
+    let formatted_input_assignment = publicize_vals(formatted_input_assignment);
+    let witness_assignment: Vec<F> = witness_assignment.into_iter().map(share).collect();
+
+    let num_input_variables = formatted_input_assignment.len();
+    let num_witness_variables = witness_assignment.len();
+    if index.index_info.num_constraints != num_constraints
+        || num_input_variables + num_witness_variables != index.index_info.num_variables
+    {
+        return Err(AHPError::InstanceDoesNotMatchIndex);
+    }
+
+    if !AHPForR1CS::formatted_public_input_is_admissible(&formatted_input_assignment) {
+        return Err(AHPError::InvalidPublicInputLength);
+    }
+
+    // Perform matrix multiplications
+    let input_shares: Vec<F> = formatted_input_assignment
+        .iter()
+        .map(|x| share(MpcVal::from_public(*x)))
+        .collect();
+    let inner_prod_fn = |row: &[(F, usize)]| {
+        let mut acc = F::zero();
+        for &(ref coeff, i) in row {
+            let tmp = if i < num_input_variables {
+                input_shares[i]
+            } else {
+                witness_assignment[i - num_input_variables]
+            };
+
+            acc += &(if coeff.is_one() { tmp } else { tmp * coeff });
+        }
+        acc
+    };
+
+    let eval_z_a_time = start_timer!(|| "Evaluating z_A");
+    let z_a = index.a.iter().map(|row| inner_prod_fn(row)).collect();
+    end_timer!(eval_z_a_time);
+
+    let eval_z_b_time = start_timer!(|| "Evaluating z_B");
+    let z_b = index.b.iter().map(|row| inner_prod_fn(row)).collect();
+    end_timer!(eval_z_b_time);
+
+    let zk_bound = 1; // One query is sufficient for our desired soundness
+
+    let domain_h = GeneralEvaluationDomain::new(num_constraints)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+
+    let domain_k = GeneralEvaluationDomain::new(num_non_zero)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+
+    let domain_x = GeneralEvaluationDomain::new(num_input_variables)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+
+    end_timer!(init_time);
+
+    Ok(ProverState {
+        formatted_input_assignment,
+        witness_assignment,
+        z_a: Some(z_a),
+        z_b: Some(z_b),
+        w_poly: None,
+        mz_polys: None,
+        zk_bound,
+        index,
+        verifier_first_msg: None,
+        mask_poly: None,
+        domain_h,
+        domain_k,
+        domain_x,
+    })
+}
+
+/// Output the first round oracles and the next state.
+fn prover_first_round<F: PrimeField>(
+    mut state: ProverState<'_, F>,
+) -> Result<(Vec<SharedPolynomial<F>>, ProverState<'_, F>), AHPError>
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    let round_time = start_timer!(|| "AHP::Prover::FirstRound");
+    let domain_h = state.domain_h;
+    let zk_bound = state.zk_bound;
+
+    let v_H = domain_h.vanishing_polynomial().into();
+
+    let x_time = start_timer!(|| "Computing x polynomial and evals");
+    let domain_x = state.domain_x;
+    let x_poly = EvaluationsOnDomain::from_vec_and_domain(
+        state.formatted_input_assignment.clone(),
+        domain_x,
+    )
+    .interpolate();
+    let x_evals = domain_h.fft(&x_poly);
+    end_timer!(x_time);
+
+    let ratio = domain_h.size() / domain_x.size();
+
+    // Our shares of the blinding scalars for w, z_A and z_B, and of the mask's coefficients: each
+    // party samples its own, so they're uniform if either party is honest.
+    let mask_poly_degree = 3 * domain_h.size() + 2 * zk_bound - 3;
+    let mut rands = channel::field_rands::<F>(3 + mask_poly_degree + 1)
+        .into_iter()
+        .map(|x| x.val);
+    let mut blind = || P::<F>::from_coefficients_slice(&[rands.next().unwrap()]);
+
+    let mut w_extended = state.witness_assignment.clone();
+    w_extended.extend(vec![
+        F::zero();
+        domain_h.size()
+            - domain_x.size()
+            - state.witness_assignment.len()
+    ]);
+
+    let w_poly_time = start_timer!(|| "Computing w polynomial");
+    let am_first = channel::am_first();
+    let w_poly_evals = (0..domain_h.size())
+        .map(|k| {
+            if k % ratio == 0 {
+                F::zero()
+            } else if am_first {
+                w_extended[k - (k / ratio) - 1] - x_evals[k]
+            } else {
+                w_extended[k - (k / ratio) - 1]
+            }
+        })
+        .collect();
+
+    let w_poly = &EvaluationsOnDomain::from_vec_and_domain(w_poly_evals, domain_h).interpolate()
+        + &(&blind() * &v_H);
+    let (w_poly, remainder) = w_poly.divide_by_vanishing_poly(domain_x).unwrap();
+    assert!(remainder.is_zero());
+    end_timer!(w_poly_time);
+
+    let z_a_poly_time = start_timer!(|| "Computing z_A polynomial");
+    let z_a = state.z_a.clone().unwrap();
+    let z_a_poly =
+        &EvaluationsOnDomain::from_vec_and_domain(z_a, domain_h).interpolate() + &(&blind() * &v_H);
+    end_timer!(z_a_poly_time);
+
+    let z_b_poly_time = start_timer!(|| "Computing z_B polynomial");
+    let z_b = state.z_b.clone().unwrap();
+    let z_b_poly =
+        &EvaluationsOnDomain::from_vec_and_domain(z_b, domain_h).interpolate() + &(&blind() * &v_H);
+    end_timer!(z_b_poly_time);
+
+    let mask_poly_time = start_timer!(|| "Computing mask polynomial");
+    let mut mask_poly = P::<F>::from_coefficients_vec(rands.collect());
+    let scaled_sigma_1 = (mask_poly.divide_by_vanishing_poly(domain_h).unwrap().1)[0];
+    mask_poly[0] -= &scaled_sigma_1;
+    end_timer!(mask_poly_time);
+
+    assert!(w_poly.degree() < domain_h.size() - domain_x.size() + zk_bound);
+    assert!(z_a_poly.degree() < domain_h.size() + zk_bound);
+    assert!(z_b_poly.degree() < domain_h.size() + zk_bound);
+    assert!(mask_poly.degree() <= 3 * domain_h.size() + 2 * zk_bound - 3);
+
+    let w = shared_poly("w", w_poly, None, Some(1));
+    let z_a = shared_poly("z_a", z_a_poly, None, Some(1));
+    let z_b = shared_poly("z_b", z_b_poly, None, Some(1));
+    let mask_poly = shared_poly("mask_poly", mask_poly, None, None);
+
+    let oracles = vec![w.clone(), z_a.clone(), z_b.clone(), mask_poly.clone()];
+
+    state.w_poly = Some(w);
+    state.mz_polys = Some((z_a, z_b));
+    state.mask_poly = Some(mask_poly);
+    end_timer!(round_time);
+
+    Ok((oracles, state))
+}
+
+fn calculate_t<'a, F: PrimeField>(
+    matrices: impl Iterator<Item = &'a Matrix<F>>,
+    matrix_randomizers: &[F],
+    input_domain: GeneralEvaluationDomain<F>,
+    domain_h: GeneralEvaluationDomain<F>,
+    r_alpha_x_on_h: Vec<F>,
+) -> P<F> {
+    let mut t_evals_on_h = vec![F::zero(); domain_h.size()];
+    for (matrix, eta) in matrices.zip(matrix_randomizers) {
+        for (r, row) in matrix.iter().enumerate() {
+            for (coeff, c) in row.iter() {
+                let index = domain_h.reindex_by_subdomain(input_domain, *c);
+                t_evals_on_h[index] += *eta * coeff * r_alpha_x_on_h[r];
+            }
+        }
+    }
+    EvaluationsOnDomain::from_vec_and_domain(t_evals_on_h, domain_h).interpolate()
+}
+
+/// Output the second round oracles and the next state.
+fn prover_second_round<'a, F: PrimeField>(
+    ver_message: &VerifierFirstMsg<F>,
+    mut state: ProverState<'a, F>,
+) -> (Vec<SharedPolynomial<F>>, ProverState<'a, F>)
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    let round_time = start_timer!(|| "AHP::Prover::SecondRound");
+
+    let domain_h = state.domain_h;
+    let zk_bound = state.zk_bound;
+
+    let mask_poly = state
+        .mask_poly
+        .as_ref()
+        .expect("ProverState should include mask_poly when prover_second_round is called");
+
+    let VerifierFirstMsg {
+        alpha,
+        eta_a,
+        eta_b,
+        eta_c,
+    } = *ver_message;
+
+    let summed_z_m_poly_time = start_timer!(|| "Compute z_m poly");
+    let (z_a_poly, z_b_poly) = state.mz_polys.as_ref().unwrap();
+    let (z_a_poly, z_b_poly) = (&z_a_poly.polynomial().val, &z_b_poly.polynomial().val);
+    let z_m_len = domain_h.size() + zk_bound;
+    let z_c_domain = GeneralEvaluationDomain::new(2 * z_m_len - 1)
+        .expect("field is not smooth enough to construct domain");
+    let z_c_poly = mul_shared(z_a_poly, z_b_poly, z_c_domain);
+
+    let mut summed_z_m_coeffs = z_c_poly.coeffs;
+    // Our shares may be shorter than their degree bounds; don't let the `zip` truncate.
+    summed_z_m_coeffs.resize(2 * z_m_len - 1, F::zero());
+    summed_z_m_coeffs.iter_mut().for_each(|c| *c *= &eta_c);
+    summed_z_m_coeffs
+        .iter_mut()
+        .zip(&z_a_poly.coeffs)
+        .zip(&z_b_poly.coeffs)
+        .for_each(|((c, a), b)| *c += eta_a * a + eta_b * b);
+
+    let summed_z_m = P::<F>::from_coefficients_vec(summed_z_m_coeffs);
+    end_timer!(summed_z_m_poly_time);
+
+    let r_alpha_x_evals_time = start_timer!(|| "Compute r_alpha_x evals");
+    let r_alpha_x_evals =
+        domain_h.batch_eval_unnormalized_bivariate_lagrange_poly_with_diff_inputs(alpha);
+    end_timer!(r_alpha_x_evals_time);
+
+    let r_alpha_poly_time = start_timer!(|| "Compute r_alpha_x poly");
+    let r_alpha_poly = P::<F>::from_coefficients_vec(domain_h.ifft(&r_alpha_x_evals));
+    end_timer!(r_alpha_poly_time);
+
+    let t_poly_time = start_timer!(|| "Compute t poly");
+    let t_poly = calculate_t(
+        vec![&state.index.a, &state.index.b, &state.index.c].into_iter(),
+        &[eta_a, eta_b, eta_c],
+        state.domain_x,
+        state.domain_h,
+        r_alpha_x_evals.to_vec(),
+    );
+    end_timer!(t_poly_time);
+
+    let z_poly_time = start_timer!(|| "Compute z poly");
+
+    let domain_x = state.domain_x;
+    let x_poly = EvaluationsOnDomain::from_vec_and_domain(
+        state.formatted_input_assignment.clone(),
+        domain_x,
+    )
+    .interpolate();
+    let w_poly = state.w_poly.as_ref().unwrap();
+    let mut z_poly = w_poly.polynomial().val.mul_by_vanishing_poly(domain_x);
+    if channel::am_first() {
+        z_poly
+            .coeffs
+            .iter_mut()
+            .zip(&x_poly.coeffs)
+            .for_each(|(z, x)| *z += x);
+    }
+    assert!(z_poly.degree() < domain_h.size() + zk_bound);
+
+    end_timer!(z_poly_time);
+
+    let q_1_time = start_timer!(|| "Compute q_1 poly");
+
+    let mul_domain_size = *[
+        3 * domain_h.size() + 2 * zk_bound - 2,
+        r_alpha_poly.coeffs.len() + 2 * z_m_len - 1,
+        t_poly.coeffs.len() + z_m_len,
+    ]
+    .iter()
+    .max()
+    .unwrap();
+    let mul_domain = GeneralEvaluationDomain::new(mul_domain_size)
+        .expect("field is not smooth enough to construct domain");
+    let mut r_alpha_evals = r_alpha_poly.evaluate_over_domain_by_ref(mul_domain);
+    let summed_z_m_evals = summed_z_m.evaluate_over_domain_by_ref(mul_domain);
+    let z_poly_evals = z_poly.evaluate_over_domain_by_ref(mul_domain);
+    let t_poly_m_evals = t_poly.evaluate_over_domain_by_ref(mul_domain);
+
+    // r_alpha and t are public, so these products are local
+    r_alpha_evals
+        .evals
+        .iter_mut()
+        .zip(&summed_z_m_evals.evals)
+        .zip(&z_poly_evals.evals)
+        .zip(&t_poly_m_evals.evals)
+        .for_each(|(((a, b), &c), d)| {
+            *a *= b;
+            *a -= c * d;
+        });
+    let rhs = r_alpha_evals.interpolate();
+    let q_1 = &mask_poly.polynomial().val + &rhs;
+    end_timer!(q_1_time);
+
+    let sumcheck_time = start_timer!(|| "Compute sumcheck h and g polys");
+    let (h_1, x_g_1) = q_1.divide_by_vanishing_poly(domain_h).unwrap();
+    let g_1 = P::<F>::from_coefficients_slice(&x_g_1.coeffs[1..]);
+    end_timer!(sumcheck_time);
+
+    assert!(g_1.degree() <= domain_h.size() - 2);
+    assert!(h_1.degree() <= 2 * domain_h.size() + 2 * zk_bound - 2);
+
+    let t = LabeledPolynomial::new("t".into(), t_poly, None, None);
+    let oracles = vec![
+        share_public_poly(&t),
+        shared_poly("g_1", g_1, Some(domain_h.size() - 2), Some(1)),
+        shared_poly("h_1", h_1, None, None),
+    ];
+
+    state.w_poly = None;
+    state.verifier_first_msg = Some(*ver_message);
+    end_timer!(round_time);
+
+    (oracles, state)
+}
+
+/// Output the third round oracles.
+///
+/// These only depend on the index and the verifier's challenges, so we compute them in the
+/// clear and share them.
+fn prover_third_round<F: PrimeField>(
+    ver_message: &VerifierSecondMsg<F>,
+    prover_state: ProverState<'_, F>,
+) -> Result<Vec<SharedPolynomial<F>>, AHPError>
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    let round_time = start_timer!(|| "AHP::Prover::ThirdRound");
+
+    let ProverState {
+        index,
+        verifier_first_msg,
+        domain_h,
+        domain_k,
+        ..
+    } = prover_state;
+
+    let VerifierFirstMsg {
+        eta_a,
+        eta_b,
+        eta_c,
+        alpha,
+    } = verifier_first_msg
+        .expect("ProverState should include verifier_first_msg when prover_third_round is called");
+
+    let beta = ver_message.beta;
+
+    let v_H_at_alpha = domain_h.evaluate_vanishing_polynomial(alpha);
+    let v_H_at_beta = domain_h.evaluate_vanishing_polynomial(beta);
+
+    let (a_star, b_star, c_star) = (
+        &index.a_star_arith,
+        &index.b_star_arith,
+        &index.c_star_arith,
+    );
+
+    let f_evals_time = start_timer!(|| "Computing f evals on K");
+    let mut f_vals_on_K = Vec::with_capacity(domain_k.size());
+    let mut inverses_a = Vec::with_capacity(domain_k.size());
+    let mut inverses_b = Vec::with_capacity(domain_k.size());
+    let mut inverses_c = Vec::with_capacity(domain_k.size());
+
+    for i in 0..domain_k.size() {
+        inverses_a.push((beta - a_star.evals_on_K.row[i]) * (alpha - a_star.evals_on_K.col[i]));
+        inverses_b.push((beta - b_star.evals_on_K.row[i]) * (alpha - b_star.evals_on_K.col[i]));
+        inverses_c.push((beta - c_star.evals_on_K.row[i]) * (alpha - c_star.evals_on_K.col[i]));
+    }
+    ark_ff::batch_inversion(&mut inverses_a);
+    ark_ff::batch_inversion(&mut inverses_b);
+    ark_ff::batch_inversion(&mut inverses_c);
+
+    for i in 0..domain_k.size() {
+        let t = eta_a * a_star.evals_on_K.val[i] * inverses_a[i]
+            + eta_b * b_star.evals_on_K.val[i] * inverses_b[i]
+            + eta_c * c_star.evals_on_K.val[i] * inverses_c[i];
+        let f_at_kappa = v_H_at_beta * v_H_at_alpha * t;
+        f_vals_on_K.push(f_at_kappa);
+    }
+    end_timer!(f_evals_time);
+
+    let f_poly_time = start_timer!(|| "Computing f poly");
+    let f = EvaluationsOnDomain::from_vec_and_domain(f_vals_on_K, domain_k).interpolate();
+    end_timer!(f_poly_time);
+
+    let g_2 = P::<F>::from_coefficients_slice(&f.coeffs[1..]);
+
+    let domain_b = GeneralEvaluationDomain::<F>::new(3 * domain_k.size() - 3)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+
+    let denom_eval_time = start_timer!(|| "Computing denominator evals on B");
+    let a_denom: Vec<_> = a_star
+        .evals_on_B
+        .row
+        .evals
+        .iter()
+        .zip(&a_star.evals_on_B.col.evals)
+        .zip(&a_star.row_col_evals_on_B.evals)
+        .map(|((&r, c), r_c)| beta * alpha - (r * alpha) - (beta * c) + r_c)
+        .collect();
+
+    let b_denom: Vec<_> = b_star
+        .evals_on_B
+        .row
+        .evals
+        .iter()
+        .zip(&b_star.evals_on_B.col.evals)
+        .zip(&b_star.row_col_evals_on_B.evals)
+        .map(|((&r, c), r_c)| beta * alpha - (r * alpha) - (beta * c) + r_c)
+        .collect();
+
+    let c_denom: Vec<_> = c_star
+        .evals_on_B
+        .row
+        .evals
+        .iter()
+        .zip(&c_star.evals_on_B.col.evals)
+        .zip(&c_star.row_col_evals_on_B.evals)
+        .map(|((&r, c), r_c)| beta * alpha - (r * alpha) - (beta * c) + r_c)
+        .collect();
+    end_timer!(denom_eval_time);
+
+    let a_evals_time = start_timer!(|| "Computing a evals on B");
+    let a_star_evals_on_B = &a_star.evals_on_B;
+    let b_star_evals_on_B = &b_star.evals_on_B;
+    let c_star_evals_on_B = &c_star.evals_on_B;
+    let a_poly_on_B = (0..domain_b.size())
+        .map(|i| {
+            let t = eta_a * a_star_evals_on_B.val.evals[i] * b_denom[i] * c_denom[i]
+                + eta_b * b_star_evals_on_B.val.evals[i] * a_denom[i] * c_denom[i]
+                + eta_c * c_star_evals_on_B.val.evals[i] * a_denom[i] * b_denom[i];
+            v_H_at_beta * v_H_at_alpha * t
+        })
+        .collect();
+    end_timer!(a_evals_time);
+
+    let a_poly_time = start_timer!(|| "Computing a poly");
+    let a_poly = EvaluationsOnDomain::from_vec_and_domain(a_poly_on_B, domain_b).interpolate();
+    end_timer!(a_poly_time);
+
+    let b_evals_time = start_timer!(|| "Computing b evals on B");
+    let b_poly_on_B = (0..domain_b.size())
+        .map(|i| a_denom[i] * b_denom[i] * c_denom[i])
+        .collect();
+    end_timer!(b_evals_time);
+
+    let b_poly_time = start_timer!(|| "Computing b poly");
+    let b_poly = EvaluationsOnDomain::from_vec_and_domain(b_poly_on_B, domain_b).interpolate();
+    end_timer!(b_poly_time);
+
+    let h_2_poly_time = start_timer!(|| "Computing sumcheck h poly");
+    let h_2 = (&a_poly - &(&b_poly * &f))
+        .divide_by_vanishing_poly(domain_k)
+        .unwrap()
+        .0;
+    end_timer!(h_2_poly_time);
+
+    assert!(g_2.degree() <= domain_k.size() - 2);
+    let g_2 = LabeledPolynomial::new("g_2".to_string(), g_2, Some(domain_k.size() - 2), None);
+    let h_2 = LabeledPolynomial::new("h_2".to_string(), h_2, None, None);
+    end_timer!(round_time);
+
+    Ok(vec![share_public_poly(&g_2), share_public_poly(&h_2)])
+}
+
+/// Evaluates linear combinations of shared polynomials, and opens the results.
+///
+/// Each (combination, point) is opened once; later requests are served from `opened`.
+struct OpenedEvaluations<'a, F: PrimeField>
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    polynomials: &'a [&'a SharedPolynomial<F>],
+    opened: RefCell<BTreeMap<(String, F), F>>,
+}
+
+impl<'a, F: PrimeField> EvaluationsProvider<F> for OpenedEvaluations<'a, F>
+where
+    MpcVal<F>: PrimeField,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>, Point = MpcVal<F>>,
+{
+    fn get_lc_eval(&self, lc: &LinearCombination<F>, point: F) -> Result<F, AHPError> {
+        let key = (lc.label.clone(), point);
+        if let Some(eval) = self.opened.borrow().get(&key) {
+            return Ok(*eval);
+        }
+        let mut eval = F::zero();
+        for (coeff, term) in lc.iter() {
+            let value = if let LCTerm::PolyLabel(label) = term {
+                self.polynomials
+                    .iter()
+                    .find(|p| p.label() == label)
+                    .ok_or_else(|| {
+                        AHPError::MissingEval(format!("Missing {} for {}", label, lc.label))
+                    })?
+                    .polynomial()
+                    .val
+                    .evaluate(&point)
+            } else {
+                assert!(term.is_one());
+                share(MpcVal::one())
+            };
+            eval += *coeff * value
+        }
+        let eval = MpcVal::from_shared(eval).publicize_unwrap();
+        self.opened.borrow_mut().insert(key, eval);
+        Ok(eval)
+    }
+}
+
+/// Generate a universal SRS in MPC (see `MpcPolyCommit::setup`), for circuits of up to the given
+/// sizes. The SRS is public.
+pub fn universal_setup<E: PairingEngine, R: RngCore>(
+    num_constraints: usize,
+    num_variables: usize,
+    num_non_zero: usize,
+    rng: &mut R,
+) -> Result<UniversalSRS<E::Fr, PC<E>>, Error<PCError>>
+where
+    MpcVal<E::Fr>: PrimeField,
+    MpcVal<P<E::Fr>>: Polynomial<MpcVal<E::Fr>, Point = MpcVal<E::Fr>>,
+{
+    let max_degree = AHPForR1CS::<E::Fr>::max_degree(num_constraints, num_variables, num_non_zero)?;
+    MpcPC::<E>::setup(max_degree, None, rng).map_err(Error::from_pc_err)
+}
+
+/// Create a Marlin proof over a shared witness. The proof is public.
+///
+/// The circuit's public inputs may be shared; they are opened.
+pub fn prove<E: PairingEngine, D: Digest, C: ConstraintSynthesizer<MpcVal<E::Fr>>>(
+    index_pk: &IndexProverKey<E::Fr, PC<E>>,
+    c: C,
+) -> Result<Proof<E::Fr, PC<E>>, Error<PCError>>
+where
+    MpcVal<E::Fr>: PrimeField,
+    MpcVal<P<E::Fr>>: Polynomial<MpcVal<E::Fr>, Point = MpcVal<E::Fr>>,
+{
+    let prover_time = start_timer!(|| "Marlin::Prover");
+    // for the commitments' hiding randomness, which is shared
+    let zk_rng = &mut party_rng();
+
+    let prover_init_state = prover_init(&index_pk.index, c)?;
+    let public_input = prover_init_state.public_input();
+    let mut fs_rng = FiatShamirRng::<D>::from_seed(
+        &to_bytes![
+            &Marlin::<E::Fr, PC<E>, D>::PROTOCOL_NAME,
+            &index_pk.index_vk,
+            &public_input
+        ]
+        .unwrap(),
+    );
+    let prover_msg = ProverMsg::<E::Fr>::EmptyMessage;
+
+    // --------------------------------------------------------------------
+    // First round
+
+    let (prover_first_oracles, prover_state) = prover_first_round(prover_init_state)?;
+
+    let first_round_comm_time = start_timer!(|| "Committing to first round polys");
+    let (first_comms, first_comm_rands) = MpcPC::<E>::commit(
+        &index_pk.committer_key,
+        prover_first_oracles.iter(),
+        Some(zk_rng),
+    )
+    .map_err(Error::from_pc_err)?;
+    end_timer!(first_round_comm_time);
+
+    fs_rng.absorb(&to_bytes![first_comms, prover_msg].unwrap());
+
+    let (verifier_first_msg, verifier_state) =
+        AHPForR1CS::verifier_first_round(index_pk.index_vk.index_info, &mut fs_rng)?;
+    // --------------------------------------------------------------------
+
+    // --------------------------------------------------------------------
+    // Second round
+
+    let (prover_second_oracles, prover_state) =
+        prover_second_round(&verifier_first_msg, prover_state);
+
+    let second_round_comm_time = start_timer!(|| "Committing to second round polys");
+    let (second_comms, second_comm_rands) = MpcPC::<E>::commit(
+        &index_pk.committer_key,
+        prover_second_oracles.iter(),
+        Some(zk_rng),
+    )
+    .map_err(Error::from_pc_err)?;
+    end_timer!(second_round_comm_time);
+
+    fs_rng.absorb(&to_bytes![second_comms, prover_msg].unwrap());
+
+    let (verifier_second_msg, verifier_state) =
+        AHPForR1CS::verifier_second_round(verifier_state, &mut fs_rng);
+    // --------------------------------------------------------------------
+
+    // --------------------------------------------------------------------
+    // Third round
+    let prover_third_oracles = prover_third_round(&verifier_second_msg, prover_state)?;
+
+    let third_round_comm_time = start_timer!(|| "Committing to third round polys");
+    let (third_comms, third_comm_rands) = MpcPC::<E>::commit(
+        &index_pk.committer_key,
+        prover_third_oracles.iter(),
+        Some(zk_rng),
+    )
+    .map_err(Error::from_pc_err)?;
+    end_timer!(third_round_comm_time);
+
+    fs_rng.absorb(&to_bytes![third_comms, prover_msg].unwrap());
+
+    let verifier_state = AHPForR1CS::verifier_third_round(verifier_state, &mut fs_rng);
+    // --------------------------------------------------------------------
+
+    // Gather prover polynomials in one vector.
+    let index_polynomials: Vec<SharedPolynomial<E::Fr>> =
+        index_pk.index.iter().map(share_public_poly).collect();
+    let polynomials: Vec<_> = index_polynomials
+        .iter()
+        .chain(prover_first_oracles.iter())
+        .chain(prover_second_oracles.iter())
+        .chain(prover_third_oracles.iter())
+        .collect();
+
+    // Gather commitments in one vector.
+    #[rustfmt::skip]
+    let commitments = vec![
+        first_comms.iter().map(|p| *p.commitment()).collect(),
+        second_comms.iter().map(|p| *p.commitment()).collect(),
+        third_comms.iter().map(|p| *p.commitment()).collect(),
+    ];
+    let labeled_comms: Vec<_> = index_pk
+        .index_vk
+        .iter()
+        .cloned()
+        .zip(&AHPForR1CS::<E::Fr>::INDEXER_POLYNOMIALS)
+        .map(|(c, l)| LabeledCommitment::new(l.to_string(), c, None))
+        .chain(first_comms.iter().cloned())
+        .chain(second_comms.iter().cloned())
+        .chain(third_comms.iter().cloned())
+        .collect();
+
+    // Gather commitment randomness together. The index polynomials aren't hiding, so their
+    // randomness is empty, and each party can hold it as its share.
+    let comm_rands: Vec<_> = index_pk
+        .index_comm_rands
+        .iter()
+        .cloned()
+        .map(MpcVal::from_shared)
+        .chain(first_comm_rands)
+        .chain(second_comm_rands)
+        .chain(third_comm_rands)
+        .collect();
+
+    // Compute the AHP verifier's query set.
+    let (query_set, verifier_state) = AHPForR1CS::verifier_query_set(verifier_state, &mut fs_rng);
+    let opened_evaluations = OpenedEvaluations {
+        polynomials: &polynomials,
+        opened: RefCell::new(BTreeMap::new()),
+    };
+    let lc_s = AHPForR1CS::construct_linear_combinations(
+        &public_input,
+        &opened_evaluations,
+        &verifier_state,
+    )?;
+
+    let eval_time = start_timer!(|| "Evaluating linear combinations over query set");
+    let mut evaluations = Vec::new();
+    for (label, (_, point)) in &query_set {
+        if !AHPForR1CS::<E::Fr>::LC_WITH_ZERO_EVAL.contains(&label.as_ref()) {
+            let lc = lc_s
+                .iter()
+                .find(|lc| &lc.label == label)
+                .ok_or_else(|| AHPError::MissingEval(label.to_string()))?;
+            let eval = opened_evaluations.get_lc_eval(lc, *point)?;
+            evaluations.push((label.to_string(), eval));
+        }
+    }
+
+    evaluations.sort_by(|a, b| a.0.cmp(&b.0));
+    let evaluations = evaluations.into_iter().map(|x| x.1).collect::<Vec<E::Fr>>();
+    end_timer!(eval_time);
+
+    fs_rng.absorb(&evaluations);
+    let opening_challenge: E::Fr = u128::rand(&mut fs_rng).into();
+
+    let mpc_lc_s: Vec<LinearCombination<MpcVal<E::Fr>>> = lc_s
+        .iter()
+        .map(|lc| {
+            LinearCombination::new(
+                lc.label.clone(),
+                lc.iter()
+                    .map(|(coeff, term)| (MpcVal::from_public(*coeff), term.clone()))
+                    .collect(),
+            )
+        })
+        .collect();
+    let mpc_query_set: QuerySet<MpcVal<E::Fr>> = query_set
+        .iter()
+        .map(|(label, (point_label, point))| {
+            (
+                label.clone(),
+                (point_label.clone(), MpcVal::from_public(*point)),
+            )
+        })
+        .collect();
+    let mpc_pc_proof = MpcPC::<E>::open_combinations(
+        &index_pk.committer_key,
+        &mpc_lc_s,
+        polynomials,
+        &labeled_comms,
+        &mpc_query_set,
+        MpcVal::from_public(opening_challenge),
+        &comm_rands,
+        Some(zk_rng),
+    )
+    .map_err(Error::from_pc_err)?;
+    let pc_proof = BatchLCProof {
+        proof: mpc_pc_proof.proof,
+        evals: mpc_pc_proof.evals.map(publicize_vals),
+    };
+
+    // Gather prover messages together.
+    let prover_messages = vec![prover_msg.clone(), prover_msg.clone(), prover_msg];
+
+    let proof = Proof::new(commitments, evaluations, prover_messages, pc_proof);
+    proof.print_size_info();
+    end_timer!(prover_time);
+    Ok(proof)
+}
@@ -12,9 +12,20 @@ use sha2::Digest;
 use std::borrow::Cow;
 use std::ops::*;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub mod bigint;
+pub mod boolean;
 pub mod channel;
+pub mod circom;
+pub mod domain;
 pub mod groth;
+pub mod hash_to_curve;
+pub mod input;
 pub mod marlin;
+pub mod plonk;
+pub mod r1cs_std;
 pub mod silly;
 pub mod poly;
 
@@ -47,6 +58,21 @@ pub struct MpcCurve2<T> {
     shared: bool,
 }
 
+/// An MPC bit, shared by XOR rather than by addition
+#[derive(Clone, Copy, Default, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MpcBool {
+    val: bool,
+    shared: bool,
+}
+
+/// The `BigInt` of a prime field `MpcVal`. A share's integer is not linear in the value, so only
+/// the round trip back to the field works on shares; everything else needs a public value.
+#[derive(Clone, Copy, Default, Hash, Debug, PartialEq, Eq)]
+pub struct MpcBigInt<T> {
+    val: T,
+    shared: bool,
+}
+
 #[derive(Clone, Copy, Default, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct MpcPrepCurve<T> {
     val: T,
@@ -1164,32 +1190,38 @@ macro_rules! wrap_mul {
                 )
             }
         }
-        impl<G, F: DivAssign<G>> DivAssign<$wrap<G>> for $wrap<F> {
-            fn div_assign(&mut self, other: $wrap<G>) {
+        impl<F: Field> DivAssign<$wrap<F>> for $wrap<F> {
+            fn div_assign(&mut self, other: $wrap<F>) {
                 match (self.shared, other.shared) {
-                    (true, true) => unimplemented!("ss div"),
+                    (true, true) => {
+                        let inv = channel::field_inv(other.into()).expect("ss div by zero");
+                        *self = channel::field_mul((*self).into(), inv).into();
+                    }
                     _ => self.val.div_assign(other.val),
                 };
                 self.shared = self.shared || other.shared;
             }
         }
-        impl<F: DivAssign<F>> Div<$wrap<F>> for $wrap<F> {
+        impl<F: Field> Div<$wrap<F>> for $wrap<F> {
             type Output = $wrap<F>;
             fn div(mut self, other: $wrap<F>) -> Self::Output {
                 self.div_assign(other);
                 self
             }
         }
-        impl<'a, G, F: DivAssign<&'a G>> DivAssign<&'a $wrap<G>> for $wrap<F> {
-            fn div_assign(&mut self, other: &'a $wrap<G>) {
+        impl<'a, F: Field> DivAssign<&'a $wrap<F>> for $wrap<F> {
+            fn div_assign(&mut self, other: &'a $wrap<F>) {
                 match (self.shared, other.shared) {
-                    (true, true) => unimplemented!("ss div"),
+                    (true, true) => {
+                        let inv = channel::field_inv((*other).into()).expect("ss div by zero");
+                        *self = channel::field_mul((*self).into(), inv).into();
+                    }
                     _ => self.val.div_assign(&other.val),
                 };
                 self.shared = self.shared || other.shared;
             }
         }
-        impl<'a, F: DivAssign<&'a F>> Div<&'a $wrap<F>> for $wrap<F> {
+        impl<'a, F: Field> Div<&'a $wrap<F>> for $wrap<F> {
             type Output = $wrap<F>;
             fn div(mut self, other: &'a $wrap<F>) -> Self::Output {
                 self.div_assign(other);
@@ -1437,6 +1469,9 @@ macro_rules! shared_field {
             fn extension_degree() -> u64 {
                 todo!()
             }
+            fn characteristic<'a>() -> &'a [u64] {
+                <$Pf>::characteristic()
+            }
             fn from_base_prime_field_elems(b: &[<Self as Field>::BasePrimeField]) -> Option<Self> {
                 assert!(b.len() > 0);
                 let shared = b[0].shared;
@@ -1460,7 +1495,7 @@ macro_rules! shared_field {
             }
             fn square(&self) -> Self {
                 if self.shared {
-                    unimplemented!()
+                    channel::field_mul(*self, *self)
                 } else {
                     Self::new(self.val.square(), self.shared)
                 }
@@ -1475,21 +1510,14 @@ macro_rules! shared_field {
             }
             fn inverse(&self) -> Option<Self> {
                 if self.shared {
-                    unimplemented!()
+                    channel::field_inv(*self)
                 } else {
                     self.val.inverse().map(Self::from_public)
                 }
             }
             fn inverse_in_place(&mut self) -> Option<&mut Self> {
-                if self.shared {
-                    unimplemented!()
-                } else {
-                    if self.val.inverse_in_place().is_some() {
-                        Some(self)
-                    } else {
-                        None
-                    }
-                }
+                *self = self.inverse()?;
+                Some(self)
             }
             fn frobenius_map(&mut self, _: usize) {
                 todo!()
@@ -1520,43 +1548,28 @@ macro_rules! shared_mul_field {
                 }
             }
             fn double_in_place(&mut self) -> &mut Self {
-                self.val.double_in_place();
+                self.val.square_in_place();
                 self
             }
             fn from_random_bytes_with_flags<F: Flags>(b: &[u8]) -> Option<(Self, F)> {
                 <$Pf>::from_random_bytes_with_flags(b).map(|(val, f)| (Self::from_shared(val), f))
             }
+            // The shares multiply to the value, so these act on each share locally.
             fn square(&self) -> Self {
-                if self.shared {
-                    unimplemented!()
-                } else {
-                    Self::new(self.val.square(), self.shared)
-                }
+                Self::new(self.val.square(), self.shared)
             }
             fn square_in_place(&mut self) -> &mut Self {
-                if self.shared {
-                    unimplemented!()
-                } else {
-                    self.val.square_in_place();
-                    self
-                }
+                self.val.square_in_place();
+                self
             }
             fn inverse(&self) -> Option<Self> {
-                if self.shared {
-                    unimplemented!()
-                } else {
-                    self.val.inverse().map(Self::from_public)
-                }
+                self.val.inverse().map(|val| Self::new(val, self.shared))
             }
             fn inverse_in_place(&mut self) -> Option<&mut Self> {
-                if self.shared {
-                    unimplemented!()
+                if self.val.inverse_in_place().is_some() {
+                    Some(self)
                 } else {
-                    if self.val.inverse_in_place().is_some() {
-                        Some(self)
-                    } else {
-                        None
-                    }
+                    None
                 }
             }
             fn frobenius_map(&mut self, _: usize) {
@@ -1569,7 +1582,7 @@ macro_rules! shared_mul_field {
 macro_rules! shared_prime_field {
     ($Pf:ty, $Repr:ty) => {
         impl FftField for MpcVal<$Pf> {
-            type FftParams = <$Pf as FftField>::FftParams;
+            type FftParams = bigint::MpcFpParams<<$Pf as PrimeField>::Params>;
             fn two_adic_root_of_unity() -> Self {
                 Self::from_public(<$Pf as FftField>::two_adic_root_of_unity())
             }
@@ -1582,38 +1595,30 @@ macro_rules! shared_prime_field {
         }
 
         impl PrimeField for MpcVal<$Pf> {
-            type Params = <$Pf as PrimeField>::Params;
-            // type BigInt = MpcVal<F::BigInt>;
-            type BigInt = <$Pf as PrimeField>::BigInt;
-            // We're assuming that from_repr is linear
+            type Params = bigint::MpcFpParams<<$Pf as PrimeField>::Params>;
+            type BigInt = MpcBigInt<$Repr>;
+            // A share is a field element, so its integer round-trips, keeping the shared flag.
             fn from_repr(r: <Self as PrimeField>::BigInt) -> Option<Self> {
-                // F::from_repr(r.val).map(|v| MpcVal::new(v, r.shared))
-                <$Pf>::from_repr(r).map(|v| MpcVal::from_public(v))
+                <$Pf>::from_repr(r.val).map(|v| MpcVal::new(v, r.shared))
             }
-            // We're assuming that into_repr is linear
             fn into_repr(&self) -> <Self as PrimeField>::BigInt {
-                // MpcVal::new(self.val.into_repr(), self.shared)
-                self.val.into_repr()
+                MpcBigInt::new(self.val.into_repr(), self.shared)
             }
         }
 
-        impl From<$Repr> for MpcVal<$Pf> {
+        impl From<MpcBigInt<$Repr>> for MpcVal<$Pf> {
             /// Converts `Self::BigInteger` into `Self`
             ///
             /// # Panics
             /// This method panics if `int` is larger than `P::MODULUS`.
-            fn from(int: $Repr) -> Self {
+            fn from(int: MpcBigInt<$Repr>) -> Self {
                 Self::from_repr(int).unwrap()
             }
         }
 
-        impl From<MpcVal<$Pf>> for $Repr {
-            /// Converts `Self::BigInteger` into `Self`
-            ///
-            /// # Panics
-            /// This method panics if `int` is larger than `P::MODULUS`.
-            fn from(int: MpcVal<$Pf>) -> Self {
-                int.into_repr()
+        impl From<MpcVal<$Pf>> for MpcBigInt<$Repr> {
+            fn from(x: MpcVal<$Pf>) -> Self {
+                x.into_repr()
             }
         }
     };
@@ -1634,6 +1639,7 @@ macro_rules! shared_sqrt_field {
     };
 }
 
+// BLS12-377
 shared_field!(ark_bls12_377::Fr, ark_bls12_377::Fr);
 shared_prime_field!(ark_bls12_377::Fr, ark_ff::BigInteger256);
 shared_sqrt_field!(ark_bls12_377::Fr);
@@ -1648,8 +1654,51 @@ shared_sqrt_field!(ark_bls12_377::Fq2);
 shared_field!(ark_bls12_377::Fq12, ark_bls12_377::Fq);
 shared_mul_field!(ark_bls12_377::Fq12, ark_bls12_377::Fq);
 
+// BLS12-381
+shared_field!(ark_bls12_381::Fr, ark_bls12_381::Fr);
+shared_prime_field!(ark_bls12_381::Fr, ark_ff::BigInteger256);
+shared_sqrt_field!(ark_bls12_381::Fr);
+
+shared_field!(ark_bls12_381::Fq, ark_bls12_381::Fq);
+shared_prime_field!(ark_bls12_381::Fq, ark_ff::BigInteger384);
+shared_sqrt_field!(ark_bls12_381::Fq);
+
+shared_field!(ark_bls12_381::Fq2, ark_bls12_381::Fq);
+shared_sqrt_field!(ark_bls12_381::Fq2);
+
+shared_field!(ark_bls12_381::Fq12, ark_bls12_381::Fq);
+shared_mul_field!(ark_bls12_381::Fq12, ark_bls12_381::Fq);
+
+// BN254
+shared_field!(ark_bn254::Fr, ark_bn254::Fr);
+shared_prime_field!(ark_bn254::Fr, ark_ff::BigInteger256);
+shared_sqrt_field!(ark_bn254::Fr);
+
+shared_field!(ark_bn254::Fq, ark_bn254::Fq);
+shared_prime_field!(ark_bn254::Fq, ark_ff::BigInteger256);
+shared_sqrt_field!(ark_bn254::Fq);
+
+shared_field!(ark_bn254::Fq2, ark_bn254::Fq);
+shared_sqrt_field!(ark_bn254::Fq2);
+
+shared_field!(ark_bn254::Fq12, ark_bn254::Fq);
+shared_mul_field!(ark_bn254::Fq12, ark_bn254::Fq);
+
+// ed_on_bls12_377: Fq is the BLS12-377 Fr (lifted above).
+shared_field!(ark_ed_on_bls12_377::Fr, ark_ed_on_bls12_377::Fr);
+shared_prime_field!(ark_ed_on_bls12_377::Fr, ark_ff::BigInteger256);
+shared_sqrt_field!(ark_ed_on_bls12_377::Fr);
+
+// BW6-761: Fr is the BLS12-377 Fq (lifted above), and G2 is defined over Fq.
+shared_field!(ark_bw6_761::Fq, ark_bw6_761::Fq);
+shared_prime_field!(ark_bw6_761::Fq, ark_ff::BigInteger768);
+shared_sqrt_field!(ark_bw6_761::Fq);
+
+shared_field!(ark_bw6_761::Fq6, ark_bw6_761::Fq);
+shared_mul_field!(ark_bw6_761::Fq6, ark_bw6_761::Fq);
+
 macro_rules! curve_impl {
-    ($curve:path, $curve_proj:path, $base:path, $scalar:path, $cofactor:path, $curve_wrapper:ident) => {
+    ($curve:ty, $curve_proj:ty, $base:ty, $scalar:ty, $cofactor:expr, $curve_wrapper:ident) => {
         impl AffineCurve for $curve_wrapper<$curve> {
             type ScalarField = MpcVal<$scalar>;
             const COFACTOR: &'static [u64] = $cofactor;
@@ -1658,29 +1707,50 @@ macro_rules! curve_impl {
             fn prime_subgroup_generator() -> Self {
                 Self::from_public(<$curve as AffineCurve>::prime_subgroup_generator())
             }
-            fn from_random_bytes(_: &[u8]) -> Option<Self> {
-                todo!("AffineCurve::from_random_bytes")
+            // Both parties see the same bytes, so the point is public.
+            fn from_random_bytes(bytes: &[u8]) -> Option<Self> {
+                <$curve>::from_random_bytes(bytes).map(Self::from_public)
             }
             fn mul<S: Into<<Self::ScalarField as PrimeField>::BigInt>>(
                 &self,
                 s: S,
             ) -> <Self as AffineCurve>::Projective {
-                if self.shared {
-                    // Cast s to bigint..
-                    let bigint = s.into();
-                    let scalar = Self::ScalarField::from_repr(bigint).unwrap();
-                    let proj: Self::Projective = self.clone().into();
-                    channel::curve_mul(proj.into(), scalar.cast_to_shared()).into()
-                } else {
-                    let s = s.into();
-                    $curve_wrapper::from_shared(self.val.mul(s))
-                }
+                // The scalar's BigInt says whether it is shared.
+                let scalar = Self::ScalarField::from_repr(s.into()).unwrap();
+                let proj: Self::Projective = (*self).into();
+                channel::curve_mul(proj.into(), scalar).into()
             }
+            // Multiplying by a public constant is linear, so shares are handled locally.
             fn mul_by_cofactor_to_projective(&self) -> <Self as AffineCurve>::Projective {
-                todo!("AffineCurve::mul_by_cofactor_to_projective")
+                $curve_wrapper::new(self.val.mul_by_cofactor_to_projective(), self.shared)
             }
             fn mul_by_cofactor_inv(&self) -> Self {
-                todo!("AffineCurve::mul_by_cofactor_inv")
+                Self::new(self.val.mul_by_cofactor_inv(), self.shared)
+            }
+        }
+        impl $curve_wrapper<$curve> {
+            /// Open the point, checking that it is on the curve and in the prime-order subgroup.
+            /// A bad share from the other party gives `None` rather than a panic.
+            pub fn publicize_checked(self) -> Option<Self> {
+                let p = if self.shared {
+                    let proj: $curve_wrapper<$curve_proj> = self.into();
+                    channel::curve_publicize_unchecked(proj.into())?.val.into_affine()
+                } else {
+                    self.val
+                };
+                if p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve() {
+                    Some(Self::from_public(p))
+                } else {
+                    None
+                }
+            }
+        }
+        impl $curve_wrapper<$curve_proj> {
+            /// See the affine `publicize_checked`.
+            pub fn publicize_checked(self) -> Option<Self> {
+                $curve_wrapper::<$curve>::from(self)
+                    .publicize_checked()
+                    .map(Into::into)
             }
         }
         impl From<$curve_wrapper<$curve_proj>> for $curve_wrapper<$curve> {
@@ -1701,13 +1771,18 @@ macro_rules! curve_impl {
             fn prime_subgroup_generator() -> Self {
                 Self::from_public(<$curve_proj as ProjectiveCurve>::prime_subgroup_generator())
             }
+            // Normalization changes a point's representation, not the point, so each party just
+            // normalizes its own values (public points and shares alike), with one inversion.
             fn batch_normalization(elems: &mut [Self]) {
-                //TODO: wrong?
-                elems.iter_mut().for_each(|e| <$curve_proj>::batch_normalization(&mut[e.val]));
-                //todo!("ProjectiveCurve::batch_normalization")
+                let mut vals: Vec<$curve_proj> = elems.iter().map(|e| e.val).collect();
+                <$curve_proj>::batch_normalization(&mut vals);
+                for (e, v) in elems.iter_mut().zip(vals) {
+                    e.val = v;
+                }
             }
+            // For a shared point, this is about our share's representation only.
             fn is_normalized(&self) -> bool {
-                todo!("ProjectiveCurve::is_normalized")
+                self.val.is_normalized()
             }
             fn double_in_place(&mut self) -> &mut Self {
                 <$curve_proj as ProjectiveCurve>::double_in_place(&mut self.val);
@@ -1734,16 +1809,18 @@ macro_rules! curve_impl {
                 }
                 self.shared = self.shared || o.shared;
             }
+            // Words can't be shared (`MpcBigInt` only gives out public ones), so this is linear in
+            // the point. For a shared scalar, use `AffineCurve::mul` or `*=`.
             fn mul<S: AsRef<[u64]>>(self, scalar_words: S) -> Self {
-                if self.shared {
-                    // Cast s to bigint..
-                    let mut scalar = <Self::ScalarField as PrimeField>::BigInt::from(0u64);
-                    scalar.as_mut().copy_from_slice(scalar_words.as_ref());
-                    let scalar = Self::ScalarField::from_repr(scalar).unwrap();
-                    channel::curve_mul(self.into(), scalar.cast_to_shared()).into()
-                } else {
-                    $curve_wrapper::from_shared(self.val.mul(scalar_words))
-                }
+                Self::new(self.val.mul(scalar_words), self.shared)
+            }
+        }
+        impl BatchScalarMul for $curve_wrapper<$curve_proj> {
+            fn batch_scalar_mul(xs: Vec<Self>, ys: Vec<Self::ScalarField>) -> Vec<Self> {
+                channel::curve_batch_mul(xs.into_iter().map(Into::into).collect(), ys)
+                    .into_iter()
+                    .map(Into::into)
+                    .collect()
             }
         }
     };
@@ -1768,22 +1845,6 @@ macro_rules! curve_impl {
 //     }
 // }
 
-curve_impl!(
-    ark_bls12_377::G1Affine,
-    ark_bls12_377::G1Projective,
-    ark_bls12_377::Fq,
-    ark_bls12_377::Fr,
-    ark_bls12_377::G1Affine::COFACTOR,
-    MpcCurve
-);
-curve_impl!(
-    ark_bls12_377::G2Affine,
-    ark_bls12_377::G2Projective,
-    ark_bls12_377::Fq2,
-    ark_bls12_377::Fr,
-    ark_bls12_377::G2Affine::COFACTOR,
-    MpcCurve2
-);
 //group_impl!(ark_bls12_377::G1Projective, ark_bls12_377::Fr);
 
 //    type ScalarField: PrimeField + SquareRootField + Into<<Self::ScalarField
@@ -1814,73 +1875,15 @@ pub struct MpcPairingEngine<E: PairingEngine> {
 }
 
 macro_rules! impl_prep {
-    ($wrap:ident, $curve:path, $prep_wrap:ident, $prep_curve:path) => {
-        impl std::convert::From<$wrap<$curve>> for $prep_wrap<$prep_curve> {
+    ($wrap:ident, $curve:ty, $prep_wrap:ident) => {
+        impl std::convert::From<$wrap<$curve>> for $prep_wrap<$curve> {
             fn from(f: $wrap<$curve>) -> Self {
-                Self::new(f.val.into(), f.shared)
+                Self::new(f.val, f.shared)
             }
         }
     };
 }
 
-type BlsG1Prep = <ark_bls12_377::Bls12_377 as PairingEngine>::G1Prepared;
-impl_prep!(MpcCurve, ark_bls12_377::G1Affine, MpcPrepCurve, BlsG1Prep);
-type BlsG2Prep = <ark_bls12_377::Bls12_377 as PairingEngine>::G2Prepared;
-impl_prep!(MpcCurve2, ark_bls12_377::G2Affine, MpcPrepCurve2, BlsG2Prep);
-
-impl PairingEngine for MpcPairingEngine<Bls12_377> {
-    type Fr = MpcVal<<Bls12_377 as PairingEngine>::Fr>;
-    type G1Projective = MpcCurve<<Bls12_377 as PairingEngine>::G1Projective>;
-    type G1Affine = MpcCurve<<Bls12_377 as PairingEngine>::G1Affine>;
-    type G1Prepared = MpcPrepCurve<<Bls12_377 as PairingEngine>::G1Prepared>;
-    type G2Projective = MpcCurve2<<Bls12_377 as PairingEngine>::G2Projective>;
-    type G2Affine = MpcCurve2<<Bls12_377 as PairingEngine>::G2Affine>;
-    type G2Prepared = MpcPrepCurve2<<Bls12_377 as PairingEngine>::G2Prepared>;
-    type Fq = MpcVal<<Bls12_377 as PairingEngine>::Fq>;
-    type Fqe = MpcVal<<Bls12_377 as PairingEngine>::Fqe>;
-    type Fqk = MpcMulVal<<Bls12_377 as PairingEngine>::Fqk>;
-
-    fn miller_loop<'a, I>(_i: I) -> Self::Fqk
-    where
-        I: IntoIterator<Item = &'a (Self::G1Prepared, Self::G2Prepared)>,
-    {
-        unimplemented!()
-        // <Bls12_377 as PairingEngine>::miller_loop(i)
-    }
-
-    fn final_exponentiation(_f: &Self::Fqk) -> Option<Self::Fqk> {
-        unimplemented!()
-        // <Bls12_377 as PairingEngine>::final_exponentiation(f)
-    }
-
-    /// Computes a product of pairings.
-    #[must_use]
-    fn product_of_pairings<'a, I>(_i: I) -> Self::Fqk
-    where
-        I: IntoIterator<Item = &'a (Self::G1Prepared, Self::G2Prepared)>,
-    {
-        // TODO: MPC!
-        // <Bls12_377 as PairingEngine>::product_of_pairings(i)
-        unimplemented!()
-    }
-
-    /// Performs multiple pairing operations
-    #[must_use]
-    fn pairing<G1, G2>(p: G1, q: G2) -> Self::Fqk
-    where
-        G1: Into<Self::G1Affine>,
-        G2: Into<Self::G2Affine>,
-    {
-        let p: Self::G1Affine = p.into();
-        let q: Self::G2Affine = q.into();
-        let p_val: MpcVal<<Bls12_377 as PairingEngine>::G1Projective> =
-            MpcVal::new(p.val.into(), p.shared);
-        let q_val: MpcVal<<Bls12_377 as PairingEngine>::G2Projective> =
-            MpcVal::new(q.val.into(), q.shared);
-        channel::pairing::<Bls12_377>(p_val, q_val).into()
-    }
-}
-
 /// Vector-Commitable Field
 pub trait MpcWire: Clone {
     type Base: Clone;
@@ -2027,28 +2030,56 @@ pub trait MsmCurve: AffineCurve {
 }
 
 impl<P: ark_ec::models::SWModelParameters> MsmCurve for ark_ec::short_weierstrass_jacobian::GroupAffine<P> {}
+impl<P: ark_ec::models::TEModelParameters> MsmCurve for ark_ec::twisted_edwards_extended::GroupAffine<P> {}
 
 macro_rules! impl_msm {
     ($wrap:ident, $curve:ty) => {
         impl MsmCurve for $wrap<$curve> {
             fn multi_scalar_mul(bases: &[Self], scalars: &[Self::ScalarField]) -> Self::Projective {
-                assert!(bases.iter().all(|b| !b.shared));
-                assert!(scalars.iter().all(|b| b.shared));
-                let bigint_scalars = cfg_into_iter!(scalars)
-                    .map(|s| s.into_repr())
-                    .collect::<Vec<_>>();
-                let product = VariableBaseMSM::multi_scalar_mul(&bases, &bigint_scalars);
-                // This is shared because the big intergers are representations of a shared value.
-                product.cast_to_shared()
+                // Split the terms by which of their bases and scalars are shared.
+                let mut public = (Vec::new(), Vec::new());
+                let mut local = (Vec::new(), Vec::new());
+                let mut both = (Vec::new(), Vec::new());
+                for (b, s) in bases.iter().zip(scalars) {
+                    match (b.shared, s.shared) {
+                        (false, false) => {
+                            public.0.push(b.val);
+                            public.1.push(s.val.into_repr());
+                        }
+                        (true, true) => {
+                            both.0.push(MpcVal::from_shared(b.val.into_projective()));
+                            both.1.push(*s);
+                        }
+                        // Linear in the shared operand, so each party can work on its share.
+                        _ => {
+                            local.0.push(b.val);
+                            local.1.push(s.val.into_repr());
+                        }
+                    }
+                }
+                let mut product = Self::Projective::from_public(
+                    VariableBaseMSM::multi_scalar_mul(&public.0, &public.1),
+                );
+                if !local.0.is_empty() {
+                    product += Self::Projective::from_shared(VariableBaseMSM::multi_scalar_mul(
+                        &local.0, &local.1,
+                    ));
+                }
+                if !both.0.is_empty() {
+                    let both: Self::Projective = channel::curve_msm(both.0, both.1).into();
+                    product += both;
+                }
+                product
             }
         }
     }
 }
 
-impl_msm!(MpcCurve, ark_bls12_377::G1Affine);
-impl_msm!(MpcCurve2, ark_bls12_377::G2Affine);
-
 pub trait BatchProd: Field {
+    /// Whether values are secret-shared. Then equal values can have unequal shares, so e.g. a
+    /// constraint system's satisfiability check (which compares them) is meaningless.
+    const SHARED: bool = false;
+
     fn batch_product(mut xs: Vec<Self>, ys: Vec<Self>) -> Vec<Self> {
         assert_eq!(xs.len(), ys.len());
         ark_std::cfg_iter_mut!(xs)
@@ -2058,13 +2089,195 @@ pub trait BatchProd: Field {
         xs
     }
 }
-impl BatchProd for ark_bls12_377::Fr {}
-impl BatchProd for MpcVal<ark_bls12_377::Fr> {
-    fn batch_product(xs: Vec<Self>, ys: Vec<Self>) -> Vec<Self> {
-        channel::field_batch_mul(xs, ys)
+
+/// Curves whose elements can be multiplied by scalars in a batch
+pub trait BatchScalarMul: ProjectiveCurve {
+    fn batch_scalar_mul(mut xs: Vec<Self>, ys: Vec<Self::ScalarField>) -> Vec<Self> {
+        assert_eq!(xs.len(), ys.len());
+        ark_std::cfg_iter_mut!(xs)
+            .zip(ys)
+            .for_each(|(a, b)| *a *= b);
+
+        xs
+    }
+}
+impl<P: ark_ec::models::SWModelParameters> BatchScalarMul
+    for ark_ec::short_weierstrass_jacobian::GroupProjective<P>
+{
+}
+impl<P: ark_ec::models::TEModelParameters> BatchScalarMul
+    for ark_ec::twisted_edwards_extended::GroupProjective<P>
+{
+}
+
+/// An rng seeded from this party's OS entropy, for sampling shares that the other party must not
+/// learn (e.g., of toxic waste). Unlike `test_rng`, which gives both parties the same shares.
+pub fn party_rng() -> ark_std::rand::rngs::StdRng {
+    use ark_std::rand::SeedableRng;
+    let mut seed = [0u8; 32];
+    getrandom::getrandom(&mut seed).unwrap();
+    ark_std::rand::rngs::StdRng::from_seed(seed)
+}
+
+/// Multiply a public point by a batch of SS scalars, giving SS points. Local: each party multiplies
+/// by its shares.
+pub fn fixed_base_mul_shares<G: ProjectiveCurve>(
+    g: G,
+    scalars: &[MpcVal<G::ScalarField>],
+) -> Vec<MpcVal<G>> {
+    assert!(scalars.iter().all(|s| s.shared));
+    let scalar_bits = G::ScalarField::size_in_bits();
+    let window = ark_ec::msm::FixedBaseMSM::get_mul_window_size(scalars.len());
+    let table = ark_ec::msm::FixedBaseMSM::get_window_table(scalar_bits, window, g);
+    let shares: Vec<G::ScalarField> = scalars.iter().map(|s| s.val).collect();
+    ark_ec::msm::FixedBaseMSM::multi_scalar_mul::<G>(scalar_bits, window, &table, &shares)
+        .into_iter()
+        .map(MpcVal::from_shared)
+        .collect()
+}
+
+/// Pairing engines whose target group can be raised to a scalar power
+pub trait TargetPow: PairingEngine {
+    fn target_pow(f: Self::Fqk, s: Self::Fr) -> Self::Fqk {
+        f.pow(s.into_repr())
     }
 }
 
+/// Lift a pairing engine (from the curve crate `$m`) and its curves to MPC.
+///
+/// Its fields must be lifted separately, since some curves share fields. The curves are named by
+/// their parameters rather than the crate's `G1Affine` (etc.) aliases, since coherence checking
+/// can't see through the aliases' associated types to tell the curves apart.
+macro_rules! mpc_pairing_engine {
+    ($m:ident, $E:ident) => {
+        curve_impl!(
+            ark_ec::short_weierstrass_jacobian::GroupAffine<$m::g1::Parameters>,
+            ark_ec::short_weierstrass_jacobian::GroupProjective<$m::g1::Parameters>,
+            <$m::$E as PairingEngine>::Fq,
+            $m::Fr,
+            <$m::G1Affine as AffineCurve>::COFACTOR,
+            MpcCurve
+        );
+        curve_impl!(
+            ark_ec::short_weierstrass_jacobian::GroupAffine<$m::g2::Parameters>,
+            ark_ec::short_weierstrass_jacobian::GroupProjective<$m::g2::Parameters>,
+            <$m::$E as PairingEngine>::Fqe,
+            $m::Fr,
+            <$m::G2Affine as AffineCurve>::COFACTOR,
+            MpcCurve2
+        );
+
+        // Shared points can't be prepared until they're masked and opened, so "prepared" points
+        // are just affine points; the preparation happens inside the miller loop.
+        impl_prep!(
+            MpcCurve,
+            ark_ec::short_weierstrass_jacobian::GroupAffine<$m::g1::Parameters>,
+            MpcPrepCurve
+        );
+        impl_prep!(
+            MpcCurve2,
+            ark_ec::short_weierstrass_jacobian::GroupAffine<$m::g2::Parameters>,
+            MpcPrepCurve2
+        );
+
+        impl_msm!(MpcCurve, ark_ec::short_weierstrass_jacobian::GroupAffine<$m::g1::Parameters>);
+        impl_msm!(MpcCurve2, ark_ec::short_weierstrass_jacobian::GroupAffine<$m::g2::Parameters>);
+
+        impl PairingEngine for MpcPairingEngine<$m::$E> {
+            type Fr = MpcVal<<$m::$E as PairingEngine>::Fr>;
+            type G1Projective = MpcCurve<<$m::$E as PairingEngine>::G1Projective>;
+            type G1Affine = MpcCurve<<$m::$E as PairingEngine>::G1Affine>;
+            type G1Prepared = MpcPrepCurve<<$m::$E as PairingEngine>::G1Affine>;
+            type G2Projective = MpcCurve2<<$m::$E as PairingEngine>::G2Projective>;
+            type G2Affine = MpcCurve2<<$m::$E as PairingEngine>::G2Affine>;
+            type G2Prepared = MpcPrepCurve2<<$m::$E as PairingEngine>::G2Affine>;
+            type Fq = MpcVal<<$m::$E as PairingEngine>::Fq>;
+            type Fqe = MpcVal<<$m::$E as PairingEngine>::Fqe>;
+            type Fqk = MpcMulVal<<$m::$E as PairingEngine>::Fqk>;
+
+            /// Over shared inputs, the shares of the result multiply to a value with the same final
+            /// exponentiation as the true miller loop (but not necessarily the same value).
+            fn miller_loop<'a, I>(i: I) -> Self::Fqk
+            where
+                I: IntoIterator<Item = &'a (Self::G1Prepared, Self::G2Prepared)>,
+            {
+                let pairs = i
+                    .into_iter()
+                    .map(|(p, q)| (MpcVal::new(p.val, p.shared), MpcVal::new(q.val, q.shared)))
+                    .collect();
+                channel::miller_loop::<$m::$E>(pairs).into()
+            }
+
+            /// A power map, so it acts on each multiplicative share locally.
+            fn final_exponentiation(f: &Self::Fqk) -> Option<Self::Fqk> {
+                <$m::$E as PairingEngine>::final_exponentiation(&f.val)
+                    .map(|val| MpcMulVal::new(val, f.shared))
+            }
+
+            /// Computes a product of pairings, in one round.
+            #[must_use]
+            fn product_of_pairings<'a, I>(i: I) -> Self::Fqk
+            where
+                I: IntoIterator<Item = &'a (Self::G1Prepared, Self::G2Prepared)>,
+            {
+                Self::final_exponentiation(&Self::miller_loop(i)).unwrap()
+            }
+
+            /// Performs multiple pairing operations
+            #[must_use]
+            fn pairing<G1, G2>(p: G1, q: G2) -> Self::Fqk
+            where
+                G1: Into<Self::G1Affine>,
+                G2: Into<Self::G2Affine>,
+            {
+                let p: Self::G1Affine = p.into();
+                let q: Self::G2Affine = q.into();
+                let p_val: MpcVal<<$m::$E as PairingEngine>::G1Projective> =
+                    MpcVal::new(p.val.into(), p.shared);
+                let q_val: MpcVal<<$m::$E as PairingEngine>::G2Projective> =
+                    MpcVal::new(q.val.into(), q.shared);
+                channel::pairing::<$m::$E>(p_val, q_val).into()
+            }
+        }
+
+        impl BatchProd for $m::Fr {}
+        impl domain::BatchFft for $m::Fr {}
+        impl BatchProd for MpcVal<$m::Fr> {
+            const SHARED: bool = true;
+
+            fn batch_product(xs: Vec<Self>, ys: Vec<Self>) -> Vec<Self> {
+                channel::field_batch_mul(xs, ys)
+            }
+        }
+
+        impl TargetPow for $m::$E {}
+        impl TargetPow for MpcPairingEngine<$m::$E> {
+            fn target_pow(f: Self::Fqk, s: Self::Fr) -> Self::Fqk {
+                channel::gt_exp::<$m::$E>(f.into(), s).into()
+            }
+        }
+    };
+}
+
+mpc_pairing_engine!(ark_bls12_377, Bls12_377);
+mpc_pairing_engine!(ark_bls12_381, Bls12_381);
+mpc_pairing_engine!(ark_bn254, Bn254);
+mpc_pairing_engine!(ark_bw6_761, BW6_761);
+
+// Embedded (twisted Edwards) curves, for in-circuit signatures and commitments
+curve_impl!(
+    ark_ec::twisted_edwards_extended::GroupAffine<ark_ed_on_bls12_377::EdwardsParameters>,
+    ark_ec::twisted_edwards_extended::GroupProjective<ark_ed_on_bls12_377::EdwardsParameters>,
+    ark_ed_on_bls12_377::Fq,
+    ark_ed_on_bls12_377::Fr,
+    <ark_ed_on_bls12_377::EdwardsAffine as AffineCurve>::COFACTOR,
+    MpcCurve
+);
+impl_msm!(
+    MpcCurve,
+    ark_ec::twisted_edwards_extended::GroupAffine<ark_ed_on_bls12_377::EdwardsParameters>
+);
+
 macro_rules! mpc_debug {
     ($e:expr) => {
         debug!("{}: {}", stringify!($e), ($e).clone().publicize())
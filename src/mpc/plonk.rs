@@ -0,0 +1,579 @@
+//! A small PLONK, to prove with over shared witnesses.
+//!
+//! One gate, `q_l a + q_r b + q_o c + q_m a b + q_c + PI = 0`, with copy constraints between
+//! wires, and Marlin's KZG (without degree bounds or hiding: the wire polynomials are blinded
+//! with multiples of `Z_H`, as in the paper) for commitments. The verifier does not linearize:
+//! every polynomial is opened at `zeta`, and `z` at `zeta * omega`.
+//!
+//! This is a custom variant, not the protocol of the paper: its transcript, openings and proofs
+//! are its own. No standard PLONK verifier accepts its proofs; they're checked only by `verify`
+//! here, so it is not a drop-in prover for existing PLONK deployments. The plain prover and
+//! verifier (`local_test_prove_and_verify`) serve as the reference for the MPC prover.
+use ark_bls12_377::{Bls12_377, Fr};
+use ark_ff::{to_bytes, FftField, Field, One, UniformRand, Zero};
+use ark_marlin::rng::FiatShamirRng;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{
+    EvaluationDomain, Evaluations as EvaluationsOnDomain, GeneralEvaluationDomain, Polynomial,
+    UVPolynomial,
+};
+use ark_poly_commit::marlin_pc::{self, MarlinKZG10};
+use ark_poly_commit::{
+    Evaluations, LabeledCommitment, LabeledPolynomial, PCRandomness, PolynomialCommitment, QuerySet,
+};
+use ark_std::rand::RngCore;
+use ark_std::{end_timer, start_timer, test_rng};
+use blake2::Blake2s;
+
+use super::*;
+
+pub mod prover;
+
+type P = DensePolynomial<Fr>;
+type PC = MarlinKZG10<Bls12_377, P>;
+type D = GeneralEvaluationDomain<Fr>;
+type Commitment = marlin_pc::Commitment<Bls12_377>;
+pub type UniversalParams = <PC as PolynomialCommitment<Fr, P>>::UniversalParams;
+
+const PROTOCOL_NAME: &[u8] = b"PLONK";
+const INDEX_POLYNOMIALS: [&str; 8] = ["q_l", "q_r", "q_o", "q_m", "q_c", "s_1", "s_2", "s_3"];
+const PROVER_POLYNOMIALS: [&str; 7] = ["a", "b", "c", "z", "t_lo", "t_mid", "t_hi"];
+
+/// Gates `q_l a + q_r b + q_o c + q_m a b + q_c + PI = 0`.
+///
+/// Variables `0..num_public_inputs` are the public inputs; the first gates expose them, through
+/// `PI`.
+#[derive(Clone)]
+pub struct Circuit {
+    /// `[q_l, q_r, q_o, q_m, q_c]` for each gate
+    pub selectors: Vec<[Fr; 5]>,
+    /// The variables on the `a`, `b` and `c` wires of each gate
+    pub wires: Vec<[usize; 3]>,
+    pub num_variables: usize,
+    pub num_public_inputs: usize,
+}
+
+impl Circuit {
+    pub fn new(num_public_inputs: usize) -> Self {
+        let mut c = Circuit {
+            selectors: Vec::new(),
+            wires: Vec::new(),
+            num_variables: num_public_inputs,
+            num_public_inputs,
+        };
+        for i in 0..num_public_inputs {
+            c.gate(
+                [Fr::one(), Fr::zero(), Fr::zero(), Fr::zero(), Fr::zero()],
+                [i, 0, 0],
+            );
+        }
+        c
+    }
+    pub fn new_variable(&mut self) -> usize {
+        self.num_variables += 1;
+        self.num_variables - 1
+    }
+    pub fn gate(&mut self, selectors: [Fr; 5], wires: [usize; 3]) {
+        assert!(wires.iter().all(|w| *w < self.num_variables.max(1)));
+        self.selectors.push(selectors);
+        self.wires.push(wires);
+    }
+    /// `a * b = c`
+    pub fn mul(&mut self, a: usize, b: usize, c: usize) {
+        self.gate(
+            [Fr::zero(), Fr::zero(), -Fr::one(), Fr::one(), Fr::zero()],
+            [a, b, c],
+        );
+    }
+    /// `a + b = c`
+    pub fn add(&mut self, a: usize, b: usize, c: usize) {
+        self.gate(
+            [Fr::one(), Fr::one(), -Fr::one(), Fr::zero(), Fr::zero()],
+            [a, b, c],
+        );
+    }
+}
+
+pub struct VerifierKey {
+    pub n: usize,
+    pub num_public_inputs: usize,
+    /// Commitments to the selectors and the permutation
+    pub comms: Vec<LabeledCommitment<Commitment>>,
+    pub pc_vk: <PC as PolynomialCommitment<Fr, P>>::VerifierKey,
+}
+
+pub struct ProverKey {
+    pub vk: VerifierKey,
+    pub ck: <PC as PolynomialCommitment<Fr, P>>::CommitterKey,
+    pub wires: Vec<[usize; 3]>,
+    /// The selectors and the permutation
+    pub polys: Vec<LabeledPolynomial<Fr, P>>,
+    /// The permutation, evaluated over the domain
+    pub sigma_evals: [Vec<Fr>; 3],
+}
+
+pub struct Proof {
+    /// Commitments to `a`, `b`, `c`, `z`, `t_lo`, `t_mid` and `t_hi`
+    pub commitments: Vec<Commitment>,
+    /// The evaluations in the query set, in order
+    pub evaluations: Vec<Fr>,
+    pub pc_proof: <PC as PolynomialCommitment<Fr, P>>::BatchProof,
+}
+
+/// Shifts for the cosets of the domain that identify the `a`, `b` and `c` wires
+fn coset_shifts() -> [Fr; 3] {
+    let g = Fr::multiplicative_generator();
+    [Fr::one(), g, g.square()]
+}
+
+fn interpolate(evals: Vec<Fr>, domain: D) -> P {
+    EvaluationsOnDomain::from_vec_and_domain(evals, domain).interpolate()
+}
+
+/// Interpolate `evals`, and add `blinding(X) * Z_H(X)`
+fn blind(evals: Vec<Fr>, blinding: &[Fr], domain: D) -> P {
+    let v_h: P = domain.vanishing_polynomial().into();
+    &interpolate(evals, domain) + &(&P::from_coefficients_slice(blinding) * &v_h)
+}
+
+/// `p(omega X)`
+fn shift(p: &P, omega: Fr) -> P {
+    let mut pow = Fr::one();
+    P::from_coefficients_vec(
+        p.coeffs
+            .iter()
+            .map(|c| {
+                let r = *c * pow;
+                pow *= omega;
+                r
+            })
+            .collect(),
+    )
+}
+
+/// Split `t` into three pieces of `n + 2` coefficients
+fn split_quotient(t: &P, n: usize) -> [P; 3] {
+    let piece = |i: usize| {
+        let lo = (i * (n + 2)).min(t.coeffs.len());
+        let hi = ((i + 1) * (n + 2)).min(t.coeffs.len());
+        P::from_coefficients_slice(&t.coeffs[lo..hi])
+    };
+    [piece(0), piece(1), piece(2)]
+}
+
+/// The domain for the quotient, with the points of its coset, and `1 / Z_H` over them
+fn quotient_domain(domain: D) -> (D, Vec<Fr>, Vec<Fr>) {
+    let domain_t = D::new(8 * domain.size()).unwrap();
+    let g = Fr::multiplicative_generator();
+    let points: Vec<Fr> = domain_t.elements().map(|x| g * x).collect();
+    let mut v_h_inv: Vec<Fr> = points
+        .iter()
+        .map(|x| domain.evaluate_vanishing_polynomial(*x))
+        .collect();
+    ark_ff::batch_inversion(&mut v_h_inv);
+    (domain_t, points, v_h_inv)
+}
+
+/// `PI(X)`, which is `-x_i` on the `i`th public input gate
+fn public_input_poly(public_inputs: &[Fr], domain: D) -> P {
+    let mut evals = vec![Fr::zero(); domain.size()];
+    for (e, x) in evals.iter_mut().zip(public_inputs) {
+        *e = -*x;
+    }
+    interpolate(evals, domain)
+}
+
+/// `L_1(X)`, which is one at the first point of the domain
+fn first_lagrange_poly(domain: D) -> P {
+    let mut evals = vec![Fr::zero(); domain.size()];
+    evals[0] = Fr::one();
+    interpolate(evals, domain)
+}
+
+fn transcript(vk: &VerifierKey, public_inputs: &[Fr]) -> FiatShamirRng<Blake2s> {
+    FiatShamirRng::from_seed(&to_bytes![PROTOCOL_NAME, vk.comms, public_inputs.to_vec()].unwrap())
+}
+
+fn query_set(zeta: Fr, omega: Fr) -> QuerySet<Fr> {
+    let mut query_set: QuerySet<Fr> = INDEX_POLYNOMIALS
+        .iter()
+        .chain(&PROVER_POLYNOMIALS)
+        .map(|l| (l.to_string(), ("zeta".to_string(), zeta)))
+        .collect();
+    query_set.insert(("z".to_string(), ("zeta_omega".to_string(), zeta * omega)));
+    query_set
+}
+
+/// The quotient identity, at `zeta`, given the evaluations in the query set
+fn quotient_identity_holds(
+    evals: &Evaluations<Fr, Fr>,
+    zeta: Fr,
+    omega: Fr,
+    [beta, gamma, alpha]: [Fr; 3],
+    public_inputs: &[Fr],
+    domain: D,
+) -> bool {
+    let n = domain.size();
+    let e = |l: &str| evals[&(l.to_string(), zeta)];
+    let z_omega = evals[&("z".to_string(), zeta * omega)];
+    let [k_1, k_2, k_3] = coset_shifts();
+    let lagranges = domain.evaluate_all_lagrange_coefficients(zeta);
+    let pi: Fr = lagranges
+        .iter()
+        .zip(public_inputs)
+        .map(|(l, x)| -*l * x)
+        .sum();
+    let gate = e("q_l") * e("a")
+        + e("q_r") * e("b")
+        + e("q_o") * e("c")
+        + e("q_m") * e("a") * e("b")
+        + e("q_c")
+        + pi;
+    let perm = (e("a") + beta * k_1 * zeta + gamma)
+        * (e("b") + beta * k_2 * zeta + gamma)
+        * (e("c") + beta * k_3 * zeta + gamma)
+        * e("z")
+        - (e("a") + beta * e("s_1") + gamma)
+            * (e("b") + beta * e("s_2") + gamma)
+            * (e("c") + beta * e("s_3") + gamma)
+            * z_omega;
+    let l_1 = (e("z") - Fr::one()) * lagranges[0];
+    let zeta_n2 = zeta.pow([n as u64 + 2]);
+    let t = e("t_lo") + zeta_n2 * e("t_mid") + zeta_n2.square() * e("t_hi");
+    gate + alpha * perm + alpha.square() * l_1 == t * domain.evaluate_vanishing_polynomial(zeta)
+}
+
+/// Parameters for circuits of up to `max_gates` gates
+pub fn setup<R: RngCore>(max_gates: usize, rng: &mut R) -> UniversalParams {
+    let n = D::compute_size_of_domain(max_gates).unwrap();
+    PC::setup(n + 2, None, rng).unwrap()
+}
+
+/// Preprocess a circuit: its selectors and permutation
+pub fn index(pp: &UniversalParams, circuit: &Circuit) -> (ProverKey, VerifierKey) {
+    let index_time = start_timer!(|| "PLONK::Index");
+    let domain = D::new(circuit.wires.len()).unwrap();
+    let n = domain.size();
+    // Unused gates are all zero, and their wires hold variable 0.
+    let mut wires = circuit.wires.clone();
+    wires.resize(n, [0; 3]);
+    let mut selectors = circuit.selectors.clone();
+    selectors.resize(n, [Fr::zero(); 5]);
+
+    // Cycle each variable through the wires that hold it
+    let shifts = coset_shifts();
+    let omegas: Vec<Fr> = domain.elements().collect();
+    let mut positions = vec![Vec::new(); circuit.num_variables.max(1)];
+    for (i, ws) in wires.iter().enumerate() {
+        for (j, w) in ws.iter().enumerate() {
+            positions[*w].push((j, i));
+        }
+    }
+    let mut sigma_evals = [
+        vec![Fr::zero(); n],
+        vec![Fr::zero(); n],
+        vec![Fr::zero(); n],
+    ];
+    for ps in &positions {
+        for (k, &(j, i)) in ps.iter().enumerate() {
+            let (j_next, i_next) = ps[(k + 1) % ps.len()];
+            sigma_evals[j][i] = shifts[j_next] * omegas[i_next];
+        }
+    }
+
+    let mut polys = Vec::new();
+    for (s, label) in INDEX_POLYNOMIALS[..5].iter().enumerate() {
+        let evals = selectors.iter().map(|q| q[s]).collect();
+        polys.push(LabeledPolynomial::new(
+            label.to_string(),
+            interpolate(evals, domain),
+            None,
+            None,
+        ));
+    }
+    for (evals, label) in sigma_evals.iter().zip(&INDEX_POLYNOMIALS[5..]) {
+        polys.push(LabeledPolynomial::new(
+            label.to_string(),
+            interpolate(evals.clone(), domain),
+            None,
+            None,
+        ));
+    }
+
+    let (ck, pc_vk) = PC::trim(pp, n + 2, 0, None).unwrap();
+    let (comms, _) = PC::commit(&ck, &polys, None).unwrap();
+    let vk = VerifierKey {
+        n,
+        num_public_inputs: circuit.num_public_inputs,
+        comms,
+        pc_vk,
+    };
+    let pk = ProverKey {
+        vk: VerifierKey {
+            n,
+            num_public_inputs: circuit.num_public_inputs,
+            comms: vk.comms.clone(),
+            pc_vk: vk.pc_vk.clone(),
+        },
+        ck,
+        wires,
+        polys,
+        sigma_evals,
+    };
+    end_timer!(index_time);
+    (pk, vk)
+}
+
+fn labeled(label: &str, p: P) -> LabeledPolynomial<Fr, P> {
+    LabeledPolynomial::new(label.to_string(), p, None, None)
+}
+
+/// Prove that `witness` (an assignment to the circuit's variables) satisfies the circuit
+pub fn prove<R: RngCore>(pk: &ProverKey, witness: &[Fr], rng: &mut R) -> Proof {
+    let prover_time = start_timer!(|| "PLONK::Prover");
+    let domain = D::new(pk.vk.n).unwrap();
+    let n = domain.size();
+    let omega = domain.element(1);
+    let public_inputs = &witness[..pk.vk.num_public_inputs];
+    let mut fs_rng = transcript(&pk.vk, public_inputs);
+
+    // Round 1: wires
+    let wire_evals: Vec<Vec<Fr>> = (0..3)
+        .map(|j| pk.wires.iter().map(|w| witness[w[j]]).collect())
+        .collect();
+    let wire_polys: Vec<P> = wire_evals
+        .iter()
+        .map(|e| blind(e.clone(), &[Fr::rand(rng), Fr::rand(rng)], domain))
+        .collect();
+    let wires: Vec<_> = wire_polys
+        .iter()
+        .zip(&PROVER_POLYNOMIALS)
+        .map(|(p, l)| labeled(l, p.clone()))
+        .collect();
+    let (wire_comms, _) = PC::commit(&pk.ck, &wires, None).unwrap();
+    fs_rng.absorb(&to_bytes![wire_comms].unwrap());
+    let beta = Fr::rand(&mut fs_rng);
+    let gamma = Fr::rand(&mut fs_rng);
+
+    // Round 2: the permutation's grand product
+    let shifts = coset_shifts();
+    let mut z_evals = vec![Fr::one()];
+    for (i, x) in domain.elements().enumerate().take(n - 1) {
+        let mut num = Fr::one();
+        let mut den = Fr::one();
+        for j in 0..3 {
+            num *= wire_evals[j][i] + beta * shifts[j] * x + gamma;
+            den *= wire_evals[j][i] + beta * pk.sigma_evals[j][i] + gamma;
+        }
+        let last = *z_evals.last().unwrap();
+        z_evals.push(last * num * den.inverse().unwrap());
+    }
+    let z_poly = blind(
+        z_evals,
+        &[Fr::rand(rng), Fr::rand(rng), Fr::rand(rng)],
+        domain,
+    );
+    let z = labeled("z", z_poly.clone());
+    let (z_comms, _) = PC::commit(&pk.ck, vec![&z], None).unwrap();
+    fs_rng.absorb(&to_bytes![z_comms].unwrap());
+    let alpha = Fr::rand(&mut fs_rng);
+
+    // Round 3: the quotient
+    let (domain_t, points, v_h_inv) = quotient_domain(domain);
+    let evals = |p: &P| domain_t.coset_fft(&p.coeffs);
+    let [a, b, c] = [
+        evals(&wire_polys[0]),
+        evals(&wire_polys[1]),
+        evals(&wire_polys[2]),
+    ];
+    let [q_l, q_r, q_o, q_m, q_c, s_1, s_2, s_3] =
+        [0, 1, 2, 3, 4, 5, 6, 7].map(|i| evals(pk.polys[i].polynomial()));
+    let z_e = evals(&z_poly);
+    let z_omega = evals(&shift(&z_poly, omega));
+    let pi = evals(&public_input_poly(public_inputs, domain));
+    let l_1 = evals(&first_lagrange_poly(domain));
+    let t_evals = (0..domain_t.size())
+        .map(|i| {
+            let x = points[i];
+            let gate = q_l[i] * a[i]
+                + q_r[i] * b[i]
+                + q_o[i] * c[i]
+                + q_m[i] * a[i] * b[i]
+                + q_c[i]
+                + pi[i];
+            let perm = (a[i] + beta * shifts[0] * x + gamma)
+                * (b[i] + beta * shifts[1] * x + gamma)
+                * (c[i] + beta * shifts[2] * x + gamma)
+                * z_e[i]
+                - (a[i] + beta * s_1[i] + gamma)
+                    * (b[i] + beta * s_2[i] + gamma)
+                    * (c[i] + beta * s_3[i] + gamma)
+                    * z_omega[i];
+            let l = (z_e[i] - Fr::one()) * l_1[i];
+            (gate + alpha * perm + alpha.square() * l) * v_h_inv[i]
+        })
+        .collect::<Vec<_>>();
+    let t_poly = P::from_coefficients_vec(domain_t.coset_ifft(&t_evals));
+    assert!(t_poly.degree() < 3 * (n + 2));
+    let t: Vec<_> = split_quotient(&t_poly, n)
+        .iter()
+        .zip(&PROVER_POLYNOMIALS[4..])
+        .map(|(p, l)| labeled(l, p.clone()))
+        .collect();
+    let (t_comms, _) = PC::commit(&pk.ck, &t, None).unwrap();
+    fs_rng.absorb(&to_bytes![t_comms].unwrap());
+    let zeta = Fr::rand(&mut fs_rng);
+
+    // Round 4: openings
+    let polys: Vec<_> = pk
+        .polys
+        .iter()
+        .chain(&wires)
+        .chain(vec![&z])
+        .chain(&t)
+        .collect();
+    let comms: Vec<_> = pk
+        .vk
+        .comms
+        .iter()
+        .chain(&wire_comms)
+        .chain(&z_comms)
+        .chain(&t_comms)
+        .cloned()
+        .collect();
+    let query_set = query_set(zeta, omega);
+    let evaluations: Vec<Fr> = query_set
+        .iter()
+        .map(|(l, (_, point))| {
+            polys
+                .iter()
+                .find(|p| p.label() == l)
+                .unwrap()
+                .evaluate(point)
+        })
+        .collect();
+    fs_rng.absorb(&evaluations);
+    let v = Fr::rand(&mut fs_rng);
+    let rands = vec![<PC as PolynomialCommitment<Fr, P>>::Randomness::empty(); polys.len()];
+    let pc_proof = PC::batch_open(&pk.ck, polys, &comms, &query_set, v, &rands, None).unwrap();
+    end_timer!(prover_time);
+    Proof {
+        commitments: comms[8..].iter().map(|c| *c.commitment()).collect(),
+        evaluations,
+        pc_proof,
+    }
+}
+
+pub fn verify<R: RngCore>(
+    vk: &VerifierKey,
+    public_inputs: &[Fr],
+    proof: &Proof,
+    rng: &mut R,
+) -> bool {
+    let verifier_time = start_timer!(|| "PLONK::Verify");
+    let domain = D::new(vk.n).unwrap();
+    let omega = domain.element(1);
+    if public_inputs.len() != vk.num_public_inputs
+        || proof.commitments.len() != PROVER_POLYNOMIALS.len()
+    {
+        return false;
+    }
+    let mut fs_rng = transcript(vk, public_inputs);
+    fs_rng.absorb(&to_bytes![proof.commitments[..3].to_vec()].unwrap());
+    let beta = Fr::rand(&mut fs_rng);
+    let gamma = Fr::rand(&mut fs_rng);
+    fs_rng.absorb(&to_bytes![proof.commitments[3..4].to_vec()].unwrap());
+    let alpha = Fr::rand(&mut fs_rng);
+    fs_rng.absorb(&to_bytes![proof.commitments[4..].to_vec()].unwrap());
+    let zeta = Fr::rand(&mut fs_rng);
+
+    let query_set = query_set(zeta, omega);
+    if query_set.len() != proof.evaluations.len() {
+        return false;
+    }
+    let evaluations: Evaluations<Fr, Fr> = query_set
+        .iter()
+        .map(|(l, (_, point))| (l.clone(), *point))
+        .zip(proof.evaluations.iter().cloned())
+        .collect();
+    if !quotient_identity_holds(
+        &evaluations,
+        zeta,
+        omega,
+        [beta, gamma, alpha],
+        public_inputs,
+        domain,
+    ) {
+        return false;
+    }
+    fs_rng.absorb(&proof.evaluations);
+    let v = Fr::rand(&mut fs_rng);
+    let comms: Vec<_> = vk
+        .comms
+        .iter()
+        .cloned()
+        .chain(
+            proof
+                .commitments
+                .iter()
+                .zip(&PROVER_POLYNOMIALS)
+                .map(|(c, l)| LabeledCommitment::new(l.to_string(), *c, None)),
+        )
+        .collect();
+    let r = PC::batch_check(
+        &vk.pc_vk,
+        &comms,
+        &query_set,
+        &evaluations,
+        &proof.pc_proof,
+        v,
+        rng,
+    )
+    .unwrap_or(false);
+    end_timer!(verifier_time);
+    r
+}
+
+/// `a * b = c`, and `d = a + b`, with `c` public
+fn silly_circuit() -> Circuit {
+    let mut circ = Circuit::new(1);
+    let (a, b, d) = (
+        circ.new_variable(),
+        circ.new_variable(),
+        circ.new_variable(),
+    );
+    circ.mul(a, b, 0);
+    circ.add(a, b, d);
+    circ
+}
+
+pub fn local_test_prove_and_verify(n_iters: usize) {
+    let rng = &mut test_rng();
+    let circ = silly_circuit();
+    let pp = setup(circ.wires.len(), rng);
+    let (pk, vk) = index(&pp, &circ);
+    for _ in 0..n_iters {
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let proof = prove(&pk, &[a * b, a, b, a + b], rng);
+        assert!(verify(&vk, &[a * b], &proof, rng));
+        assert!(!verify(&vk, &[a], &proof, rng));
+    }
+}
+
+pub fn mpc_test_prove_and_verify(n_iters: usize) {
+    let rng = &mut test_rng();
+    let circ = silly_circuit();
+    let pp = prover::setup(circ.wires.len(), &mut party_rng());
+    let (pk, vk) = index(&pp, &circ);
+    for _ in 0..n_iters {
+        let a = MpcVal::<Fr>::rand(rng);
+        let b = MpcVal::<Fr>::rand(rng);
+        let c = a * b;
+        let proof = prover::prove(&pk, &[c, a, b, a + b], rng);
+        let pub_a = a.publicize_unwrap();
+        let pub_c = c.publicize_unwrap();
+        assert!(verify(&vk, &[pub_c], &proof, rng));
+        assert!(!verify(&vk, &[pub_a], &proof, rng));
+    }
+}
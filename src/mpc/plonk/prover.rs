@@ -0,0 +1,273 @@
+use super::super::poly::pc::{publicize_vals, MpcPolyCommit};
+use super::*;
+use std::convert::TryInto;
+
+// Changelog:
+// 1. Copied from `super::prove`. The witness is shared; the public inputs are opened.
+// 2. The wire, grand-product and quotient polynomials are shared (each party holds an additive
+//    share of the coefficients), and committed to with `MpcPolyCommit`; the commitments (and so
+//    the Fiat-Shamir challenges) are public.
+// 3. Blinding scalars come from `channel::field_rands`.
+// 4. Public constants are only added by the first party (`MpcVal` arithmetic does this).
+// 5. The grand product takes one inversion, and a log-depth prefix product, over the domain.
+// 6. The quotient's products are computed over the coset, in two batched multiplication rounds.
+// 7. Evaluations of shared polynomials are opened in one batch.
+
+type MpcPC = MpcPolyCommit<Fr, P, PC>;
+type SharedPolynomial = LabeledPolynomial<MpcVal<Fr>, MpcVal<P>>;
+
+/// Our share of `x`; public values are held by the first party
+fn share(x: MpcVal<Fr>) -> Fr {
+    if x.shared || channel::am_first() {
+        x.val
+    } else {
+        Fr::zero()
+    }
+}
+
+fn shared_poly(label: &str, poly: P) -> SharedPolynomial {
+    LabeledPolynomial::new(label.to_string(), MpcVal::from_shared(poly), None, None)
+}
+
+/// Share a public polynomial: the first party holds all of it
+fn share_public_poly(p: &LabeledPolynomial<Fr, P>) -> SharedPolynomial {
+    let poly = if channel::am_first() {
+        p.polynomial().clone()
+    } else {
+        P::zero()
+    };
+    shared_poly(p.label(), poly)
+}
+
+/// Shares of the evaluations of a shared polynomial over the coset of `domain`
+fn coset_evals(p: &P, domain: D) -> Vec<MpcVal<Fr>> {
+    domain
+        .coset_fft(&p.coeffs)
+        .into_iter()
+        .map(MpcVal::from_shared)
+        .collect()
+}
+
+/// The shared blinding `(r_0 + r_1 X + ...) Z_H` added to the interpolation of `evals`
+fn blind_shared(evals: Vec<MpcVal<Fr>>, n_blinding: usize, domain: D) -> P {
+    let blinding: Vec<Fr> = channel::field_rands(n_blinding)
+        .into_iter()
+        .map(share)
+        .collect();
+    blind(evals.into_iter().map(share).collect(), &blinding, domain)
+}
+
+/// Prefix products `[1, x_0, x_0 x_1, ...]`, without the full product, in `log n` rounds
+fn prefix_products(xs: Vec<MpcVal<Fr>>) -> Vec<MpcVal<Fr>> {
+    let mut prods = xs;
+    let mut step = 1;
+    while step < prods.len() {
+        let (a, b): (Vec<_>, Vec<_>) = (step..prods.len())
+            .map(|i| (prods[i], prods[i - step]))
+            .unzip();
+        let products = channel::field_batch_mul(a, b);
+        prods[step..].copy_from_slice(&products);
+        step *= 2;
+    }
+    prods.pop();
+    prods.insert(0, MpcVal::from_public(Fr::one()));
+    prods
+}
+
+/// Parameters for circuits of up to `max_gates` gates, generated in MPC (see
+/// `MpcPolyCommit::setup`)
+pub fn setup<R: RngCore>(max_gates: usize, rng: &mut R) -> UniversalParams {
+    let n = D::compute_size_of_domain(max_gates).unwrap();
+    MpcPC::setup(n + 2, None, rng).unwrap()
+}
+
+/// Prove that `witness` (a shared assignment to the circuit's variables) satisfies the circuit
+pub fn prove<R: RngCore>(pk: &ProverKey, witness: &[MpcVal<Fr>], rng: &mut R) -> Proof {
+    let prover_time = start_timer!(|| "PLONK::Prover");
+    let domain = D::new(pk.vk.n).unwrap();
+    let n = domain.size();
+    let omega = domain.element(1);
+    let public_inputs = publicize_vals(witness[..pk.vk.num_public_inputs].to_vec());
+    let mut fs_rng = transcript(&pk.vk, &public_inputs);
+
+    // Round 1: wires
+    let wire_evals: Vec<Vec<MpcVal<Fr>>> = (0..3)
+        .map(|j| pk.wires.iter().map(|w| witness[w[j]]).collect())
+        .collect();
+    let wire_polys: Vec<P> = wire_evals
+        .iter()
+        .map(|e| blind_shared(e.clone(), 2, domain))
+        .collect();
+    let wires: Vec<_> = wire_polys
+        .iter()
+        .zip(&PROVER_POLYNOMIALS)
+        .map(|(p, l)| shared_poly(l, p.clone()))
+        .collect();
+    let (wire_comms, wire_rands) = MpcPC::commit(&pk.ck, &wires, None).unwrap();
+    fs_rng.absorb(&to_bytes![wire_comms].unwrap());
+    let beta = Fr::rand(&mut fs_rng);
+    let gamma = Fr::rand(&mut fs_rng);
+
+    // Round 2: the permutation's grand product
+    let shifts = coset_shifts();
+    let pub_val = MpcVal::<Fr>::from_public;
+    let (mut num, mut den): (Vec<Vec<_>>, Vec<Vec<_>>) = (0..3)
+        .map(|j| {
+            domain
+                .elements()
+                .enumerate()
+                .map(|(i, x)| {
+                    (
+                        wire_evals[j][i] + pub_val(beta * shifts[j] * x + gamma),
+                        wire_evals[j][i] + pub_val(beta * pk.sigma_evals[j][i] + gamma),
+                    )
+                })
+                .unzip()
+        })
+        .unzip();
+    let mut products = channel::field_batch_mul(
+        [num[0].clone(), den[0].clone()].concat(),
+        [num[1].clone(), den[1].clone()].concat(),
+    );
+    products =
+        channel::field_batch_mul(products, [num.pop().unwrap(), den.pop().unwrap()].concat());
+    let den_invs = channel::field_batch_inv(products.split_off(n))
+        .into_iter()
+        .map(|x| x.expect("PLONK: zero in the grand product"))
+        .collect();
+    let ratios = channel::field_batch_mul(products, den_invs);
+    let z_poly = blind_shared(prefix_products(ratios), 3, domain);
+    let z = shared_poly("z", z_poly.clone());
+    let (z_comms, z_rands) = MpcPC::commit(&pk.ck, vec![&z], None).unwrap();
+    fs_rng.absorb(&to_bytes![z_comms].unwrap());
+    let alpha = Fr::rand(&mut fs_rng);
+
+    // Round 3: the quotient
+    let (domain_t, points, v_h_inv) = quotient_domain(domain);
+    let pub_evals = |p: &P| domain_t.coset_fft(&p.coeffs);
+    let [a, b, c] = [0, 1, 2].map(|j| coset_evals(&wire_polys[j], domain_t));
+    let [q_l, q_r, q_o, q_m, q_c, s_1, s_2, s_3] =
+        [0, 1, 2, 3, 4, 5, 6, 7].map(|i| pub_evals(pk.polys[i].polynomial()));
+    let z_e = coset_evals(&z_poly, domain_t);
+    let z_omega = coset_evals(&shift(&z_poly, omega), domain_t);
+    let pi = pub_evals(&public_input_poly(&public_inputs, domain));
+    let l_1 = pub_evals(&first_lagrange_poly(domain));
+    let m = domain_t.size();
+    let num_term = |j: usize, w: &[MpcVal<Fr>]| -> Vec<_> {
+        (0..m)
+            .map(|i| w[i] + pub_val(beta * shifts[j] * points[i] + gamma))
+            .collect()
+    };
+    let den_term = |w: &[MpcVal<Fr>], s: &[Fr]| -> Vec<_> {
+        (0..m)
+            .map(|i| w[i] + pub_val(beta * s[i] + gamma))
+            .collect()
+    };
+    let round_a = channel::field_batch_mul(
+        [
+            a.clone(),
+            num_term(0, &a),
+            num_term(2, &c),
+            den_term(&a, &s_1),
+            den_term(&c, &s_3),
+        ]
+        .concat(),
+        [
+            b.clone(),
+            num_term(1, &b),
+            z_e.clone(),
+            den_term(&b, &s_2),
+            z_omega,
+        ]
+        .concat(),
+    );
+    let [ab, num_12, num_3z, den_12, den_3z]: [Vec<_>; 5] = round_a
+        .chunks(m)
+        .map(|c| c.to_vec())
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    let mut round_b =
+        channel::field_batch_mul([num_12, den_12].concat(), [num_3z, den_3z].concat());
+    let den_prod = round_b.split_off(m);
+    let num_prod = round_b;
+    let t_evals: Vec<Fr> = (0..m)
+        .map(|i| {
+            let gate = a[i] * pub_val(q_l[i])
+                + b[i] * pub_val(q_r[i])
+                + c[i] * pub_val(q_o[i])
+                + ab[i] * pub_val(q_m[i])
+                + pub_val(q_c[i] + pi[i]);
+            let perm = num_prod[i] - den_prod[i];
+            let l = (z_e[i] - pub_val(Fr::one())) * pub_val(l_1[i]);
+            share(
+                (gate + perm * pub_val(alpha) + l * pub_val(alpha.square())) * pub_val(v_h_inv[i]),
+            )
+        })
+        .collect();
+    let t_poly = P::from_coefficients_vec(domain_t.coset_ifft(&t_evals));
+    let t: Vec<_> = split_quotient(&t_poly, n)
+        .iter()
+        .zip(&PROVER_POLYNOMIALS[4..])
+        .map(|(p, l)| shared_poly(l, p.clone()))
+        .collect();
+    let (t_comms, t_rands) = MpcPC::commit(&pk.ck, &t, None).unwrap();
+    fs_rng.absorb(&to_bytes![t_comms].unwrap());
+    let zeta = Fr::rand(&mut fs_rng);
+
+    // Round 4: openings
+    let index_polys: Vec<_> = pk.polys.iter().map(share_public_poly).collect();
+    let polys: Vec<_> = index_polys
+        .iter()
+        .chain(&wires)
+        .chain(vec![&z])
+        .chain(&t)
+        .collect();
+    let comms: Vec<_> = pk
+        .vk
+        .comms
+        .iter()
+        .chain(&wire_comms)
+        .chain(&z_comms)
+        .chain(&t_comms)
+        .cloned()
+        .collect();
+    let query_set = query_set(zeta, omega);
+    let evaluations = publicize_vals(
+        query_set
+            .iter()
+            .map(|(l, (_, point))| {
+                let p = polys.iter().find(|p| p.label() == l).unwrap();
+                MpcVal::from_shared(p.polynomial().val.evaluate(point))
+            })
+            .collect(),
+    );
+    fs_rng.absorb(&evaluations);
+    let v = Fr::rand(&mut fs_rng);
+    let rands: Vec<_> = vec![MpcVal::from_shared(PCRandomness::empty()); index_polys.len()]
+        .into_iter()
+        .chain(wire_rands)
+        .chain(z_rands)
+        .chain(t_rands)
+        .collect();
+    let mpc_query_set: QuerySet<MpcVal<Fr>> = query_set
+        .into_iter()
+        .map(|(l, (pl, point))| (l, (pl, pub_val(point))))
+        .collect();
+    let pc_proof = MpcPC::batch_open(
+        &pk.ck,
+        polys,
+        &comms,
+        &mpc_query_set,
+        pub_val(v),
+        &rands,
+        Some(rng),
+    )
+    .unwrap();
+    end_timer!(prover_time);
+    Proof {
+        commitments: comms[8..].iter().map(|c| *c.commitment()).collect(),
+        evaluations,
+        pc_proof,
+    }
+}
@@ -2,8 +2,12 @@ use ark_poly::{univariate, Polynomial, UVPolynomial};
 
 use super::*;
 
+pub mod dense;
+pub mod marlin_pc;
 pub mod pc;
 
+use dense::MpcDensePolynomial;
+
 macro_rules! impl_poly {
     ($field:ty, $poly:ty) => {
         impl<'a> std::ops::AddAssign<(MpcVal<$field>, &'a MpcVal<$poly>)> for MpcVal<$poly> {
@@ -12,7 +16,11 @@ macro_rules! impl_poly {
                     (false, false, false) => {
                         self.val += (scalar.val, &f.val);
                     }
-                    _ => unimplemented!("Shared poly scaled sum"),
+                    _ => {
+                        let mut sum = MpcDensePolynomial::from(self.clone());
+                        sum += (scalar, &MpcDensePolynomial::from(f.clone()));
+                        *self = sum.into();
+                    }
                 }
             }
         }
@@ -23,8 +31,11 @@ macro_rules! impl_poly {
                 self.val.degree()
             }
             fn evaluate(&self, p: &Self::Point) -> MpcVal<$field> {
-                assert!(!p.shared, "unimplemented: evaluation at shared points");
-                MpcVal::new(self.val.evaluate(&p.val), self.shared)
+                if p.shared {
+                    MpcDensePolynomial::from(self.clone()).evaluate(p)
+                } else {
+                    MpcVal::new(self.val.evaluate(&p.val), self.shared)
+                }
             }
         }
     };
@@ -32,17 +43,13 @@ macro_rules! impl_poly {
 
 macro_rules! impl_uv_poly {
     ($field:ty, $poly:ty) => {
-        impl UVPolynomial<MpcVal<$field>> for MpcVal<$poly> {
-            fn from_coefficients_slice(s: &[MpcVal<$field>]) -> Self {
-                assert!(s.len() > 0);
-                let first_shared = s[0].shared;
-                assert!(s.iter().all(|x| x.shared == first_shared));
-                MpcVal::new(
-                    <$poly>::from_coefficients_vec(s.iter().map(|x| x.val).collect()),
-                    first_shared,
-                )
+        /// Not `UVPolynomial`: the shares of the coefficients are not stored as `MpcVal`s, so
+        /// there is no `coeffs`. Generic code takes `MpcDensePolynomial` instead.
+        impl MpcVal<$poly> {
+            pub fn from_coefficients_slice(s: &[MpcVal<$field>]) -> Self {
+                Self::from_coefficients_vec(s.to_vec())
             }
-            fn from_coefficients_vec(s: Vec<MpcVal<$field>>) -> Self {
+            pub fn from_coefficients_vec(s: Vec<MpcVal<$field>>) -> Self {
                 assert!(s.len() > 0);
                 let first_shared = s[0].shared;
                 assert!(s.iter().all(|x| x.shared == first_shared));
@@ -51,26 +58,33 @@ macro_rules! impl_uv_poly {
                     first_shared,
                 )
             }
-            fn coeffs(&self) -> &[MpcVal<$field>] {
-                // hard because we don't have that in memory. Leak? New wrapper?
-                unimplemented!("{} UVPolynomial::coeffs", std::stringify!($field))
-            }
-            fn rand<R>(d: usize, r: &mut R) -> Self
-            where
-                R: rand::Rng,
-            {
+            pub fn rand<R: rand::Rng>(d: usize, r: &mut R) -> Self {
                 MpcVal::from_shared(<$poly>::rand(d, r))
             }
         }
         impl<'a, 'b> std::ops::Div<&'b MpcVal<$poly>> for &'a MpcVal<$poly> {
             type Output = MpcVal<$poly>;
             fn div(self, other: &MpcVal<$poly>) -> MpcVal<$poly> {
-                assert!(!other.shared);
+                if other.shared {
+                    let self_p = MpcDensePolynomial::from(self.clone());
+                    return (&self_p / &MpcDensePolynomial::from(other.clone())).into();
+                }
                 let self_p = <$poly>::from_coefficients_slice(self.val.coeffs());
                 let other_p = <$poly>::from_coefficients_slice(other.val.coeffs());
                 MpcVal::new(&self_p / &other_p, self.shared)
             }
         }
+        /// By FFT: one batch of multiplications if both are shared.
+        impl<'a, 'b> std::ops::Mul<&'b MpcVal<$poly>> for &'a MpcVal<$poly> {
+            type Output = MpcVal<$poly>;
+            fn mul(self, other: &MpcVal<$poly>) -> MpcVal<$poly> {
+                if !self.shared || !other.shared {
+                    return MpcVal::new(&self.val * &other.val, self.shared || other.shared);
+                }
+                let self_p = MpcDensePolynomial::from(self.clone());
+                (&self_p * &MpcDensePolynomial::from(other.clone())).into()
+            }
+        }
     };
 }
 
@@ -83,3 +97,7 @@ impl_uv_poly!(
     ark_bls12_377::Fr,
     univariate::DensePolynomial<ark_bls12_377::Fr>
 );
+
+impl_poly!(ark_bn254::Fr, univariate::DensePolynomial<ark_bn254::Fr>);
+
+impl_uv_poly!(ark_bn254::Fr, univariate::DensePolynomial<ark_bn254::Fr>);
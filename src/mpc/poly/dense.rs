@@ -0,0 +1,316 @@
+use super::super::*;
+use super::super::domain::{shares, MpcDomain};
+
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{Polynomial, UVPolynomial};
+
+/// A univariate polynomial whose coefficients are `MpcVal`s, so each can be shared or public.
+///
+/// Unlike `MpcVal<DensePolynomial<F>>`, this has the `[MpcVal<F>]` for `UVPolynomial::coeffs`.
+/// Leading coefficients are only dropped when they are public zeros: the degree of a shared
+/// polynomial is its length, less one, and dividing by it needs its leading coefficient to be
+/// nonzero.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MpcDensePolynomial<F: Field> {
+    pub coeffs: Vec<MpcVal<F>>,
+}
+
+/// Give `v` one sharing: if any entry is shared, they all are.
+fn share_all<F: Field>(v: &[MpcVal<F>]) -> Vec<MpcVal<F>> {
+    let (vals, shared) = shares(v);
+    vals.into_iter().map(|x| MpcVal::new(x, shared)).collect()
+}
+
+/// Multiply coefficient vectors over a domain large enough for the product: shared by shared is
+/// one batch of multiplications, anything else is local.
+fn mul_coeffs<F: FftField>(a: &[MpcVal<F>], b: &[MpcVal<F>]) -> Vec<MpcVal<F>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let n = a.len() + b.len() - 1;
+    let domain = MpcDomain::new(n).expect("no domain for the product");
+    let mut evals = [a.to_vec(), b.to_vec()];
+    domain.batch_fft_in_place(&mut evals);
+    let [a, b] = evals;
+    let mut products = [channel::field_batch_mul(a, b)];
+    domain.batch_ifft_in_place(&mut products);
+    let [mut product] = products;
+    product.truncate(n);
+    product
+}
+
+/// `1 / b mod X^k`, for `b(0) != 0`, by Newton iteration: `g <- g (2 - b g) mod X^2i`. Two
+/// rounds of multiplication per doubling.
+fn inverse_mod_xk<F: FftField>(b: &[MpcVal<F>], k: usize) -> Vec<MpcVal<F>> {
+    let b0_inv = if b[0].shared {
+        channel::field_inv(b[0])
+    } else {
+        b[0].val.inverse().map(MpcVal::from_public)
+    };
+    let mut g = vec![b0_inv.expect("divisor's leading coefficient is zero")];
+    while g.len() < k {
+        let len = std::cmp::min(2 * g.len(), k);
+        let mut t = mul_coeffs(&b[..std::cmp::min(len, b.len())], &g);
+        t.resize(len, MpcVal::zero());
+        for t in &mut t {
+            *t = -*t;
+        }
+        t[0] += MpcVal::from_public(F::one() + F::one());
+        g = mul_coeffs(&g, &t);
+        g.truncate(len);
+    }
+    g
+}
+
+impl<F: Field> MpcDensePolynomial<F> {
+    /// The polynomial with these coefficients, without its leading public zeros.
+    pub fn new(coeffs: Vec<MpcVal<F>>) -> Self {
+        let mut p = Self { coeffs };
+        p.truncate_leading_zeros();
+        p
+    }
+
+    fn truncate_leading_zeros(&mut self) {
+        while self.coeffs.last().is_some_and(|c| !c.shared && c.val.is_zero()) {
+            self.coeffs.pop();
+        }
+    }
+
+    /// Whether each coefficient is public.
+    pub fn is_public(&self) -> bool {
+        self.coeffs.iter().all(|c| !c.shared)
+    }
+
+    /// Multiply by a scalar: a shared scalar times shared coefficients is one batch of
+    /// multiplications.
+    pub fn scale(&self, s: MpcVal<F>) -> Self {
+        let coeffs = if s.shared && !self.is_public() {
+            let n = self.coeffs.len();
+            channel::field_batch_mul(vec![s; n], share_all(&self.coeffs))
+        } else {
+            self.coeffs.iter().map(|c| *c * s).collect()
+        };
+        Self { coeffs }
+    }
+
+    /// Open the coefficients, in one exchange.
+    pub fn publicize(&self) -> DensePolynomial<F> {
+        let (vals, shared) = shares(&self.coeffs);
+        let vals = if shared {
+            channel::field_batch_publicize(vals.into_iter().map(MpcVal::from_shared).collect())
+                .into_iter()
+                .map(|x| x.val)
+                .collect()
+        } else {
+            vals
+        };
+        DensePolynomial::from_coefficients_vec(vals)
+    }
+}
+
+impl<F: FftField> MpcDensePolynomial<F> {
+    /// Divide by `divisor`, giving the quotient and remainder.
+    ///
+    /// A public divisor is long division, with no communication. A shared divisor inverts its
+    /// reversal mod `X^(deg q + 1)` by Newton iteration, so it takes `O(log deg q)` rounds, and
+    /// its leading coefficient must be nonzero.
+    pub fn divide_with_q_and_r(&self, divisor: &Self) -> (Self, Self) {
+        let mut d = divisor.clone();
+        d.truncate_leading_zeros();
+        assert!(!d.coeffs.is_empty(), "division by zero polynomial");
+        if self.coeffs.len() < d.coeffs.len() {
+            return (Self::zero(), self.clone());
+        }
+        let q_len = self.coeffs.len() - d.coeffs.len() + 1;
+        if d.is_public() {
+            let lead_inv = MpcVal::from_public(d.coeffs.last().unwrap().val.inverse().unwrap());
+            let mut quotient = vec![MpcVal::zero(); q_len];
+            let mut remainder = self.coeffs.clone();
+            for i in (0..q_len).rev() {
+                let q = *remainder.last().unwrap() * lead_inv;
+                quotient[i] = q;
+                for (j, c) in d.coeffs.iter().enumerate() {
+                    remainder[i + j] -= q * c;
+                }
+                // the leading coefficient is now zero
+                remainder.pop();
+            }
+            return (
+                Self::new(quotient),
+                Self::new(remainder),
+            );
+        }
+        let rev = |p: &[MpcVal<F>]| p.iter().rev().take(q_len).cloned().collect::<Vec<_>>();
+        let inv = inverse_mod_xk(&rev(&d.coeffs), q_len);
+        let mut quotient = mul_coeffs(&rev(&self.coeffs), &inv);
+        quotient.resize(q_len, MpcVal::zero());
+        quotient.reverse();
+        let quotient = Self::new(quotient);
+        // the top `q_len` coefficients of the difference are zero
+        let mut remainder = self - &(&d * &quotient);
+        remainder.coeffs.truncate(d.coeffs.len() - 1);
+        remainder.truncate_leading_zeros();
+        (quotient, remainder)
+    }
+}
+
+/// The parties' shares can have different lengths, since `DensePolynomial` drops zero leading
+/// coefficients, so converting a shared polynomial exchanges them.
+impl<F: Field> From<MpcVal<DensePolynomial<F>>> for MpcDensePolynomial<F> {
+    fn from(p: MpcVal<DensePolynomial<F>>) -> Self {
+        let shared = p.shared;
+        let mut coeffs = p.val.coeffs;
+        if shared {
+            let len = coeffs.len() as u64;
+            let len = std::cmp::max(len, channel::exchange(len));
+            coeffs.resize(len as usize, F::zero());
+        }
+        Self::new(coeffs.into_iter().map(|c| MpcVal::new(c, shared)).collect())
+    }
+}
+
+impl<F: Field> From<MpcDensePolynomial<F>> for MpcVal<DensePolynomial<F>> {
+    fn from(p: MpcDensePolynomial<F>) -> Self {
+        let (vals, shared) = shares(&p.coeffs);
+        MpcVal::new(DensePolynomial::from_coefficients_vec(vals), shared)
+    }
+}
+
+impl<F: Field> Zero for MpcDensePolynomial<F> {
+    fn zero() -> Self {
+        Self { coeffs: Vec::new() }
+    }
+    /// Known to be zero: a polynomial with shared coefficients is not.
+    fn is_zero(&self) -> bool {
+        self.coeffs.iter().all(|c| !c.shared && c.val.is_zero())
+    }
+}
+
+impl<F: Field> Polynomial<MpcVal<F>> for MpcDensePolynomial<F>
+where
+    MpcVal<F>: Field,
+{
+    type Point = MpcVal<F>;
+    fn degree(&self) -> usize {
+        self.coeffs.len().saturating_sub(1)
+    }
+    /// At a shared point, this takes the point's powers in `log n` rounds, and one more for the
+    /// products with shared coefficients.
+    fn evaluate(&self, point: &MpcVal<F>) -> MpcVal<F> {
+        if self.coeffs.is_empty() {
+            return MpcVal::zero();
+        }
+        if !point.shared {
+            return self
+                .coeffs
+                .iter()
+                .rev()
+                .fold(MpcVal::zero(), |acc, c| acc * point + c);
+        }
+        let powers = channel::field_batch_powers(*point, self.coeffs.len() - 1);
+        channel::field_batch_mul(share_all(&self.coeffs), share_all(&powers))
+            .into_iter()
+            .sum()
+    }
+}
+
+impl<F: Field> UVPolynomial<MpcVal<F>> for MpcDensePolynomial<F>
+where
+    MpcVal<F>: Field,
+{
+    fn from_coefficients_slice(coeffs: &[MpcVal<F>]) -> Self {
+        Self::new(coeffs.to_vec())
+    }
+    fn from_coefficients_vec(coeffs: Vec<MpcVal<F>>) -> Self {
+        Self::new(coeffs)
+    }
+    fn coeffs(&self) -> &[MpcVal<F>] {
+        &self.coeffs
+    }
+    fn rand<R: Rng>(d: usize, rng: &mut R) -> Self {
+        Self::new((0..=d).map(|_| MpcVal::from_shared(F::rand(rng))).collect())
+    }
+}
+
+impl<'a, F: Field> AddAssign<&'a MpcDensePolynomial<F>> for MpcDensePolynomial<F> {
+    fn add_assign(&mut self, other: &'a Self) {
+        if self.coeffs.len() < other.coeffs.len() {
+            self.coeffs.resize(other.coeffs.len(), MpcVal::zero());
+        }
+        for (a, b) in self.coeffs.iter_mut().zip(&other.coeffs) {
+            *a += b;
+        }
+        self.truncate_leading_zeros();
+    }
+}
+
+impl<'a, F: Field> SubAssign<&'a MpcDensePolynomial<F>> for MpcDensePolynomial<F> {
+    fn sub_assign(&mut self, other: &'a Self) {
+        if self.coeffs.len() < other.coeffs.len() {
+            self.coeffs.resize(other.coeffs.len(), MpcVal::zero());
+        }
+        for (a, b) in self.coeffs.iter_mut().zip(&other.coeffs) {
+            *a -= b;
+        }
+        self.truncate_leading_zeros();
+    }
+}
+
+/// `self += scalar * other`
+impl<'a, F: Field> AddAssign<(MpcVal<F>, &'a MpcDensePolynomial<F>)> for MpcDensePolynomial<F> {
+    fn add_assign(&mut self, (scalar, other): (MpcVal<F>, &'a Self)) {
+        *self += &other.scale(scalar);
+    }
+}
+
+impl<F: Field> Add for MpcDensePolynomial<F> {
+    type Output = Self;
+    fn add(mut self, other: Self) -> Self {
+        self += &other;
+        self
+    }
+}
+
+impl<F: Field> Add<&MpcDensePolynomial<F>> for &MpcDensePolynomial<F> {
+    type Output = MpcDensePolynomial<F>;
+    fn add(self, other: &MpcDensePolynomial<F>) -> MpcDensePolynomial<F> {
+        let mut sum = self.clone();
+        sum += other;
+        sum
+    }
+}
+
+impl<F: Field> Sub<&MpcDensePolynomial<F>> for &MpcDensePolynomial<F> {
+    type Output = MpcDensePolynomial<F>;
+    fn sub(self, other: &MpcDensePolynomial<F>) -> MpcDensePolynomial<F> {
+        let mut difference = self.clone();
+        difference -= other;
+        difference
+    }
+}
+
+impl<F: Field> Neg for MpcDensePolynomial<F> {
+    type Output = Self;
+    fn neg(mut self) -> Self {
+        for c in &mut self.coeffs {
+            *c = -*c;
+        }
+        self
+    }
+}
+
+/// By FFT: one batch of multiplications if both are shared.
+impl<F: FftField> Mul<&MpcDensePolynomial<F>> for &MpcDensePolynomial<F> {
+    type Output = MpcDensePolynomial<F>;
+    fn mul(self, other: &MpcDensePolynomial<F>) -> MpcDensePolynomial<F> {
+        MpcDensePolynomial::new(mul_coeffs(&self.coeffs, &other.coeffs))
+    }
+}
+
+/// The quotient; see `divide_with_q_and_r`.
+impl<F: FftField> Div<&MpcDensePolynomial<F>> for &MpcDensePolynomial<F> {
+    type Output = MpcDensePolynomial<F>;
+    fn div(self, other: &MpcDensePolynomial<F>) -> MpcDensePolynomial<F> {
+        self.divide_with_q_and_r(other).0
+    }
+}
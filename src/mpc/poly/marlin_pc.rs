@@ -0,0 +1,838 @@
+//! A hand port of Marlin's KZG10 variant to MPC types,
+//! `MpcMarlinKZG10Impl<MpcPairingEngine<E>, P>`, for polynomials with `MpcVal` coefficients (e.g.
+//! [MpcDensePolynomial]).
+//!
+//! This is not ark-poly-commit's `MarlinKZG10`, which can't run over MPC types: it commits with
+//! `VariableBaseMSM`, which reads the words of each scalar's `BigInt` (a shared scalar's are one
+//! party's share, and reading them panics), and it needs `UVPolynomial::coeffs`, which
+//! `MpcVal<DensePolynomial>` doesn't have.
+// copied from ark-poly-commit 0.2 (`marlin_pc`, `kzg10`, and `marlin`)
+// Changelog:
+// 1. Renamed to `MpcMarlinKZG10Impl`. Run over the lifted engines, `MpcPairingEngine<E>`, with
+//    ark's data structures.
+// 2. Lift the MSMs to MsmCurve: `VariableBaseMSM` reads the words of each scalar's `BigInt`, and a
+//    shared scalar's words are one party's share.
+// 3. Don't skip leading zero coefficients: whether a shared coefficient is zero isn't known.
+// 4. Setup samples the trapdoor as shares (see `mpc_setup`).
+// 5. Open commitments and proofs before returning them, in one exchange per call.
+// 6. Verification and trimming are ark's: they only touch public values (shared evaluations are
+//    opened first). Points and challenges must be public.
+use super::*;
+
+use ark_poly_commit::marlin_pc::{
+    Commitment, CommitterKey, PreparedCommitment, PreparedVerifierKey, Randomness, UniversalParams,
+    VerifierKey,
+};
+use ark_poly_commit::optional_rng::OptionalRng;
+use ark_poly_commit::{kzg10, Error, PCCommitterKey};
+use ark_poly_commit::{BatchLCProof, Evaluations, PCRandomness, PolynomialCommitment, QuerySet};
+use ark_poly_commit::{LabeledCommitment, LabeledPolynomial, LinearCombination};
+use ark_std::{end_timer, start_timer};
+
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::marker::PhantomData;
+
+use super::pc::publicize_vals;
+
+type Stock<E, P> = ark_poly_commit::marlin_pc::MarlinKZG10<E, P>;
+
+/// Polynomial commitment based on KZG10, with degree enforcement, batching, and (optional)
+/// hiding, ported from ark-poly-commit's `MarlinKZG10`.
+///
+/// Implemented for `MpcMarlinKZG10Impl<MpcPairingEngine<E>, P>`: the polynomials and hiding
+/// randomness can be shared, and the commitments and proofs come out public (and are plain `MarlinKZG10`
+/// commitments and proofs; see [pp_from_mpc], [comm_from_mpc], and [proof_from_mpc]).
+pub struct MpcMarlinKZG10Impl<E: PairingEngine, P: UVPolynomial<E::Fr>> {
+    _engine: PhantomData<E>,
+    _poly: PhantomData<P>,
+}
+
+/// KZG10 parameters (powers of tau, and of tau times the hiding generator's discrete log), with
+/// the trapdoor shared: each party samples its own shares, so `rng` must be this party's own (see
+/// `crate::mpc::party_rng`). The powers are computed in `log max_degree` rounds, and the points
+/// opened in one more.
+pub fn mpc_setup<E: PairingEngine, R: RngCore>(
+    max_degree: usize,
+    rng: &mut R,
+) -> Result<kzg10::UniversalParams<E>, Error> {
+    if max_degree < 1 {
+        return Err(Error::DegreeIsZero);
+    }
+    let setup_time = start_timer!(|| format!("MpcKZG10::Setup with degree {}", max_degree));
+    let beta = MpcVal::<E::Fr>::rand(rng);
+    let gamma = MpcVal::<E::Fr>::rand(rng);
+    let g = E::G1Projective::prime_subgroup_generator();
+    let h = E::G2Projective::prime_subgroup_generator();
+
+    // beta^1..=beta^(max_degree + 1)
+    let mut powers_of_beta = channel::field_batch_powers(beta, max_degree + 1);
+    powers_of_beta.remove(0);
+    // gamma * beta^0..=beta^(max_degree + 1)
+    let mut powers_of_gamma =
+        channel::field_batch_mul(vec![gamma; max_degree + 1], powers_of_beta.clone());
+    powers_of_gamma.insert(0, gamma);
+    powers_of_beta.pop();
+
+    let (g1s, g2s) = channel::curve_batch_publicize2(
+        fixed_base_mul_shares(g, &[powers_of_beta, powers_of_gamma].concat()),
+        fixed_base_mul_shares(h, &[beta]),
+    );
+    let mut g1s: Vec<_> = g1s.into_iter().map(|p| p.val).collect();
+    g1s.insert(0, g);
+    let mut g1s = E::G1Projective::batch_normalization_into_affine(&g1s);
+    let powers_of_gamma_g = g1s
+        .split_off(max_degree + 1)
+        .into_iter()
+        .enumerate()
+        .collect();
+    let powers_of_g = g1s;
+
+    let h = h.into_affine();
+    let beta_h = g2s[0].val.into_affine();
+    let pp = kzg10::UniversalParams {
+        powers_of_g,
+        powers_of_gamma_g,
+        h,
+        beta_h,
+        neg_powers_of_h: Default::default(),
+        prepared_h: h.into(),
+        prepared_beta_h: beta_h.into(),
+    };
+    end_timer!(setup_time);
+    Ok(pp)
+}
+
+/// Lift public parameters to the MPC engine
+pub fn pp_to_mpc<E: PairingEngine>(
+    pp: kzg10::UniversalParams<E>,
+) -> UniversalParams<MpcPairingEngine<E>>
+where
+    MpcPairingEngine<E>:
+        PairingEngine<G1Affine = MpcCurve<E::G1Affine>, G2Affine = MpcCurve2<E::G2Affine>>,
+{
+    let h = MpcCurve2::from_public(pp.h);
+    let beta_h = MpcCurve2::from_public(pp.beta_h);
+    kzg10::UniversalParams {
+        powers_of_g: pp
+            .powers_of_g
+            .into_iter()
+            .map(MpcCurve::from_public)
+            .collect(),
+        powers_of_gamma_g: pp
+            .powers_of_gamma_g
+            .into_iter()
+            .map(|(i, g)| (i, MpcCurve::from_public(g)))
+            .collect(),
+        h,
+        beta_h,
+        neg_powers_of_h: Default::default(),
+        prepared_h: h.into(),
+        prepared_beta_h: beta_h.into(),
+    }
+}
+
+fn public_curve<T>(p: MpcCurve<T>) -> T {
+    assert!(!p.shared, "MpcMarlinKZG10Impl: expected a public point");
+    p.val
+}
+
+fn public_curve2<T>(p: MpcCurve2<T>) -> T {
+    assert!(!p.shared, "MpcMarlinKZG10Impl: expected a public point");
+    p.val
+}
+
+fn public_val<F: Field>(x: &MpcVal<F>) -> MpcVal<F> {
+    assert!(
+        !x.shared,
+        "MpcMarlinKZG10Impl: points and challenges must be public"
+    );
+    *x
+}
+
+/// The plain parameters that these (public) MPC parameters lift
+pub fn pp_from_mpc<E: PairingEngine>(
+    pp: &UniversalParams<MpcPairingEngine<E>>,
+) -> kzg10::UniversalParams<E>
+where
+    MpcPairingEngine<E>:
+        PairingEngine<G1Affine = MpcCurve<E::G1Affine>, G2Affine = MpcCurve2<E::G2Affine>>,
+{
+    let h = public_curve2(pp.h);
+    let beta_h = public_curve2(pp.beta_h);
+    kzg10::UniversalParams {
+        powers_of_g: pp.powers_of_g.iter().cloned().map(public_curve).collect(),
+        powers_of_gamma_g: pp
+            .powers_of_gamma_g
+            .iter()
+            .map(|(i, g)| (*i, public_curve(*g)))
+            .collect(),
+        h,
+        beta_h,
+        neg_powers_of_h: Default::default(),
+        prepared_h: h.into(),
+        prepared_beta_h: beta_h.into(),
+    }
+}
+
+/// The plain commitment equal to this (public) MPC one
+pub fn comm_from_mpc<E: PairingEngine>(
+    c: &LabeledCommitment<Commitment<MpcPairingEngine<E>>>,
+) -> LabeledCommitment<Commitment<E>>
+where
+    MpcPairingEngine<E>: PairingEngine<G1Affine = MpcCurve<E::G1Affine>>,
+{
+    let comm = c.commitment();
+    LabeledCommitment::new(
+        c.label().clone(),
+        Commitment {
+            comm: kzg10::Commitment(public_curve(comm.comm.0)),
+            shifted_comm: comm
+                .shifted_comm
+                .map(|s| kzg10::Commitment(public_curve(s.0))),
+        },
+        c.degree_bound(),
+    )
+}
+
+/// The plain proof equal to this (public) MPC one
+pub fn proof_from_mpc<E: PairingEngine>(p: &kzg10::Proof<MpcPairingEngine<E>>) -> kzg10::Proof<E>
+where
+    MpcPairingEngine<E>: PairingEngine<Fr = MpcVal<E::Fr>, G1Affine = MpcCurve<E::G1Affine>>,
+{
+    kzg10::Proof {
+        w: public_curve(p.w),
+        random_v: p.random_v.map(|v| {
+            assert!(!v.shared, "MpcMarlinKZG10Impl: expected a public value");
+            v.val
+        }),
+    }
+}
+
+/// Open a batch of points, in one exchange
+fn open_points<E: PairingEngine>(
+    points: Vec<MpcCurve<E::G1Projective>>,
+    vals: Vec<MpcVal<E::Fr>>,
+) -> (Vec<MpcCurve<E::G1Projective>>, Vec<MpcVal<E::Fr>>) {
+    let points = points
+        .into_iter()
+        .map(|p| MpcVal::new(p.val, p.shared))
+        .collect();
+    let (points, vals) = channel::curve_field_batch_publicize(points, vals);
+    (
+        points
+            .into_iter()
+            .map(|p| MpcCurve::from_public(p.val))
+            .collect(),
+        vals,
+    )
+}
+
+/// Open any shared values, in one exchange
+fn open_evaluations<F: Field>(
+    evaluations: &Evaluations<MpcVal<F>, MpcVal<F>>,
+) -> Evaluations<MpcVal<F>, MpcVal<F>> {
+    let keys: Vec<(String, MpcVal<F>)> = evaluations
+        .keys()
+        .map(|(label, point)| (label.clone(), public_val(point)))
+        .collect();
+    let values = publicize_vals(evaluations.values().cloned().collect());
+    keys.into_iter()
+        .zip(values.into_iter().map(MpcVal::from_public))
+        .collect()
+}
+
+impl<E, P> MpcMarlinKZG10Impl<MpcPairingEngine<E>, P>
+where
+    E: PairingEngine,
+    MpcPairingEngine<E>: PairingEngine<
+        Fr = MpcVal<E::Fr>,
+        G1Projective = MpcCurve<E::G1Projective>,
+        G1Affine = MpcCurve<E::G1Affine>,
+    >,
+    MpcVal<E::Fr>: PrimeField,
+    MpcCurve<E::G1Affine>:
+        MsmCurve + AffineCurve<ScalarField = MpcVal<E::Fr>, Projective = MpcCurve<E::G1Projective>>,
+    MpcCurve<E::G1Projective>:
+        ProjectiveCurve<ScalarField = MpcVal<E::Fr>, Affine = MpcCurve<E::G1Affine>>,
+    P: UVPolynomial<MpcVal<E::Fr>, Point = MpcVal<E::Fr>>,
+    for<'a, 'b> &'a P: Div<&'b P, Output = P>,
+{
+    /// KZG10 commitment to `polynomial`, unopened
+    fn kzg_commit(
+        powers: &kzg10::Powers<MpcPairingEngine<E>>,
+        polynomial: &P,
+        hiding_bound: Option<usize>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<
+        (
+            MpcCurve<E::G1Projective>,
+            kzg10::Randomness<MpcVal<E::Fr>, P>,
+        ),
+        Error,
+    > {
+        Self::check_degree_is_too_large(polynomial.degree(), powers.size())?;
+
+        let commit_time = start_timer!(|| format!(
+            "Committing to polynomial of degree {} with hiding_bound: {:?}",
+            polynomial.degree(),
+            hiding_bound,
+        ));
+
+        let msm_time = start_timer!(|| "MSM to compute commitment to plaintext poly");
+        let mut commitment =
+            MsmCurve::multi_scalar_mul(&powers.powers_of_g[..], polynomial.coeffs());
+        end_timer!(msm_time);
+
+        let mut randomness = kzg10::Randomness::<MpcVal<E::Fr>, P>::empty();
+        if let Some(hiding_degree) = hiding_bound {
+            let mut rng = rng.ok_or(Error::MissingRng)?;
+            let sample_random_poly_time = start_timer!(|| format!(
+                "Sampling a random polynomial of degree {}",
+                hiding_degree
+            ));
+
+            randomness = kzg10::Randomness::rand(hiding_degree, false, None, &mut rng);
+            Self::check_hiding_bound(
+                randomness.blinding_polynomial.degree(),
+                powers.powers_of_gamma_g.len(),
+            )?;
+            end_timer!(sample_random_poly_time);
+        }
+
+        let msm_time = start_timer!(|| "MSM to compute commitment to random poly");
+        let random_commitment = MsmCurve::multi_scalar_mul(
+            &powers.powers_of_gamma_g,
+            randomness.blinding_polynomial.coeffs(),
+        );
+        end_timer!(msm_time);
+
+        commitment += &random_commitment;
+
+        end_timer!(commit_time);
+        Ok((commitment, randomness))
+    }
+
+    /// KZG10 opening proof, unopened
+    fn open_with_witness_polynomial(
+        powers: &kzg10::Powers<MpcPairingEngine<E>>,
+        point: MpcVal<E::Fr>,
+        randomness: &kzg10::Randomness<MpcVal<E::Fr>, P>,
+        witness_polynomial: &P,
+        hiding_witness_polynomial: Option<&P>,
+    ) -> Result<(MpcCurve<E::G1Projective>, Option<MpcVal<E::Fr>>), Error> {
+        Self::check_degree_is_too_large(witness_polynomial.degree(), powers.size())?;
+
+        let witness_comm_time = start_timer!(|| "Computing commitment to witness polynomial");
+        let mut w =
+            MsmCurve::multi_scalar_mul(&powers.powers_of_g[..], witness_polynomial.coeffs());
+        end_timer!(witness_comm_time);
+
+        let random_v = if let Some(hiding_witness_polynomial) = hiding_witness_polynomial {
+            let blinding_p = &randomness.blinding_polynomial;
+            let blinding_eval_time = start_timer!(|| "Evaluating random polynomial");
+            let blinding_evaluation = blinding_p.evaluate(&point);
+            end_timer!(blinding_eval_time);
+
+            let witness_comm_time =
+                start_timer!(|| "Computing commitment to random witness polynomial");
+            w += &MsmCurve::multi_scalar_mul(
+                &powers.powers_of_gamma_g,
+                hiding_witness_polynomial.coeffs(),
+            );
+            end_timer!(witness_comm_time);
+            Some(blinding_evaluation)
+        } else {
+            None
+        };
+
+        Ok((w, random_v))
+    }
+
+    fn check_degree_is_too_large(degree: usize, num_powers: usize) -> Result<(), Error> {
+        let num_coefficients = degree + 1;
+        if num_coefficients > num_powers {
+            Err(Error::TooManyCoefficients {
+                num_coefficients,
+                num_powers,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_hiding_bound(hiding_poly_degree: usize, num_powers: usize) -> Result<(), Error> {
+        if hiding_poly_degree == 0 {
+            Err(Error::HidingBoundIsZero)
+        } else if hiding_poly_degree >= num_powers {
+            // The above check uses `>=` because committing to a hiding poly with
+            // degree `hiding_poly_degree` requires `hiding_poly_degree + 1`
+            // powers.
+            Err(Error::HidingBoundToolarge {
+                hiding_poly_degree,
+                num_powers,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_degrees_and_bounds(
+        supported_degree: usize,
+        max_degree: usize,
+        enforced_degree_bounds: Option<&[usize]>,
+        p: &LabeledPolynomial<MpcVal<E::Fr>, P>,
+    ) -> Result<(), Error> {
+        if let Some(bound) = p.degree_bound() {
+            let enforced_degree_bounds =
+                enforced_degree_bounds.ok_or(Error::UnsupportedDegreeBound(bound))?;
+
+            if enforced_degree_bounds.binary_search(&bound).is_err() {
+                Err(Error::UnsupportedDegreeBound(bound))
+            } else if bound < p.degree() || bound > max_degree {
+                Err(Error::IncorrectDegreeBound {
+                    poly_degree: p.degree(),
+                    degree_bound: p.degree_bound().unwrap(),
+                    supported_degree,
+                    label: p.label().to_string(),
+                })
+            } else {
+                Ok(())
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    fn shift_polynomial(ck: &CommitterKey<MpcPairingEngine<E>>, p: &P, degree_bound: usize) -> P {
+        if p.is_zero() {
+            P::zero()
+        } else {
+            let enforced_degree_bounds = ck
+                .enforced_degree_bounds
+                .as_ref()
+                .expect("Polynomial requires degree bounds, but `ck` does not support any");
+            let largest_enforced_degree_bound = enforced_degree_bounds.last().unwrap();
+
+            let mut shifted_polynomial_coeffs =
+                vec![MpcVal::zero(); largest_enforced_degree_bound - degree_bound];
+            shifted_polynomial_coeffs.extend_from_slice(p.coeffs());
+            P::from_coefficients_vec(shifted_polynomial_coeffs)
+        }
+    }
+}
+
+impl<E, P> PolynomialCommitment<MpcVal<E::Fr>, P> for MpcMarlinKZG10Impl<MpcPairingEngine<E>, P>
+where
+    E: PairingEngine,
+    MpcPairingEngine<E>: PairingEngine<
+        Fr = MpcVal<E::Fr>,
+        G1Projective = MpcCurve<E::G1Projective>,
+        G1Affine = MpcCurve<E::G1Affine>,
+        G2Affine = MpcCurve2<E::G2Affine>,
+    >,
+    MpcVal<E::Fr>: PrimeField,
+    MpcCurve<E::G1Affine>:
+        MsmCurve + AffineCurve<ScalarField = MpcVal<E::Fr>, Projective = MpcCurve<E::G1Projective>>,
+    MpcCurve<E::G1Projective>:
+        ProjectiveCurve<ScalarField = MpcVal<E::Fr>, Affine = MpcCurve<E::G1Affine>>,
+    P: UVPolynomial<MpcVal<E::Fr>, Point = MpcVal<E::Fr>>,
+    for<'a, 'b> &'a P: Div<&'b P, Output = P>,
+{
+    type UniversalParams = UniversalParams<MpcPairingEngine<E>>;
+    type CommitterKey = CommitterKey<MpcPairingEngine<E>>;
+    type VerifierKey = VerifierKey<MpcPairingEngine<E>>;
+    type PreparedVerifierKey = PreparedVerifierKey<MpcPairingEngine<E>>;
+    type Commitment = Commitment<MpcPairingEngine<E>>;
+    type PreparedCommitment = PreparedCommitment<MpcPairingEngine<E>>;
+    type Randomness = Randomness<MpcVal<E::Fr>, P>;
+    type Proof = kzg10::Proof<MpcPairingEngine<E>>;
+    type BatchProof = Vec<Self::Proof>;
+    type Error = Error;
+
+    /// Constructs public parameters when given as input the maximum degree `max_degree`
+    /// for the polynomial commitment scheme. See [mpc_setup].
+    fn setup<R: RngCore>(
+        max_degree: usize,
+        _num_vars: Option<usize>,
+        rng: &mut R,
+    ) -> Result<Self::UniversalParams, Self::Error> {
+        mpc_setup::<E, R>(max_degree, rng).map(pp_to_mpc)
+    }
+
+    fn trim(
+        pp: &Self::UniversalParams,
+        supported_degree: usize,
+        supported_hiding_bound: usize,
+        enforced_degree_bounds: Option<&[usize]>,
+    ) -> Result<(Self::CommitterKey, Self::VerifierKey), Self::Error> {
+        Stock::<MpcPairingEngine<E>, P>::trim(
+            pp,
+            supported_degree,
+            supported_hiding_bound,
+            enforced_degree_bounds,
+        )
+    }
+
+    /// Outputs a commitment to `polynomial`.
+    fn commit<'a>(
+        ck: &Self::CommitterKey,
+        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<E::Fr>, P>>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<
+        (
+            Vec<LabeledCommitment<Self::Commitment>>,
+            Vec<Self::Randomness>,
+        ),
+        Self::Error,
+    >
+    where
+        P: 'a,
+    {
+        let rng = &mut OptionalRng(rng);
+        let commit_time = start_timer!(|| "Committing to polynomials");
+
+        let mut commitments = Vec::new();
+        let mut randomness = Vec::new();
+
+        for p in polynomials {
+            let label = p.label();
+            let degree_bound = p.degree_bound();
+            let hiding_bound = p.hiding_bound();
+            let polynomial: &P = p.polynomial();
+
+            let enforced_degree_bounds: Option<&[usize]> = ck.enforced_degree_bounds.as_deref();
+            Self::check_degrees_and_bounds(
+                ck.supported_degree(),
+                ck.max_degree,
+                enforced_degree_bounds,
+                p,
+            )?;
+
+            let commit_time = start_timer!(|| format!(
+                "Polynomial {} of degree {}, degree bound {:?}, and hiding bound {:?}",
+                label,
+                polynomial.degree(),
+                degree_bound,
+                hiding_bound,
+            ));
+
+            let (comm, rand) = Self::kzg_commit(&ck.powers(), polynomial, hiding_bound, Some(rng))?;
+            let (shifted_comm, shifted_rand) = if let Some(degree_bound) = degree_bound {
+                let shifted_powers = ck
+                    .shifted_powers(degree_bound)
+                    .ok_or(Error::UnsupportedDegreeBound(degree_bound))?;
+                let (shifted_comm, shifted_rand) =
+                    Self::kzg_commit(&shifted_powers, polynomial, hiding_bound, Some(rng))?;
+                (Some(shifted_comm), Some(shifted_rand))
+            } else {
+                (None, None)
+            };
+
+            commitments.push((label.to_string(), degree_bound, comm, shifted_comm));
+            randomness.push(Randomness { rand, shifted_rand });
+            end_timer!(commit_time);
+        }
+
+        let mut points = Vec::new();
+        for (_, _, comm, shifted_comm) in &commitments {
+            points.push(*comm);
+            points.extend(shifted_comm);
+        }
+        let mut points = open_points::<E>(points, Vec::new()).0.into_iter();
+        let commitments = commitments
+            .into_iter()
+            .map(|(label, degree_bound, _, shifted_comm)| {
+                let comm = kzg10::Commitment(points.next().unwrap().into_affine());
+                let shifted_comm =
+                    shifted_comm.map(|_| kzg10::Commitment(points.next().unwrap().into_affine()));
+                LabeledCommitment::new(label, Commitment { comm, shifted_comm }, degree_bound)
+            })
+            .collect();
+        end_timer!(commit_time);
+        Ok((commitments, randomness))
+    }
+
+    /// On input a polynomial `p` and a point `point`, outputs a proof for the same.
+    fn open_individual_opening_challenges<'a>(
+        ck: &Self::CommitterKey,
+        labeled_polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<E::Fr>, P>>,
+        _commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        point: &'a P::Point,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
+        rands: impl IntoIterator<Item = &'a Self::Randomness>,
+        _rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        P: 'a,
+        Self::Randomness: 'a,
+        Self::Commitment: 'a,
+    {
+        let point = public_val(point);
+        let mut p = P::zero();
+        let mut r = kzg10::Randomness::empty();
+        let mut shifted_w = P::zero();
+        let mut shifted_r = kzg10::Randomness::empty();
+        let mut shifted_r_witness = P::zero();
+
+        let mut enforce_degree_bound = false;
+        let mut opening_challenge_counter = 0;
+        for (polynomial, rand) in labeled_polynomials.into_iter().zip(rands) {
+            let degree_bound = polynomial.degree_bound();
+            assert_eq!(degree_bound.is_some(), rand.shifted_rand.is_some());
+
+            let enforced_degree_bounds: Option<&[usize]> = ck.enforced_degree_bounds.as_deref();
+            Self::check_degrees_and_bounds(
+                ck.supported_degree(),
+                ck.max_degree,
+                enforced_degree_bounds,
+                polynomial,
+            )?;
+
+            // compute challenge^j and challenge^{j+1}.
+            let challenge_j = public_val(&opening_challenges(opening_challenge_counter));
+            opening_challenge_counter += 1;
+
+            p += (challenge_j, polynomial.polynomial());
+            r += (challenge_j, &rand.rand);
+
+            if let Some(degree_bound) = degree_bound {
+                enforce_degree_bound = true;
+                let shifted_rand = rand.shifted_rand.as_ref().unwrap();
+                let (witness, shifted_rand_witness) =
+                    kzg10::KZG10::<MpcPairingEngine<E>, P>::compute_witness_polynomial(
+                        polynomial.polynomial(),
+                        point,
+                        shifted_rand,
+                    )?;
+                let challenge_j_1 = public_val(&opening_challenges(opening_challenge_counter));
+                opening_challenge_counter += 1;
+
+                let shifted_witness = Self::shift_polynomial(ck, &witness, degree_bound);
+
+                shifted_w += (challenge_j_1, &shifted_witness);
+                shifted_r += (challenge_j_1, shifted_rand);
+                if let Some(shifted_rand_witness) = shifted_rand_witness {
+                    shifted_r_witness += (challenge_j_1, &shifted_rand_witness);
+                }
+            }
+        }
+        let proof_time = start_timer!(|| "Creating proof for unshifted polynomials");
+        let powers = ck.powers();
+        Self::check_degree_is_too_large(p.degree(), powers.size())?;
+        let (witness_poly, hiding_witness_poly) =
+            kzg10::KZG10::<MpcPairingEngine<E>, P>::compute_witness_polynomial(&p, point, &r)?;
+        let (mut w, mut random_v) = Self::open_with_witness_polynomial(
+            &powers,
+            point,
+            &r,
+            &witness_poly,
+            hiding_witness_poly.as_ref(),
+        )?;
+        end_timer!(proof_time);
+
+        if enforce_degree_bound {
+            let proof_time = start_timer!(|| "Creating proof for shifted polynomials");
+            let (shifted_w, shifted_random_v) = Self::open_with_witness_polynomial(
+                &ck.shifted_powers(None).unwrap(),
+                point,
+                &shifted_r,
+                &shifted_w,
+                Some(&shifted_r_witness),
+            )?;
+            end_timer!(proof_time);
+
+            w += &shifted_w;
+            if let Some(shifted_random_v) = shifted_random_v {
+                random_v = random_v.map(|v| v + shifted_random_v);
+            }
+        }
+
+        let (w, random_v) = open_points::<E>(vec![w], random_v.into_iter().collect());
+        Ok(kzg10::Proof {
+            w: w[0].into_affine(),
+            random_v: random_v.into_iter().next(),
+        })
+    }
+
+    /// Verifies that `value` is the evaluation at `x` of the polynomial
+    /// committed inside `comm`.
+    fn check_individual_opening_challenges<'a>(
+        vk: &Self::VerifierKey,
+        commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        point: &'a P::Point,
+        values: impl IntoIterator<Item = MpcVal<E::Fr>>,
+        proof: &Self::Proof,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<bool, Self::Error>
+    where
+        Self::Commitment: 'a,
+    {
+        public_val(point);
+        let values = publicize_vals(values.into_iter().collect());
+        Stock::<MpcPairingEngine<E>, P>::check_individual_opening_challenges(
+            vk,
+            commitments,
+            point,
+            values.into_iter().map(MpcVal::from_public),
+            proof,
+            &|i| public_val(&opening_challenges(i)),
+            rng,
+        )
+    }
+
+    fn batch_check_individual_opening_challenges<'a, R: RngCore>(
+        vk: &Self::VerifierKey,
+        commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        query_set: &QuerySet<P::Point>,
+        values: &Evaluations<MpcVal<E::Fr>, P::Point>,
+        proof: &Self::BatchProof,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
+        rng: &mut R,
+    ) -> Result<bool, Self::Error>
+    where
+        Self::Commitment: 'a,
+    {
+        Stock::<MpcPairingEngine<E>, P>::batch_check_individual_opening_challenges(
+            vk,
+            commitments,
+            query_set,
+            &open_evaluations(values),
+            proof,
+            &|i| public_val(&opening_challenges(i)),
+            rng,
+        )
+    }
+
+    fn open_combinations_individual_opening_challenges<'a>(
+        ck: &Self::CommitterKey,
+        lc_s: impl IntoIterator<Item = &'a LinearCombination<MpcVal<E::Fr>>>,
+        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<E::Fr>, P>>,
+        commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        query_set: &QuerySet<P::Point>,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
+        rands: impl IntoIterator<Item = &'a Self::Randomness>,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<BatchLCProof<MpcVal<E::Fr>, P, Self>, Self::Error>
+    where
+        P: 'a,
+        Self::Randomness: 'a,
+        Self::Commitment: 'a,
+    {
+        let label_map = polynomials
+            .into_iter()
+            .zip(rands)
+            .zip(commitments)
+            .map(|((p, r), c)| (p.label(), (p, r, c)))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut lc_polynomials = Vec::new();
+        let mut lc_randomness = Vec::new();
+        let mut lc_commitments = Vec::new();
+
+        for lc in lc_s {
+            let lc_label = lc.label().clone();
+            let mut poly = P::zero();
+            let mut degree_bound = None;
+            let mut hiding_bound = None;
+
+            let mut randomness = Self::Randomness::empty();
+            let mut comm = MpcCurve::<E::G1Projective>::zero();
+            let mut shifted_comm: Option<MpcCurve<E::G1Projective>> = None;
+
+            let num_polys = lc.len();
+            for (coeff, label) in lc.iter().filter(|(_, l)| !l.is_one()) {
+                let coeff = public_val(coeff);
+                let label: &String = label.try_into().expect("cannot be one!");
+                let &(cur_poly, cur_rand, cur_comm) =
+                    label_map.get(label).ok_or(Error::MissingPolynomial {
+                        label: label.to_string(),
+                    })?;
+                if num_polys == 1 && cur_poly.degree_bound().is_some() {
+                    assert!(
+                        coeff.is_one(),
+                        "Coefficient must be one for degree-bounded equations"
+                    );
+                    degree_bound = cur_poly.degree_bound();
+                } else if cur_poly.degree_bound().is_some() {
+                    return Err(Error::EquationHasDegreeBounds(lc_label));
+                }
+                // Some(_) > None, always.
+                hiding_bound = core::cmp::max(hiding_bound, cur_poly.hiding_bound());
+                poly += (coeff, cur_poly.polynomial());
+                randomness += (coeff, cur_rand);
+                let cur_comm = cur_comm.commitment();
+                comm += &cur_comm.comm.0.mul(coeff);
+                if let Some(s) = &cur_comm.shifted_comm {
+                    let cur = s.0.mul(coeff);
+                    shifted_comm = Some(shifted_comm.map_or(cur, |c| c + cur));
+                }
+            }
+
+            lc_polynomials.push(LabeledPolynomial::new(
+                lc_label.clone(),
+                poly,
+                degree_bound,
+                hiding_bound,
+            ));
+            lc_randomness.push(randomness);
+            lc_commitments.push(LabeledCommitment::new(
+                lc_label,
+                Commitment {
+                    comm: kzg10::Commitment(comm.into_affine()),
+                    shifted_comm: shifted_comm.map(|c| kzg10::Commitment(c.into_affine())),
+                },
+                degree_bound,
+            ));
+        }
+
+        let proof = Self::batch_open_individual_opening_challenges(
+            ck,
+            lc_polynomials.iter(),
+            lc_commitments.iter(),
+            query_set,
+            opening_challenges,
+            lc_randomness.iter(),
+            rng,
+        )?;
+
+        Ok(BatchLCProof { proof, evals: None })
+    }
+
+    /// Checks that `values` are the true evaluations at `query_set` of the polynomials
+    /// committed in `labeled_commitments`.
+    fn check_combinations_individual_opening_challenges<'a, R: RngCore>(
+        vk: &Self::VerifierKey,
+        lc_s: impl IntoIterator<Item = &'a LinearCombination<MpcVal<E::Fr>>>,
+        commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        query_set: &QuerySet<P::Point>,
+        evaluations: &Evaluations<MpcVal<E::Fr>, P::Point>,
+        proof: &BatchLCProof<MpcVal<E::Fr>, P, Self>,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
+        rng: &mut R,
+    ) -> Result<bool, Self::Error>
+    where
+        Self::Commitment: 'a,
+    {
+        let proof = BatchLCProof {
+            proof: proof.proof.clone(),
+            evals: proof.evals.clone().map(|evals| {
+                publicize_vals(evals)
+                    .into_iter()
+                    .map(MpcVal::from_public)
+                    .collect()
+            }),
+        };
+        Stock::<MpcPairingEngine<E>, P>::check_combinations_individual_opening_challenges(
+            vk,
+            lc_s,
+            commitments,
+            query_set,
+            &open_evaluations(evaluations),
+            &proof,
+            &|i| public_val(&opening_challenges(i)),
+            rng,
+        )
+    }
+}
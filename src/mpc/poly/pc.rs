@@ -2,25 +2,28 @@ use super::*;
 use super::super::*;
 
 use ark_poly_commit::marlin_pc::MarlinKZG10;
+use ark_poly_commit::{kzg10, marlin_pc};
 use ark_poly_commit::{Evaluations, BatchLCProof, PolynomialCommitment, QuerySet, LabeledPolynomial, LabeledCommitment, LinearCombination, PCRandomness};
 use ark_poly::UVPolynomial;
 
 use std::marker::PhantomData;
 
 
+/// Runs a polynomial commitment scheme over shared polynomials.
+///
+/// KZG commitments and opening proofs are linear in the polynomial (and in the hiding
+/// randomness), so each party runs `PC` on its shares, and we open the sum of the results.
+/// Opening points and challenges must be public.
 pub struct MpcPolyCommit<F: Field, P: Polynomial<F>, PC: PolynomialCommitment<F, P>>(PC, PhantomData<F>, PhantomData<P>);
 
 
-type F = ark_bls12_377::Fr;
-type P = ark_poly::univariate::DensePolynomial<F>;
-type E = ark_bls12_377::Bls12_377;
-type PC = MarlinKZG10<E, P>;
-type PCR = <PC as PolynomialCommitment<F, P>>::Randomness;
-type MPC = MarlinKZG10<crate::mpc::MpcPairingEngine<E>, MpcVal<P>>;
+type P<F> = ark_poly::univariate::DensePolynomial<F>;
+type PC<E> = MarlinKZG10<E, P<<E as PairingEngine>::Fr>>;
+type PCR<F> = marlin_pc::Randomness<F, P<F>>;
 
-impl PCRandomness for MpcVal<PCR> {
+impl<F: PrimeField> PCRandomness for MpcVal<PCR<F>> {
     fn empty() -> Self {
-        MpcVal::from_public(PCR::empty())
+        MpcVal::from_public(PCR::<F>::empty())
     }
     fn rand<R: rand::RngCore>(
         num_queries: usize,
@@ -28,33 +31,155 @@ impl PCRandomness for MpcVal<PCR> {
         num_vars: Option<usize>,
         rng: &mut R
     ) -> Self {
-        MpcVal::from_shared(PCR::rand(num_queries, has_degree_bound, num_vars, rng))
+        MpcVal::from_shared(PCR::<F>::rand(num_queries, has_degree_bound, num_vars, rng))
     }
 }
 
-impl PolynomialCommitment<MpcVal<F>, MpcVal<P>> for MpcPolyCommit<F, P, PC>
+fn public_val<F: Field>(x: &MpcVal<F>) -> F {
+    assert!(!x.shared, "MpcPolyCommit: points and challenges must be public");
+    x.val
+}
+
+/// Open any shared values, in one exchange
+pub(crate) fn publicize_vals<F: Field>(xs: Vec<MpcVal<F>>) -> Vec<F> {
+    let shared: Vec<MpcVal<F>> = xs.iter().filter(|x| x.shared).cloned().collect();
+    let mut opened = if shared.is_empty() {
+        Vec::new()
+    } else {
+        channel::field_batch_publicize(shared)
+    }
+    .into_iter();
+    xs.into_iter()
+        .map(|x| if x.shared { opened.next().unwrap().val } else { x.val })
+        .collect()
+}
+
+fn poly_share<F: FftField>(
+    p: &LabeledPolynomial<MpcVal<F>, MpcVal<P<F>>>,
+) -> LabeledPolynomial<F, P<F>>
+where
+    MpcVal<F>: Field,
+    MpcVal<P<F>>: Polynomial<MpcVal<F>>,
+{
+    assert!(p.shared, "MpcPolyCommit: polynomials must be shared");
+    LabeledPolynomial::new(
+        p.label().clone(),
+        p.polynomial().val.clone(),
+        p.degree_bound(),
+        p.hiding_bound(),
+    )
+}
+
+fn rand_share<F: PrimeField>(r: &MpcVal<PCR<F>>) -> &PCR<F> {
+    assert!(r.shared, "MpcPolyCommit: randomness must be shared");
+    &r.val
+}
+
+fn public_query_set<F: Field>(query_set: &QuerySet<MpcVal<F>>) -> QuerySet<F> {
+    query_set
+        .iter()
+        .map(|(label, (point_label, point))| (label.clone(), (point_label.clone(), public_val(point))))
+        .collect()
+}
+
+fn public_evaluations<F: Field>(evaluations: &Evaluations<MpcVal<F>, MpcVal<F>>) -> Evaluations<F, F> {
+    let keys: Vec<(String, F)> = evaluations
+        .keys()
+        .map(|(label, point)| (label.clone(), public_val(point)))
+        .collect();
+    let values = publicize_vals(evaluations.values().cloned().collect());
+    keys.into_iter().zip(values).collect()
+}
+
+fn public_lc<F: Field>(lc: &LinearCombination<MpcVal<F>>) -> LinearCombination<F> {
+    LinearCombination::new(
+        lc.label.clone(),
+        lc.terms
+            .iter()
+            .map(|(coeff, term)| (public_val(coeff), term.clone()))
+            .collect(),
+    )
+}
+
+/// Sum the parties' commitments, in one exchange
+fn open_commitments<E: PairingEngine>(
+    comms: Vec<LabeledCommitment<marlin_pc::Commitment<E>>>,
+) -> Vec<LabeledCommitment<marlin_pc::Commitment<E>>> {
+    let mut points = Vec::new();
+    for c in &comms {
+        points.push(MpcVal::from_shared(c.commitment().comm.0.into_projective()));
+        if let Some(s) = &c.commitment().shifted_comm {
+            points.push(MpcVal::from_shared(s.0.into_projective()));
+        }
+    }
+    let mut points = channel::curve_batch_publicize(points)
+        .into_iter()
+        .map(|p| kzg10::Commitment(p.val.into_affine()));
+    comms
+        .into_iter()
+        .map(|c| {
+            let comm = points.next().unwrap();
+            let shifted_comm = c.commitment().shifted_comm.map(|_| points.next().unwrap());
+            LabeledCommitment::new(
+                c.label().clone(),
+                marlin_pc::Commitment { comm, shifted_comm },
+                c.degree_bound(),
+            )
+        })
+        .collect()
+}
+
+/// Sum the parties' proofs, in one exchange
+fn open_proofs<E: PairingEngine>(proofs: Vec<kzg10::Proof<E>>) -> Vec<kzg10::Proof<E>> {
+    let ws = proofs
+        .iter()
+        .map(|p| MpcVal::from_shared(p.w.into_projective()))
+        .collect();
+    let vs = proofs
+        .iter()
+        .filter_map(|p| p.random_v.map(MpcVal::from_shared))
+        .collect();
+    let (ws, vs) = channel::curve_field_batch_publicize(ws, vs);
+    let mut vs = vs.into_iter();
+    proofs
+        .iter()
+        .zip(ws)
+        .map(|(p, w)| kzg10::Proof {
+            w: w.val.into_affine(),
+            random_v: p.random_v.map(|_| vs.next().unwrap().val),
+        })
+        .collect()
+}
+
+impl<E: PairingEngine> PolynomialCommitment<MpcVal<E::Fr>, MpcVal<P<E::Fr>>>
+    for MpcPolyCommit<E::Fr, P<E::Fr>, PC<E>>
 where
-    for<'a, 'b> &'a P: Div<&'b P, Output = P>,
+    MpcVal<E::Fr>: PrimeField,
+    MpcVal<P<E::Fr>>: Polynomial<MpcVal<E::Fr>, Point = MpcVal<E::Fr>>,
 {
-    type UniversalParams = <PC as PolynomialCommitment<F, P>>::UniversalParams;
-    type CommitterKey = <PC as PolynomialCommitment<F, P>>::CommitterKey;
-    type VerifierKey = <PC as PolynomialCommitment<F, P>>::VerifierKey;
-    type PreparedVerifierKey = <PC as PolynomialCommitment<F, P>>::PreparedVerifierKey;
-    type Commitment = <PC as PolynomialCommitment<F, P>>::Commitment;
-    type PreparedCommitment = <PC as PolynomialCommitment<F, P>>::PreparedCommitment;
-    type Randomness = MpcVal<PCR>;
-    type Proof = <PC as PolynomialCommitment<F, P>>::Proof;
+    type UniversalParams = <PC<E> as PolynomialCommitment<E::Fr, P<E::Fr>>>::UniversalParams;
+    type CommitterKey = <PC<E> as PolynomialCommitment<E::Fr, P<E::Fr>>>::CommitterKey;
+    type VerifierKey = <PC<E> as PolynomialCommitment<E::Fr, P<E::Fr>>>::VerifierKey;
+    type PreparedVerifierKey = <PC<E> as PolynomialCommitment<E::Fr, P<E::Fr>>>::PreparedVerifierKey;
+    type Commitment = <PC<E> as PolynomialCommitment<E::Fr, P<E::Fr>>>::Commitment;
+    type PreparedCommitment = <PC<E> as PolynomialCommitment<E::Fr, P<E::Fr>>>::PreparedCommitment;
+    type Randomness = MpcVal<PCR<E::Fr>>;
+    type Proof = <PC<E> as PolynomialCommitment<E::Fr, P<E::Fr>>>::Proof;
     type BatchProof = Vec<Self::Proof>;
-    type Error = <PC as PolynomialCommitment<F, P>>::Error;
+    type Error = <PC<E> as PolynomialCommitment<E::Fr, P<E::Fr>>>::Error;
 
     /// Constructs public parameters when given as input the maximum degree `max_degree`
     /// for the polynomial commitment scheme.
+    ///
+    /// Powers of tau, with tau (and the hiding generator's discrete log) shared: each party
+    /// samples its own share, so `rng` must be this party's own (see `crate::mpc::party_rng`).
+    /// The powers are computed in `log max_degree` rounds, and the points opened in one more.
     fn setup<R: RngCore>(
         max_degree: usize,
-        num_vars: Option<usize>,
+        _num_vars: Option<usize>,
         rng: &mut R,
     ) -> Result<Self::UniversalParams, Self::Error> {
-        PC::setup(max_degree, num_vars, rng)
+        super::marlin_pc::mpc_setup(max_degree, rng)
     }
 
     fn trim(
@@ -63,13 +188,13 @@ where
         supported_hiding_bound: usize,
         enforced_degree_bounds: Option<&[usize]>,
     ) -> Result<(Self::CommitterKey, Self::VerifierKey), Self::Error> {
-        PC::trim(pp, supported_degree, supported_hiding_bound, enforced_degree_bounds)
+        PC::<E>::trim(pp, supported_degree, supported_hiding_bound, enforced_degree_bounds)
     }
 
     /// Outputs a commitment to `polynomial`.
     fn commit<'a>(
         ck: &Self::CommitterKey,
-        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<F>, MpcVal<P>>>,
+        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<E::Fr>, MpcVal<P<E::Fr>>>>,
         rng: Option<&mut dyn RngCore>,
     ) -> Result<
         (
@@ -79,36 +204,47 @@ where
         Self::Error,
     >
     where
-        P: 'a,
+        P<E::Fr>: 'a,
     {
-        let polynomial_shares: Vec<LabeledPolynomial<F, P>> = polynomials.into_iter().map(|p| {
-            assert!(p.shared, "Can only commit to shared polynomials");
-            LabeledPolynomial::new(p.label().clone(), p.polynomial().val.clone(), p.degree_bound(), p.hiding_bound())
-        }).collect();
-        let (c_share_comms, c_share_rands) = PC::commit(ck, &polynomial_shares, rng)?;
-        c_share_comms.into_iter().map(|c_share| {
-            let g_elem = c_share.commitment().comm.0;
-            let shifted_g_elem = c_share.commitment().shifted_comm.map(|s| s.0);
-        });
-        unimplemented!()
+        let polynomial_shares: Vec<LabeledPolynomial<E::Fr, P<E::Fr>>> =
+            polynomials.into_iter().map(poly_share).collect();
+        let (c_share_comms, c_share_rands) = PC::<E>::commit(ck, &polynomial_shares, rng)?;
+        Ok((
+            open_commitments(c_share_comms),
+            c_share_rands.into_iter().map(MpcVal::from_shared).collect(),
+        ))
     }
 
     /// On input a polynomial `p` and a point `point`, outputs a proof for the same.
     fn open_individual_opening_challenges<'a>(
         ck: &Self::CommitterKey,
-        labeled_polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<F>, MpcVal<P>>>,
-        _commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
-        point: &'a MpcVal<F>,
-        opening_challenges: &dyn Fn(u64) -> MpcVal<F>,
+        labeled_polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<E::Fr>, MpcVal<P<E::Fr>>>>,
+        commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
+        point: &'a MpcVal<E::Fr>,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
         rands: impl IntoIterator<Item = &'a Self::Randomness>,
-        _rng: Option<&mut dyn RngCore>,
+        rng: Option<&mut dyn RngCore>,
     ) -> Result<Self::Proof, Self::Error>
     where
-        P: 'a,
+        P<E::Fr>: 'a,
         Self::Randomness: 'a,
         Self::Commitment: 'a,
     {
-        unimplemented!()
+        let polynomial_shares: Vec<LabeledPolynomial<E::Fr, P<E::Fr>>> =
+            labeled_polynomials.into_iter().map(poly_share).collect();
+        let commitments: Vec<_> = commitments.into_iter().cloned().collect();
+        let rands: Vec<PCR<E::Fr>> = rands.into_iter().map(|r| rand_share(r).clone()).collect();
+        let point = public_val(point);
+        let proof_share = PC::<E>::open_individual_opening_challenges(
+            ck,
+            &polynomial_shares,
+            &commitments,
+            &point,
+            &|i| public_val(&opening_challenges(i)),
+            &rands,
+            rng,
+        )?;
+        Ok(open_proofs(vec![proof_share]).pop().unwrap())
     }
 
     /// Verifies that `value` is the evaluation at `x` of the polynomial
@@ -116,90 +252,163 @@ where
     fn check_individual_opening_challenges<'a>(
         vk: &Self::VerifierKey,
         commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
-        point: &'a MpcVal<F>,
-        values: impl IntoIterator<Item = MpcVal<F>>,
+        point: &'a MpcVal<E::Fr>,
+        values: impl IntoIterator<Item = MpcVal<E::Fr>>,
         proof: &Self::Proof,
-        opening_challenges: &dyn Fn(u64) -> MpcVal<F>,
-        _rng: Option<&mut dyn RngCore>,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
+        rng: Option<&mut dyn RngCore>,
     ) -> Result<bool, Self::Error>
     where
         Self::Commitment: 'a,
     {
-        unimplemented!()
+        let commitments: Vec<_> = commitments.into_iter().cloned().collect();
+        let point = public_val(point);
+        PC::<E>::check_individual_opening_challenges(
+            vk,
+            &commitments,
+            &point,
+            publicize_vals(values.into_iter().collect()),
+            proof,
+            &|i| public_val(&opening_challenges(i)),
+            rng,
+        )
     }
 
     fn batch_check_individual_opening_challenges<'a, R: RngCore>(
         vk: &Self::VerifierKey,
         commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
-        query_set: &QuerySet<MpcVal<F>>,
-        values: &Evaluations<MpcVal<F>, MpcVal<F>>,
+        query_set: &QuerySet<MpcVal<E::Fr>>,
+        values: &Evaluations<MpcVal<E::Fr>, MpcVal<E::Fr>>,
         proof: &Self::BatchProof,
-        opening_challenges: &dyn Fn(u64) -> MpcVal<F>,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
         rng: &mut R,
     ) -> Result<bool, Self::Error>
     where
         Self::Commitment: 'a,
     {
-        unimplemented!()
+        PC::<E>::batch_check_individual_opening_challenges(
+            vk,
+            commitments,
+            &public_query_set(query_set),
+            &public_evaluations(values),
+            proof,
+            &|i| public_val(&opening_challenges(i)),
+            rng,
+        )
     }
 
     fn open_combinations_individual_opening_challenges<'a>(
         ck: &Self::CommitterKey,
-        lc_s: impl IntoIterator<Item = &'a LinearCombination<MpcVal<F>>>,
-        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<F>, MpcVal<P>>>,
+        lc_s: impl IntoIterator<Item = &'a LinearCombination<MpcVal<E::Fr>>>,
+        polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<E::Fr>, MpcVal<P<E::Fr>>>>,
         commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
-        query_set: &QuerySet<MpcVal<F>>,
-        opening_challenges: &dyn Fn(u64) -> MpcVal<F>,
+        query_set: &QuerySet<MpcVal<E::Fr>>,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
         rands: impl IntoIterator<Item = &'a Self::Randomness>,
         rng: Option<&mut dyn RngCore>,
-    ) -> Result<BatchLCProof<MpcVal<F>, MpcVal<P>, Self>, Self::Error>
+    ) -> Result<BatchLCProof<MpcVal<E::Fr>, MpcVal<P<E::Fr>>, Self>, Self::Error>
     where
-        P: 'a,
+        P<E::Fr>: 'a,
         Self::Randomness: 'a,
         Self::Commitment: 'a,
     {
-        unimplemented!()
+        let lc_s: Vec<LinearCombination<E::Fr>> = lc_s.into_iter().map(public_lc).collect();
+        let polynomial_shares: Vec<LabeledPolynomial<E::Fr, P<E::Fr>>> =
+            polynomials.into_iter().map(poly_share).collect();
+        let commitments: Vec<_> = commitments.into_iter().cloned().collect();
+        let rands: Vec<PCR<E::Fr>> = rands.into_iter().map(|r| rand_share(r).clone()).collect();
+        let proof_share = PC::<E>::open_combinations_individual_opening_challenges(
+            ck,
+            &lc_s,
+            &polynomial_shares,
+            &commitments,
+            &public_query_set(query_set),
+            &|i| public_val(&opening_challenges(i)),
+            &rands,
+            rng,
+        )?;
+        Ok(BatchLCProof {
+            proof: open_proofs(proof_share.proof),
+            evals: proof_share
+                .evals
+                .map(|evals| evals.into_iter().map(MpcVal::from_shared).collect()),
+        })
     }
 
     /// Checks that `values` are the true evaluations at `query_set` of the polynomials
     /// committed in `labeled_commitments`.
     fn check_combinations_individual_opening_challenges<'a, R: RngCore>(
         vk: &Self::VerifierKey,
-        lc_s: impl IntoIterator<Item = &'a LinearCombination<MpcVal<F>>>,
+        lc_s: impl IntoIterator<Item = &'a LinearCombination<MpcVal<E::Fr>>>,
         commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
-        query_set: &QuerySet<MpcVal<F>>,
-        evaluations: &Evaluations<MpcVal<F>, MpcVal<F>>,
-        proof: &BatchLCProof<MpcVal<F>, MpcVal<P>, Self>,
-        opening_challenges: &dyn Fn(u64) -> MpcVal<F>,
+        query_set: &QuerySet<MpcVal<E::Fr>>,
+        evaluations: &Evaluations<MpcVal<E::Fr>, MpcVal<E::Fr>>,
+        proof: &BatchLCProof<MpcVal<E::Fr>, MpcVal<P<E::Fr>>, Self>,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
         rng: &mut R,
     ) -> Result<bool, Self::Error>
     where
         Self::Commitment: 'a,
     {
-        unimplemented!()
+        let lc_s: Vec<LinearCombination<E::Fr>> = lc_s.into_iter().map(public_lc).collect();
+        let proof = BatchLCProof {
+            proof: proof.proof.clone(),
+            evals: proof
+                .evals
+                .clone()
+                .map(publicize_vals),
+        };
+        let commitments: Vec<_> = commitments.into_iter().cloned().collect();
+        PC::<E>::check_combinations_individual_opening_challenges(
+            vk,
+            &lc_s,
+            &commitments,
+            &public_query_set(query_set),
+            &public_evaluations(evaluations),
+            &proof,
+            &|i| public_val(&opening_challenges(i)),
+            rng,
+        )
     }
 
     /// On input a list of labeled polynomials and a query set, `open` outputs a proof of evaluation
     /// of the polynomials at the points in the query set.
     fn batch_open_individual_opening_challenges<'a>(
         ck: &Self::CommitterKey,
-        labeled_polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<F>, MpcVal<P>>>,
+        labeled_polynomials: impl IntoIterator<Item = &'a LabeledPolynomial<MpcVal<E::Fr>, MpcVal<P<E::Fr>>>>,
         commitments: impl IntoIterator<Item = &'a LabeledCommitment<Self::Commitment>>,
-        query_set: &QuerySet<MpcVal<F>>,
-        opening_challenges: &dyn Fn(u64) -> MpcVal<F>,
+        query_set: &QuerySet<MpcVal<E::Fr>>,
+        opening_challenges: &dyn Fn(u64) -> MpcVal<E::Fr>,
         rands: impl IntoIterator<Item = &'a Self::Randomness>,
         rng: Option<&mut dyn RngCore>,
     ) -> Result<Vec<Self::Proof>, Self::Error>
     where
-        P: 'a,
+        P<E::Fr>: 'a,
         Self::Randomness: 'a,
         Self::Commitment: 'a,
     {
-        unimplemented!()
+        let polynomial_shares: Vec<LabeledPolynomial<E::Fr, P<E::Fr>>> =
+            labeled_polynomials.into_iter().map(poly_share).collect();
+        let commitments: Vec<_> = commitments.into_iter().cloned().collect();
+        let rands: Vec<PCR<E::Fr>> = rands.into_iter().map(|r| rand_share(r).clone()).collect();
+        let proof_shares = PC::<E>::batch_open_individual_opening_challenges(
+            ck,
+            &polynomial_shares,
+            &commitments,
+            &public_query_set(query_set),
+            &|i| public_val(&opening_challenges(i)),
+            &rands,
+            rng,
+        )?;
+        Ok(open_proofs(proof_shares))
     }
 }
 
-/// Marlin PC run over MPC types
-pub type MpcMarlinKZG10 = MarlinKZG10<MpcPairingEngine<Bls12_377>, MpcVal<univariate::DensePolynomial<ark_bls12_377::Fr>>>;
-
-
+/// A hand port of Marlin PC to MPC types, for generic arkworks code over `PolynomialCommitment`.
+///
+/// This is not ark's `MarlinKZG10`, which doesn't run over MPC types (see [super::marlin_pc]). Its
+/// polynomials are `MpcDensePolynomial`s: `MpcVal<DensePolynomial>` is not a `UVPolynomial`.
+pub type MpcMarlinKZG10 = super::marlin_pc::MpcMarlinKZG10Impl<
+    MpcPairingEngine<Bls12_377>,
+    MpcDensePolynomial<ark_bls12_377::Fr>,
+>;
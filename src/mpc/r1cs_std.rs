@@ -0,0 +1,211 @@
+//! A separate gadget library for secret-shared witnesses, ported from a subset of ark-r1cs-std.
+//!
+//! This is not ark-r1cs-std, which is not a dependency: the gadgets here implement none of its
+//! traits (`AllocVar`, `R1CSVar`, `FieldVar`, `EqGadget`, `ToBitsGadget`, ...), and their methods
+//! are inherent. Gadget code written against ark-r1cs-std does not compile against them; it has to
+//! be ported too.
+//!
+//! ark-r1cs-std's gadgets compute their witnesses by branching on values (`if x == 0`, the bits
+//! of `x`, ...), which shares do not support. These gadgets get such witnesses from
+//! `GadgetField`, which works over shares in MPC, and compute the rest with field arithmetic.
+//! Their constraints are the same as ark-r1cs-std's.
+//!
+//! Supported: `FpVar` (arithmetic, `is_eq`, `is_neq`, `conditionally_select`, bit decomposition,
+//! and the comparisons `is_cmp` and `enforce_cmp`), `Boolean`, and `UInt8` and `UInt32` (`xor`,
+//! `rotr` and `addmany`).
+use ark_ff::{BigInteger, BitIteratorBE, FpParameters};
+use ark_groth16::{generate_random_parameters, prepare_verifying_key, verify_proof};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable},
+};
+use ark_std::test_rng;
+
+use super::domain::BatchFft;
+use super::groth::{create_random_proof_for, pk_to_mpc};
+use super::*;
+
+pub mod bits;
+pub mod fp;
+
+use bits::{Boolean, UInt32, UInt8};
+use fp::{AllocatedFp, FpVar};
+
+/// A field whose witnesses the gadgets can compute: a plain field, or a shared one.
+pub trait GadgetField: PrimeField {
+    /// The `MODULUS_BITS` little-endian bits of each `x`, as `0` or `1`.
+    fn bits_le(xs: &[Self]) -> Vec<Vec<Self>>;
+    /// `([x == 0], 1 / x)` for each `x`, where `1 / 0` is `0`.
+    fn is_zero_and_inverse(xs: &[Self]) -> Vec<(Self, Self)>;
+}
+
+fn local_bits_le<F: PrimeField>(x: &F) -> Vec<F> {
+    x.into_repr()
+        .to_bits_le()
+        .into_iter()
+        .take(F::Params::MODULUS_BITS as usize)
+        .map(F::from)
+        .collect()
+}
+
+fn local_is_zero_and_inverse<F: PrimeField>(x: &F) -> (F, F) {
+    (F::from(x.is_zero()), x.inverse().unwrap_or_else(F::zero))
+}
+
+macro_rules! impl_local_gadget_field {
+    ($F:ty) => {
+        impl GadgetField for $F {
+            fn bits_le(xs: &[Self]) -> Vec<Vec<Self>> {
+                xs.iter().map(local_bits_le).collect()
+            }
+            fn is_zero_and_inverse(xs: &[Self]) -> Vec<(Self, Self)> {
+                xs.iter().map(local_is_zero_and_inverse).collect()
+            }
+        }
+    };
+}
+
+impl_local_gadget_field!(ark_bls12_377::Fr);
+impl_local_gadget_field!(ark_bls12_381::Fr);
+impl_local_gadget_field!(ark_bn254::Fr);
+
+impl<F: PrimeField> GadgetField for MpcVal<F>
+where
+    MpcVal<F>: PrimeField,
+{
+    fn bits_le(xs: &[Self]) -> Vec<Vec<Self>> {
+        if xs.iter().all(|x| !x.shared) {
+            return xs.iter().map(local_bits_le).collect();
+        }
+        boolean::field_to_bits(xs)
+            .iter()
+            .map(|bits| boolean::bits_to_field(bits))
+            .collect()
+    }
+    fn is_zero_and_inverse(xs: &[Self]) -> Vec<(Self, Self)> {
+        if xs.iter().all(|x| !x.shared) {
+            return xs.iter().map(local_is_zero_and_inverse).collect();
+        }
+        let zs = boolean::bits_to_field::<F>(&boolean::is_zero(xs));
+        // x + [x == 0] is nonzero, and its inverse is 1 / x if x is
+        let invs = channel::field_batch_inv(xs.iter().zip(&zs).map(|(x, z)| *x + *z).collect())
+            .into_iter()
+            .map(|i| i.unwrap())
+            .collect();
+        let nonzero = zs.iter().map(|z| Self::one() - *z).collect();
+        zs.into_iter()
+            .zip(channel::field_batch_mul(invs, nonzero))
+            .collect()
+    }
+}
+
+/// A demo circuit: for witnesses `a`, `b` (at most `(p - 1) / 2`) and 32-bit `x`, `y`, it has
+/// public inputs `[a == b]`, `[a < b]`, `[a <= b] ? a : b`, `m = (x + y) ^ (x >>> 7)`, and, over
+/// the bytes `m_0..m_3` of `m`, `(m_0 + (m_1 >>> 3) + 0xa5) ^ m_3`.
+#[derive(Clone)]
+pub struct GadgetCircuit<F: Field> {
+    pub a: Option<F>,
+    pub b: Option<F>,
+    pub x: Option<F>,
+    pub y: Option<F>,
+}
+
+impl<F: GadgetField> ConstraintSynthesizer<F> for GadgetCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let get = |v: Option<F>| move || v.ok_or(SynthesisError::AssignmentMissing);
+        let a = FpVar::new_witness(cs.clone(), get(self.a))?;
+        let b = FpVar::new_witness(cs.clone(), get(self.b))?;
+        let x = UInt32::new_witness(cs.clone(), get(self.x))?;
+        let y = UInt32::new_witness(cs.clone(), get(self.y))?;
+
+        let eq = a.is_eq(&b)?;
+        let lt = a.is_cmp(&b, std::cmp::Ordering::Less, false)?;
+        let le = a.is_cmp(&b, std::cmp::Ordering::Less, true)?;
+        let min = le.select(&a, &b)?;
+        let mix = UInt32::addmany(&[x.clone(), y])?.xor(&x.rotr(7))?;
+        let bytes: Vec<UInt8<F>> = mix.to_bits_le().chunks(8).map(UInt8::from_bits_le).collect();
+        let byte = UInt8::addmany(&[bytes[0].clone(), bytes[1].rotr(3), UInt8::constant(0xa5)])?
+            .xor(&bytes[3])?
+            .to_fp()?;
+        let mix = mix.to_fp()?;
+
+        let eq = Boolean::le_bits_to_fp_var(&[eq])?;
+        let lt = Boolean::le_bits_to_fp_var(&[lt])?;
+        for out in [eq, lt, min, mix, byte] {
+            let input = FpVar::new_input(cs.clone(), || out.value())?;
+            input.enforce_equal(&out)?;
+        }
+        Ok(())
+    }
+}
+
+/// The public inputs of `GadgetCircuit`, computed in the clear.
+pub fn gadget_circuit_outputs<F: PrimeField>(a: F, b: F, x: u32, y: u32) -> Vec<F> {
+    let lt = a.into_repr() < b.into_repr();
+    let mix = x.wrapping_add(y) ^ x.rotate_right(7);
+    let m = mix.to_le_bytes();
+    let byte = m[0].wrapping_add(m[1].rotate_right(3)).wrapping_add(0xa5) ^ m[3];
+    vec![
+        F::from(a == b),
+        F::from(lt),
+        if lt || a == b { a } else { b },
+        F::from(mix),
+        F::from(byte),
+    ]
+}
+
+/// Prove `GadgetCircuit` for shared `a`, `b`, `x` and `y`, and return its public inputs.
+pub fn mpc_test_prove_and_verify<E: PairingEngine>(
+    a: MpcVal<E::Fr>,
+    b: MpcVal<E::Fr>,
+    x: MpcVal<E::Fr>,
+    y: MpcVal<E::Fr>,
+) -> Vec<MpcVal<E::Fr>>
+where
+    MpcPairingEngine<E>: PairingEngine<
+        Fr = MpcVal<E::Fr>,
+        G1Projective = MpcCurve<E::G1Projective>,
+        G1Affine = MpcCurve<E::G1Affine>,
+        G2Affine = MpcCurve2<E::G2Affine>,
+        Fqk = MpcMulVal<E::Fqk>,
+    >,
+    MpcCurve<E::G1Projective>: BatchScalarMul,
+    MpcCurve<E::G1Affine>: MsmCurve,
+    MpcCurve2<E::G2Affine>: MsmCurve,
+    MpcVal<E::Fr>: BatchProd + BatchFft + GadgetField,
+    E::Fr: GadgetField,
+{
+    let rng = &mut test_rng();
+    let empty = GadgetCircuit::<E::Fr> {
+        a: None,
+        b: None,
+        x: None,
+        y: None,
+    };
+    let params = generate_random_parameters::<E, _, _>(empty, rng).unwrap();
+    let pvk = prepare_verifying_key::<E>(&params.vk);
+    let mpc_params = pk_to_mpc(params);
+
+    let circuit = GadgetCircuit {
+        a: Some(a),
+        b: Some(b),
+        x: Some(x),
+        y: Some(y),
+    };
+    let proof = create_random_proof_for::<E, _>(circuit, &mpc_params, None)
+        .unwrap()
+        .unwrap();
+
+    let [a, b, x, y] = [a, b, x, y].map(|v| v.publicize_unwrap());
+    let small = |v: E::Fr| -> u32 {
+        let repr = v.into_repr();
+        assert!(repr.num_bits() <= 32, "x and y must be 32-bit");
+        repr.as_ref()[0] as u32
+    };
+    let outputs = gadget_circuit_outputs(a, b, small(x), small(y));
+    assert!(verify_proof(&pvk, &proof, &outputs).unwrap());
+    let mut wrong = outputs.clone();
+    wrong[0] = E::Fr::one() - wrong[0];
+    assert!(!verify_proof(&pvk, &proof, &wrong).unwrap());
+    outputs.into_iter().map(MpcVal::from_public).collect()
+}
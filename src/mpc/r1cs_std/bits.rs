@@ -0,0 +1,515 @@
+use super::*;
+
+// Changelog:
+// 1. Copied from ark-r1cs-std's `bits::boolean`, `bits::uint8` and `bits::uint`.
+// 2. Bit values are field elements (`0` or `1`), not `bool`s, so that they can be shared. Values
+//    are kept in the gadgets, rather than read back from the constraint system.
+// 3. Witnesses for AND, OR and XOR are computed with field arithmetic (`a b`, `a + b - a b` and
+//    `a + b - 2 a b`).
+// 4. `UInt8` and `UInt32` come from one macro. Their values are field elements too, and
+//    `addmany` decomposes its (field) sum with `GadgetField::bits_le`.
+// 5. Only the operations listed in `super` are ported, as inherent methods (there are no
+//    `AllocVar`, `EqGadget` or `ToBitsGadget` traits here).
+
+/// A bit allocated in the constraint system.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct AllocatedBit<F: GadgetField> {
+    variable: Variable,
+    value: Option<F>,
+    cs: ConstraintSystemRef<F>,
+}
+
+impl<F: GadgetField> AllocatedBit<F> {
+    /// The bit, as `0` or `1`.
+    pub fn value(&self) -> Result<F, SynthesisError> {
+        self.value.ok_or(SynthesisError::AssignmentMissing)
+    }
+
+    pub fn variable(&self) -> Variable {
+        self.variable
+    }
+
+    fn new_witness_without_booleanity_check(
+        cs: ConstraintSystemRef<F>,
+        value: Option<F>,
+    ) -> Result<Self, SynthesisError> {
+        let variable = cs.new_witness_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+        Ok(Self {
+            variable,
+            value,
+            cs,
+        })
+    }
+
+    /// Allocate a witness bit, constrained to be `0` or `1`.
+    pub fn new_witness(
+        cs: ConstraintSystemRef<F>,
+        f: impl FnOnce() -> Result<F, SynthesisError>,
+    ) -> Result<Self, SynthesisError> {
+        let bit = Self::new_witness_without_booleanity_check(cs.clone(), f().ok())?;
+        // (1 - a) * a = 0
+        cs.enforce_constraint(
+            lc!() + Variable::One - bit.variable,
+            lc!() + bit.variable,
+            lc!(),
+        )?;
+        Ok(bit)
+    }
+
+    fn binary_op(
+        &self,
+        b: &Self,
+        value: impl FnOnce(F, F) -> F,
+    ) -> Result<Self, SynthesisError> {
+        let value = match (self.value, b.value) {
+            (Some(x), Some(y)) => Some(value(x, y)),
+            _ => None,
+        };
+        Self::new_witness_without_booleanity_check(self.cs.clone(), value)
+    }
+
+    pub fn xor(&self, b: &Self) -> Result<Self, SynthesisError> {
+        let result = self.binary_op(b, |a, b| a + b - (a * b).double())?;
+        // (a + a) * b = a + b - c
+        self.cs.enforce_constraint(
+            lc!() + self.variable + self.variable,
+            lc!() + b.variable,
+            lc!() + self.variable + b.variable - result.variable,
+        )?;
+        Ok(result)
+    }
+
+    pub fn and(&self, b: &Self) -> Result<Self, SynthesisError> {
+        let result = self.binary_op(b, |a, b| a * b)?;
+        // a * b = c
+        self.cs.enforce_constraint(
+            lc!() + self.variable,
+            lc!() + b.variable,
+            lc!() + result.variable,
+        )?;
+        Ok(result)
+    }
+
+    pub fn or(&self, b: &Self) -> Result<Self, SynthesisError> {
+        let result = self.binary_op(b, |a, b| a + b - a * b)?;
+        // (1 - a) * (1 - b) = (1 - c)
+        self.cs.enforce_constraint(
+            lc!() + Variable::One - self.variable,
+            lc!() + Variable::One - b.variable,
+            lc!() + Variable::One - result.variable,
+        )?;
+        Ok(result)
+    }
+
+    pub fn and_not(&self, b: &Self) -> Result<Self, SynthesisError> {
+        let result = self.binary_op(b, |a, b| a - a * b)?;
+        // a * (1 - b) = c
+        self.cs.enforce_constraint(
+            lc!() + self.variable,
+            lc!() + Variable::One - b.variable,
+            lc!() + result.variable,
+        )?;
+        Ok(result)
+    }
+
+    pub fn nor(&self, b: &Self) -> Result<Self, SynthesisError> {
+        let result = self.binary_op(b, |a, b| F::one() - a - b + a * b)?;
+        // (1 - a) * (1 - b) = c
+        self.cs.enforce_constraint(
+            lc!() + Variable::One - self.variable,
+            lc!() + Variable::One - b.variable,
+            lc!() + result.variable,
+        )?;
+        Ok(result)
+    }
+}
+
+/// A bit that is constant, allocated, or the negation of an allocated bit.
+#[derive(Clone, Debug)]
+#[must_use]
+pub enum Boolean<F: GadgetField> {
+    Is(AllocatedBit<F>),
+    Not(AllocatedBit<F>),
+    Constant(bool),
+}
+
+impl<F: GadgetField> From<AllocatedBit<F>> for Boolean<F> {
+    fn from(b: AllocatedBit<F>) -> Self {
+        Boolean::Is(b)
+    }
+}
+
+impl<F: GadgetField> Boolean<F> {
+    pub const TRUE: Self = Boolean::Constant(true);
+    pub const FALSE: Self = Boolean::Constant(false);
+
+    pub fn constant(b: bool) -> Self {
+        Boolean::Constant(b)
+    }
+
+    /// Allocate a witness bit, from a `0` or `1`.
+    pub fn new_witness(
+        cs: ConstraintSystemRef<F>,
+        f: impl FnOnce() -> Result<F, SynthesisError>,
+    ) -> Result<Self, SynthesisError> {
+        AllocatedBit::new_witness(cs, f).map(Boolean::Is)
+    }
+
+    pub fn cs(&self) -> ConstraintSystemRef<F> {
+        match self {
+            Boolean::Is(b) | Boolean::Not(b) => b.cs.clone(),
+            Boolean::Constant(_) => ConstraintSystemRef::None,
+        }
+    }
+
+    /// The bit, as `0` or `1`.
+    pub fn value(&self) -> Result<F, SynthesisError> {
+        match self {
+            Boolean::Constant(c) => Ok(F::from(*c)),
+            Boolean::Is(b) => b.value(),
+            Boolean::Not(b) => Ok(F::one() - b.value()?),
+        }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        matches!(self, Boolean::Constant(_))
+    }
+
+    pub fn lc(&self) -> LinearCombination<F> {
+        match self {
+            Boolean::Constant(false) => lc!(),
+            Boolean::Constant(true) => lc!() + Variable::One,
+            Boolean::Is(b) => lc!() + b.variable,
+            Boolean::Not(b) => lc!() + Variable::One - b.variable,
+        }
+    }
+
+    pub fn not(&self) -> Self {
+        match self {
+            Boolean::Constant(c) => Boolean::Constant(!c),
+            Boolean::Is(b) => Boolean::Not(b.clone()),
+            Boolean::Not(b) => Boolean::Is(b.clone()),
+        }
+    }
+
+    pub fn xor(&self, other: &Self) -> Result<Self, SynthesisError> {
+        use Boolean::*;
+        match (self, other) {
+            (&Constant(false), x) | (x, &Constant(false)) => Ok(x.clone()),
+            (&Constant(true), x) | (x, &Constant(true)) => Ok(x.not()),
+            // a XOR (NOT b) = NOT(a XOR b)
+            (is @ &Is(_), not @ &Not(_)) | (not @ &Not(_), is @ &Is(_)) => {
+                Ok(is.xor(&not.not())?.not())
+            }
+            // a XOR b = (NOT a) XOR (NOT b)
+            (Is(a), Is(b)) | (Not(a), Not(b)) => Ok(Is(a.xor(b)?)),
+        }
+    }
+
+    pub fn or(&self, other: &Self) -> Result<Self, SynthesisError> {
+        use Boolean::*;
+        match (self, other) {
+            (&Constant(false), x) | (x, &Constant(false)) => Ok(x.clone()),
+            (&Constant(true), _) | (_, &Constant(true)) => Ok(Constant(true)),
+            // a OR b = NOT ((NOT a) AND (NOT b))
+            (a @ &Is(_), b @ &Not(_)) | (b @ &Not(_), a @ &Is(_)) | (b @ &Not(_), a @ &Not(_)) => {
+                Ok(a.not().and(&b.not())?.not())
+            }
+            (Is(a), Is(b)) => a.or(b).map(From::from),
+        }
+    }
+
+    pub fn and(&self, other: &Self) -> Result<Self, SynthesisError> {
+        use Boolean::*;
+        match (self, other) {
+            (&Constant(false), _) | (_, &Constant(false)) => Ok(Constant(false)),
+            (&Constant(true), x) | (x, &Constant(true)) => Ok(x.clone()),
+            (Is(is), Not(not)) | (Not(not), Is(is)) => Ok(Is(is.and_not(not)?)),
+            (Not(a), Not(b)) => Ok(Is(a.nor(b)?)),
+            (Is(a), Is(b)) => Ok(Is(a.and(b)?)),
+        }
+    }
+
+    pub fn kary_and(bits: &[Self]) -> Result<Self, SynthesisError> {
+        assert!(!bits.is_empty());
+        bits[1..]
+            .iter()
+            .try_fold(bits[0].clone(), |acc, b| acc.and(b))
+    }
+
+    pub fn kary_or(bits: &[Self]) -> Result<Self, SynthesisError> {
+        assert!(!bits.is_empty());
+        bits[1..]
+            .iter()
+            .try_fold(bits[0].clone(), |acc, b| acc.or(b))
+    }
+
+    pub fn kary_nand(bits: &[Self]) -> Result<Self, SynthesisError> {
+        Ok(Self::kary_and(bits)?.not())
+    }
+
+    fn enforce_kary_nand(bits: &[Self]) -> Result<(), SynthesisError> {
+        let r = Self::kary_nand(bits)?;
+        match r {
+            Boolean::Constant(true) => Ok(()),
+            Boolean::Constant(false) => Err(SynthesisError::Unsatisfiable),
+            Boolean::Is(_) | Boolean::Not(_) => {
+                r.cs()
+                    .enforce_constraint(r.lc(), lc!() + Variable::One, lc!() + Variable::One)
+            }
+        }
+    }
+
+    /// Enforce `self == other`.
+    pub fn enforce_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+        let cs = self.cs().or(other.cs());
+        match (self, other) {
+            (Boolean::Constant(a), Boolean::Constant(b)) if a == b => Ok(()),
+            (Boolean::Constant(_), Boolean::Constant(_)) => Err(SynthesisError::Unsatisfiable),
+            _ => cs.enforce_constraint(lc!(), lc!(), self.lc() - other.lc()),
+        }
+    }
+
+    /// The field element whose little-endian bits are `bits`.
+    pub fn le_bits_to_fp_var(bits: &[Self]) -> Result<FpVar<F>, SynthesisError> {
+        let mut power = F::one();
+        let mut value = Some(F::zero());
+        let mut lc = LinearCombination::zero();
+        for b in bits {
+            value = value.and_then(|v| Some(v + b.value().ok()? * power));
+            lc = lc + (power, &b.lc());
+            power.double_in_place();
+        }
+        if bits.iter().all(Boolean::is_constant) {
+            return Ok(FpVar::Constant(value.unwrap()));
+        }
+        if bits.len() >= F::Params::MODULUS_BITS as usize {
+            Self::enforce_in_field_le(bits)?;
+        }
+        let cs = bits.iter().fold(ConstraintSystemRef::None, |cs, b| cs.or(b.cs()));
+        let variable = cs.new_lc(lc)?;
+        Ok(FpVar::Var(AllocatedFp::new(value, variable, cs)))
+    }
+
+    /// Enforce that `bits` (little-endian) is less than the modulus.
+    pub fn enforce_in_field_le(bits: &[Self]) -> Result<(), SynthesisError> {
+        let mut b = F::characteristic().to_vec();
+        assert_eq!(b[0] % 2, 1);
+        b[0] -= 1;
+        let run = Self::enforce_smaller_or_equal_than_le(bits, b)?;
+        assert!(run.is_empty());
+        Ok(())
+    }
+
+    /// Enforce that `bits` (little-endian) is at most `element`, returning the final run of ones.
+    pub fn enforce_smaller_or_equal_than_le(
+        bits: &[Self],
+        element: impl AsRef<[u64]>,
+    ) -> Result<Vec<Self>, SynthesisError> {
+        let b: &[u64] = element.as_ref();
+        let mut bits_iter = bits.iter().rev();
+        let mut last_run = Boolean::constant(true);
+        let mut current_run = vec![];
+
+        let element_num_bits = BitIteratorBE::without_leading_zeros(b).count();
+        if bits.len() > element_num_bits {
+            let mut or_result = Boolean::constant(false);
+            for should_be_zero in &bits[element_num_bits..] {
+                or_result = or_result.or(should_be_zero)?;
+                let _ = bits_iter.next().unwrap();
+            }
+            or_result.enforce_equal(&Boolean::constant(false))?;
+        }
+
+        for (b, a) in BitIteratorBE::without_leading_zeros(b).zip(bits_iter.by_ref()) {
+            if b {
+                current_run.push(a.clone());
+            } else {
+                if !current_run.is_empty() {
+                    current_run.push(last_run.clone());
+                    last_run = Self::kary_and(&current_run)?;
+                    current_run.truncate(0);
+                }
+                // If `last_run` is true, `a` must be false, or it would exceed `element`
+                Self::enforce_kary_nand(&[last_run.clone(), a.clone()])?;
+            }
+        }
+        assert!(bits_iter.next().is_none());
+        Ok(current_run)
+    }
+
+    /// `cond ? true_value : false_value`
+    pub fn select(
+        &self,
+        true_value: &FpVar<F>,
+        false_value: &FpVar<F>,
+    ) -> Result<FpVar<F>, SynthesisError> {
+        FpVar::conditionally_select(self, true_value, false_value)
+    }
+}
+
+macro_rules! make_uint {
+    ($name:ident, $size:expr, $native:ident) => {
+        /// An unsigned integer, as little-endian bits.
+        #[derive(Clone, Debug)]
+        #[must_use]
+        pub struct $name<F: GadgetField> {
+            bits: Vec<Boolean<F>>,
+            value: Option<F>,
+        }
+
+        impl<F: GadgetField> $name<F> {
+            pub fn constant(value: $native) -> Self {
+                let bits = (0..$size)
+                    .map(|i| Boolean::constant((value >> i) & 1 == 1))
+                    .collect();
+                Self {
+                    bits,
+                    value: Some(F::from(value)),
+                }
+            }
+
+            /// Allocate a witness from its value (as a field element below `2^n`).
+            pub fn new_witness(
+                cs: ConstraintSystemRef<F>,
+                f: impl FnOnce() -> Result<F, SynthesisError>,
+            ) -> Result<Self, SynthesisError> {
+                let value = f().ok();
+                let bits = match value {
+                    Some(v) => F::bits_le(&[v]).pop().unwrap()[..$size]
+                        .iter()
+                        .map(|b| Some(*b))
+                        .collect(),
+                    None => vec![None; $size],
+                };
+                let bits = bits
+                    .into_iter()
+                    .map(|b| Boolean::new_witness(cs.clone(), || b.ok_or(SynthesisError::AssignmentMissing)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Self::from_bits_le(&bits))
+            }
+
+            /// The value, as a field element.
+            pub fn value(&self) -> Result<F, SynthesisError> {
+                self.value.ok_or(SynthesisError::AssignmentMissing)
+            }
+
+            pub fn to_bits_le(&self) -> Vec<Boolean<F>> {
+                self.bits.clone()
+            }
+
+            pub fn from_bits_le(bits: &[Boolean<F>]) -> Self {
+                assert_eq!(bits.len(), $size);
+                let mut power = F::one();
+                let mut value = Some(F::zero());
+                for b in bits {
+                    value = value.and_then(|v| Some(v + b.value().ok()? * power));
+                    power.double_in_place();
+                }
+                Self {
+                    bits: bits.to_vec(),
+                    value,
+                }
+            }
+
+            pub fn xor(&self, other: &Self) -> Result<Self, SynthesisError> {
+                let bits = self
+                    .bits
+                    .iter()
+                    .zip(&other.bits)
+                    .map(|(a, b)| a.xor(b))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Self::from_bits_le(&bits))
+            }
+
+            /// Rotate right by `by` bits.
+            pub fn rotr(&self, by: usize) -> Self {
+                let by = by % $size;
+                let bits: Vec<_> = self
+                    .bits
+                    .iter()
+                    .skip(by)
+                    .chain(self.bits.iter())
+                    .take($size)
+                    .cloned()
+                    .collect();
+                Self::from_bits_le(&bits)
+            }
+
+            /// The sum of `operands`, modulo `2^n`.
+            pub fn addmany(operands: &[Self]) -> Result<Self, SynthesisError> {
+                assert!(F::Params::MODULUS_BITS >= 2 * $size);
+                assert!(!operands.is_empty());
+                assert!($size * operands.len() <= F::Params::MODULUS_BITS as usize);
+                if operands.len() == 1 {
+                    return Ok(operands[0].clone());
+                }
+
+                let mut lc = LinearCombination::zero();
+                let mut value = Some(F::zero());
+                for op in operands {
+                    value = value.and_then(|v| Some(v + op.value?));
+                    let mut coeff = F::one();
+                    for bit in &op.bits {
+                        lc = lc + (coeff, &bit.lc());
+                        coeff.double_in_place();
+                    }
+                }
+                if operands.iter().all(|op| op.bits.iter().all(Boolean::is_constant)) {
+                    let bits = F::bits_le(&[value.unwrap()]).pop().unwrap();
+                    let bits: Vec<_> = bits[..$size]
+                        .iter()
+                        .map(|b| Boolean::constant(b.is_one()))
+                        .collect();
+                    return Ok(Self::from_bits_le(&bits));
+                }
+
+                // Enough bits for `operands.len() * (2^n - 1)`
+                let n_bits = $size + (usize::BITS - (operands.len() - 1).leading_zeros()) as usize;
+                let sum_bits = match value {
+                    Some(v) => F::bits_le(&[v]).pop().unwrap()[..n_bits]
+                        .iter()
+                        .map(|b| Some(*b))
+                        .collect(),
+                    None => vec![None; n_bits],
+                };
+                let cs = operands
+                    .iter()
+                    .flat_map(|op| &op.bits)
+                    .fold(ConstraintSystemRef::None, |cs, b| cs.or(b.cs()));
+                let mut coeff = F::one();
+                let mut result_bits = Vec::with_capacity(n_bits);
+                for b in sum_bits {
+                    let b = Boolean::new_witness(cs.clone(), || {
+                        b.ok_or(SynthesisError::AssignmentMissing)
+                    })?;
+                    lc = lc - (coeff, &b.lc());
+                    result_bits.push(b);
+                    coeff.double_in_place();
+                }
+                cs.enforce_constraint(lc!(), lc!(), lc)?;
+
+                // Discard the carries
+                result_bits.truncate($size);
+                Ok(Self::from_bits_le(&result_bits))
+            }
+
+            /// Enforce `self == other`.
+            pub fn enforce_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+                self.bits
+                    .iter()
+                    .zip(&other.bits)
+                    .try_for_each(|(a, b)| a.enforce_equal(b))
+            }
+
+            /// The value, as a field variable.
+            pub fn to_fp(&self) -> Result<FpVar<F>, SynthesisError> {
+                Boolean::le_bits_to_fp_var(&self.bits)
+            }
+        }
+    };
+}
+
+make_uint!(UInt8, 8, u8);
+make_uint!(UInt32, 32, u32);
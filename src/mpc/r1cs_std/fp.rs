@@ -0,0 +1,497 @@
+use std::cmp::Ordering;
+
+use super::*;
+
+// Changelog:
+// 1. Copied from ark-r1cs-std's `fields::fp` and `fields::fp::cmp`.
+// 2. `FpVar` methods are inherent (there is no `FieldVar` trait here), and only `&FpVar` has
+//    operators.
+// 3. `is_neq` gets its witnesses, `[x == 0]` and a multiplier, from
+//    `GadgetField::is_zero_and_inverse`; it does not branch on the (maybe shared) value.
+// 4. `to_non_unique_bits_le` gets its witness bits from `GadgetField::bits_le`.
+// 5. `conditionally_select` computes `c (t - f) + f`, without branching on `c`.
+// 6. Constants must be public.
+
+/// A field element allocated in the constraint system.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct AllocatedFp<F: GadgetField> {
+    value: Option<F>,
+    pub variable: Variable,
+    pub cs: ConstraintSystemRef<F>,
+}
+
+impl<F: GadgetField> AllocatedFp<F> {
+    pub fn new(value: Option<F>, variable: Variable, cs: ConstraintSystemRef<F>) -> Self {
+        Self {
+            value,
+            variable,
+            cs,
+        }
+    }
+
+    pub fn value(&self) -> Result<F, SynthesisError> {
+        self.value.ok_or(SynthesisError::AssignmentMissing)
+    }
+
+    fn new_witness(
+        cs: ConstraintSystemRef<F>,
+        f: impl FnOnce() -> Result<F, SynthesisError>,
+    ) -> Result<Self, SynthesisError> {
+        let value = f().ok();
+        let variable = cs.new_witness_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+        Ok(Self::new(value, variable, cs))
+    }
+
+    fn new_input(
+        cs: ConstraintSystemRef<F>,
+        f: impl FnOnce() -> Result<F, SynthesisError>,
+    ) -> Result<Self, SynthesisError> {
+        let value = f().ok();
+        let variable = cs.new_input_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+        Ok(Self::new(value, variable, cs))
+    }
+
+    fn binary_value(&self, other: &Self, op: impl FnOnce(F, F) -> F) -> Option<F> {
+        match (self.value, other.value) {
+            (Some(a), Some(b)) => Some(op(a, b)),
+            _ => None,
+        }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let value = self.binary_value(other, |a, b| a + b);
+        let variable = self
+            .cs
+            .new_lc(lc!() + self.variable + other.variable)
+            .unwrap();
+        Self::new(value, variable, self.cs.clone())
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        let value = self.binary_value(other, |a, b| a - b);
+        let variable = self
+            .cs
+            .new_lc(lc!() + self.variable - other.variable)
+            .unwrap();
+        Self::new(value, variable, self.cs.clone())
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let value = self.binary_value(other, |a, b| a * b);
+        let product =
+            Self::new_witness(self.cs.clone(), || value.ok_or(SynthesisError::AssignmentMissing))
+                .unwrap();
+        self.cs
+            .enforce_constraint(
+                lc!() + self.variable,
+                lc!() + other.variable,
+                lc!() + product.variable,
+            )
+            .unwrap();
+        product
+    }
+
+    fn add_constant(&self, c: F) -> Self {
+        if c.is_zero() {
+            return self.clone();
+        }
+        let variable = self
+            .cs
+            .new_lc(lc!() + self.variable + (c, Variable::One))
+            .unwrap();
+        Self::new(self.value.map(|v| v + c), variable, self.cs.clone())
+    }
+
+    fn mul_constant(&self, c: F) -> Self {
+        if c.is_one() {
+            return self.clone();
+        }
+        let variable = self.cs.new_lc(lc!() + (c, self.variable)).unwrap();
+        Self::new(self.value.map(|v| v * c), variable, self.cs.clone())
+    }
+
+    fn negate(&self) -> Self {
+        let variable = self.cs.new_lc(lc!() - self.variable).unwrap();
+        Self::new(self.value.map(|v| -v), variable, self.cs.clone())
+    }
+
+    fn inverse(&self) -> Result<Self, SynthesisError> {
+        let inverse = Self::new_witness(self.cs.clone(), || {
+            self.value()?.inverse().ok_or(SynthesisError::DivisionByZero)
+        })?;
+        self.cs.enforce_constraint(
+            lc!() + self.variable,
+            lc!() + inverse.variable,
+            lc!() + Variable::One,
+        )?;
+        Ok(inverse)
+    }
+
+    fn is_neq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        let witnesses = self
+            .binary_value(other, |a, b| a - b)
+            .map(|d| F::is_zero_and_inverse(&[d])[0]);
+        let is_not_equal = Boolean::new_witness(self.cs.clone(), || {
+            witnesses
+                .map(|(z, _)| F::one() - z)
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        // The inverse of `self - other`, or 1 if they are equal
+        let multiplier = self.cs.new_witness_variable(|| {
+            witnesses
+                .map(|(z, inv)| inv + z)
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        // (self - other) * multiplier = is_not_equal
+        self.cs.enforce_constraint(
+            lc!() + self.variable - other.variable,
+            lc!() + multiplier,
+            is_not_equal.lc(),
+        )?;
+        // (self - other) * (1 - is_not_equal) = 0
+        self.cs.enforce_constraint(
+            lc!() + self.variable - other.variable,
+            is_not_equal.not().lc(),
+            lc!(),
+        )?;
+        Ok(is_not_equal)
+    }
+
+    fn to_non_unique_bits_le(&self) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let n_bits = F::Params::MODULUS_BITS as usize;
+        let bits = match self.value {
+            Some(v) => F::bits_le(&[v]).pop().unwrap().into_iter().map(Some).collect(),
+            None => vec![None; n_bits],
+        };
+        let bits = bits
+            .into_iter()
+            .map(|b| {
+                Boolean::new_witness(self.cs.clone(), || {
+                    b.ok_or(SynthesisError::AssignmentMissing)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut lc = LinearCombination::zero();
+        let mut coeff = F::one();
+        for bit in &bits {
+            lc = lc + (coeff, &bit.lc());
+            coeff.double_in_place();
+        }
+        lc = lc - self.variable;
+        self.cs.enforce_constraint(lc!(), lc!(), lc)?;
+        Ok(bits)
+    }
+}
+
+/// A field element: either a constant or allocated in the constraint system.
+#[derive(Clone, Debug)]
+#[must_use]
+pub enum FpVar<F: GadgetField> {
+    Constant(F),
+    Var(AllocatedFp<F>),
+}
+
+impl<F: GadgetField> From<AllocatedFp<F>> for FpVar<F> {
+    fn from(v: AllocatedFp<F>) -> Self {
+        FpVar::Var(v)
+    }
+}
+
+impl<F: GadgetField> FpVar<F> {
+    pub fn constant(c: F) -> Self {
+        FpVar::Constant(c)
+    }
+
+    pub fn zero() -> Self {
+        FpVar::Constant(F::zero())
+    }
+
+    pub fn one() -> Self {
+        FpVar::Constant(F::one())
+    }
+
+    pub fn new_witness(
+        cs: ConstraintSystemRef<F>,
+        f: impl FnOnce() -> Result<F, SynthesisError>,
+    ) -> Result<Self, SynthesisError> {
+        AllocatedFp::new_witness(cs, f).map(FpVar::Var)
+    }
+
+    pub fn new_input(
+        cs: ConstraintSystemRef<F>,
+        f: impl FnOnce() -> Result<F, SynthesisError>,
+    ) -> Result<Self, SynthesisError> {
+        AllocatedFp::new_input(cs, f).map(FpVar::Var)
+    }
+
+    pub fn value(&self) -> Result<F, SynthesisError> {
+        match self {
+            FpVar::Constant(c) => Ok(*c),
+            FpVar::Var(v) => v.value(),
+        }
+    }
+
+    pub fn cs(&self) -> ConstraintSystemRef<F> {
+        match self {
+            FpVar::Constant(_) => ConstraintSystemRef::None,
+            FpVar::Var(v) => v.cs.clone(),
+        }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        matches!(self, FpVar::Constant(_))
+    }
+
+    pub fn lc(&self) -> LinearCombination<F> {
+        match self {
+            FpVar::Constant(c) => lc!() + (*c, Variable::One),
+            FpVar::Var(v) => lc!() + v.variable,
+        }
+    }
+
+    pub fn add_constant(&self, c: F) -> Self {
+        match self {
+            FpVar::Constant(x) => FpVar::Constant(*x + c),
+            FpVar::Var(v) => FpVar::Var(v.add_constant(c)),
+        }
+    }
+
+    pub fn mul_constant(&self, c: F) -> Self {
+        match self {
+            FpVar::Constant(x) => FpVar::Constant(*x * c),
+            FpVar::Var(v) => FpVar::Var(v.mul_constant(c)),
+        }
+    }
+
+    pub fn negate(&self) -> Self {
+        match self {
+            FpVar::Constant(x) => FpVar::Constant(-*x),
+            FpVar::Var(v) => FpVar::Var(v.negate()),
+        }
+    }
+
+    pub fn double(&self) -> Self {
+        self + self
+    }
+
+    pub fn square(&self) -> Self {
+        self * self
+    }
+
+    pub fn inverse(&self) -> Result<Self, SynthesisError> {
+        match self {
+            FpVar::Constant(x) => x
+                .inverse()
+                .map(FpVar::Constant)
+                .ok_or(SynthesisError::DivisionByZero),
+            FpVar::Var(v) => v.inverse().map(FpVar::Var),
+        }
+    }
+
+    /// `[self != other]`
+    pub fn is_neq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        match (self, other) {
+            (FpVar::Constant(a), FpVar::Constant(b)) => Ok(Boolean::constant(a != b)),
+            (FpVar::Var(a), FpVar::Var(b)) => a.is_neq(b),
+            (FpVar::Var(v), FpVar::Constant(c)) | (FpVar::Constant(c), FpVar::Var(v)) => {
+                let c = AllocatedFp::new(
+                    Some(*c),
+                    v.cs.new_lc(lc!() + (*c, Variable::One))?,
+                    v.cs.clone(),
+                );
+                v.is_neq(&c)
+            }
+        }
+    }
+
+    /// `[self == other]`
+    pub fn is_eq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        Ok(self.is_neq(other)?.not())
+    }
+
+    /// Enforce `self == other`.
+    pub fn enforce_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+        match (self, other) {
+            (FpVar::Constant(a), FpVar::Constant(b)) if a == b => Ok(()),
+            (FpVar::Constant(_), FpVar::Constant(_)) => Err(SynthesisError::Unsatisfiable),
+            _ => self.cs().or(other.cs()).enforce_constraint(
+                self.lc() - other.lc(),
+                lc!() + Variable::One,
+                lc!(),
+            ),
+        }
+    }
+
+    /// `cond ? true_value : false_value`
+    pub fn conditionally_select(
+        cond: &Boolean<F>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        match cond {
+            Boolean::Constant(true) => Ok(true_value.clone()),
+            Boolean::Constant(false) => Ok(false_value.clone()),
+            _ => {
+                let cs = cond.cs();
+                let result = Self::new_witness(cs.clone(), || {
+                    let (t, f) = (true_value.value()?, false_value.value()?);
+                    Ok(cond.value()? * (t - f) + f)
+                })?;
+                // cond * (t - f) = result - f
+                cs.enforce_constraint(
+                    cond.lc(),
+                    true_value.lc() - false_value.lc(),
+                    result.lc() - false_value.lc(),
+                )?;
+                Ok(result)
+            }
+        }
+    }
+
+    /// Little-endian bits, of which there may be two choices (for `x` and `x + p`).
+    pub fn to_non_unique_bits_le(&self) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        match self {
+            FpVar::Constant(c) => Ok(F::bits_le(&[*c])
+                .pop()
+                .unwrap()
+                .into_iter()
+                .map(|b| Boolean::constant(b.is_one()))
+                .collect()),
+            FpVar::Var(v) => v.to_non_unique_bits_le(),
+        }
+    }
+
+    /// The little-endian bits (less than the modulus).
+    pub fn to_bits_le(&self) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let bits = self.to_non_unique_bits_le()?;
+        if !self.is_constant() {
+            Boolean::enforce_in_field_le(&bits)?;
+        }
+        Ok(bits)
+    }
+
+    /// Enforce `self < other` (or `>`, by `ordering`; or-equal with
+    /// `should_also_check_equality`). Both must be at most `(p - 1) / 2`, which this enforces.
+    pub fn enforce_cmp(
+        &self,
+        other: &Self,
+        ordering: Ordering,
+        should_also_check_equality: bool,
+    ) -> Result<(), SynthesisError> {
+        let (left, right) = self.process_cmp_inputs(other, ordering, should_also_check_equality)?;
+        let is_smaller_than = left.is_smaller_than(&right)?;
+        is_smaller_than.enforce_equal(&Boolean::TRUE)
+    }
+
+    /// `[self < other]` (or `>`, by `ordering`; or-equal with `should_also_check_equality`). Both
+    /// must be at most `(p - 1) / 2`, which this enforces.
+    pub fn is_cmp(
+        &self,
+        other: &Self,
+        ordering: Ordering,
+        should_also_check_equality: bool,
+    ) -> Result<Boolean<F>, SynthesisError> {
+        let (left, right) = self.process_cmp_inputs(other, ordering, should_also_check_equality)?;
+        left.is_smaller_than(&right)
+    }
+
+    /// Like `is_cmp`, without enforcing that the inputs are at most `(p - 1) / 2`.
+    pub fn is_cmp_unchecked(
+        &self,
+        other: &Self,
+        ordering: Ordering,
+        should_also_check_equality: bool,
+    ) -> Result<Boolean<F>, SynthesisError> {
+        let (left, right) = self.process_cmp_inputs(other, ordering, should_also_check_equality)?;
+        left.is_smaller_than_unchecked(&right)
+    }
+
+    fn process_cmp_inputs(
+        &self,
+        other: &Self,
+        ordering: Ordering,
+        should_also_check_equality: bool,
+    ) -> Result<(Self, Self), SynthesisError> {
+        let (left, right) = match ordering {
+            Ordering::Less => (self, other),
+            Ordering::Greater => (other, self),
+            Ordering::Equal => return Err(SynthesisError::Unsatisfiable),
+        };
+        let right_for_check = if should_also_check_equality {
+            right.add_constant(F::one())
+        } else {
+            right.clone()
+        };
+        Ok((left.clone(), right_for_check))
+    }
+
+    pub fn enforce_smaller_or_equal_than_mod_minus_one_div_two(
+        &self,
+    ) -> Result<(), SynthesisError> {
+        // Non-unique bits suffice: `self <= (p - 1) / 2` implies `self < p`
+        let _ = Boolean::enforce_smaller_or_equal_than_le(
+            &self.to_non_unique_bits_le()?,
+            F::modulus_minus_one_div_two(),
+        )?;
+        Ok(())
+    }
+
+    fn is_smaller_than(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        self.enforce_smaller_or_equal_than_mod_minus_one_div_two()?;
+        other.enforce_smaller_or_equal_than_mod_minus_one_div_two()?;
+        self.is_smaller_than_unchecked(other)
+    }
+
+    /// `2 (self - other)` is odd exactly when it wrapped around, i.e. when `self < other`
+    fn is_smaller_than_unchecked(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        Ok((self - other).double().to_bits_le()?.swap_remove(0))
+    }
+}
+
+impl<F: GadgetField> Add for &FpVar<F> {
+    type Output = FpVar<F>;
+    fn add(self, other: Self) -> FpVar<F> {
+        match (self, other) {
+            (FpVar::Constant(a), FpVar::Constant(b)) => FpVar::Constant(*a + *b),
+            (FpVar::Constant(c), FpVar::Var(v)) | (FpVar::Var(v), FpVar::Constant(c)) => {
+                FpVar::Var(v.add_constant(*c))
+            }
+            (FpVar::Var(a), FpVar::Var(b)) => FpVar::Var(a.add(b)),
+        }
+    }
+}
+
+impl<F: GadgetField> Sub for &FpVar<F> {
+    type Output = FpVar<F>;
+    fn sub(self, other: Self) -> FpVar<F> {
+        match (self, other) {
+            (FpVar::Constant(a), FpVar::Constant(b)) => FpVar::Constant(*a - *b),
+            (FpVar::Var(v), FpVar::Constant(c)) => FpVar::Var(v.add_constant(-*c)),
+            (FpVar::Constant(c), FpVar::Var(v)) => FpVar::Var(v.negate().add_constant(*c)),
+            (FpVar::Var(a), FpVar::Var(b)) => FpVar::Var(a.sub(b)),
+        }
+    }
+}
+
+impl<F: GadgetField> Mul for &FpVar<F> {
+    type Output = FpVar<F>;
+    fn mul(self, other: Self) -> FpVar<F> {
+        match (self, other) {
+            (FpVar::Constant(a), FpVar::Constant(b)) => FpVar::Constant(*a * *b),
+            (FpVar::Constant(c), FpVar::Var(v)) | (FpVar::Var(v), FpVar::Constant(c)) => {
+                FpVar::Var(v.mul_constant(*c))
+            }
+            (FpVar::Var(a), FpVar::Var(b)) => FpVar::Var(a.mul(b)),
+        }
+    }
+}
+
+impl<F: GadgetField> Neg for &FpVar<F> {
+    type Output = FpVar<F>;
+    fn neg(self) -> FpVar<F> {
+        self.negate()
+    }
+}
@@ -1,4 +1,5 @@
 use ark_bls12_377::Bls12_377;
+use ark_ec::ProjectiveCurve;
 use ark_ff::{Field, UniformRand};
 use ark_groth16::{generate_random_parameters, prepare_verifying_key, verify_proof};
 use ark_relations::{
@@ -12,10 +13,13 @@ use log::debug;
 use structopt::StructOpt;
 
 use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
 
 mod mpc;
-use mpc::groth::{pf_publicize, pk_to_mpc, prover::create_random_proof};
-use mpc::{channel, MpcPairingEngine, MpcVal, MpcWire};
+use mpc::groth::{self, generator, pf_publicize, pk_to_mpc, prover::create_random_proof};
+use mpc::circom::{read_r1cs, read_wtns, CircomCircuit, R1cs};
+use mpc::input::{receive_shares, SharedInputs};
+use mpc::{channel, party_rng, MpcVal, MpcWire};
 
 struct RepeatedSquaringCircuit<F: Field> {
     chain: Vec<Option<F>>,
@@ -72,16 +76,24 @@ impl<ConstraintF: Field> ConstraintSynthesizer<ConstraintF>
     }
 }
 
-fn test_squaring_mpc(n: usize) {
+fn test_squaring_mpc(n: usize, mpc_setup: bool, provided: Option<SharedInputs<ark_bls12_377::Fr>>) {
     let rng = &mut test_rng();
     let circ_no_data = RepeatedSquaringCircuit::without_data(n);
 
-    let params = generate_random_parameters::<Bls12_377, _, _>(circ_no_data, rng).unwrap();
+    let params = if mpc_setup {
+        generator::generate_random_parameters::<Bls12_377, _, _>(circ_no_data, &mut party_rng())
+    } else {
+        generate_random_parameters::<Bls12_377, _, _>(circ_no_data, rng)
+    }
+    .unwrap();
 
     let pvk = prepare_verifying_key::<Bls12_377>(&params.vk);
     let mpc_params = pk_to_mpc(params);
 
-    let a = MpcVal::<ark_bls12_377::Fr>::rand(rng);
+    let a = match provided {
+        Some(inputs) => inputs.assignment("x").unwrap(),
+        None => MpcVal::<ark_bls12_377::Fr>::rand(rng),
+    };
     let computation_timer = start_timer!(|| "do the mpc");
     let circ_data = RepeatedSquaringCircuit::from_start(a, n);
     let public_inputs = vec![circ_data.chain.last().unwrap().unwrap().publicize_unwrap()];
@@ -89,8 +101,7 @@ fn test_squaring_mpc(n: usize) {
     channel::reset_stats();
     let timer = start_timer!(|| "timed section");
     let mpc_proof =
-        create_random_proof::<MpcPairingEngine<Bls12_377>, _, _>(circ_data, &mpc_params, rng)
-            .unwrap();
+        groth::create_shared_random_proof::<Bls12_377, _>(circ_data, &mpc_params).unwrap();
     let proof = pf_publicize(mpc_proof);
     end_timer!(timer);
 
@@ -133,6 +144,85 @@ fn test_squaring_local_ark(n: usize) {
     assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
 }
 
+/// circom's default field is BN254's scalar field
+type Bn = ark_bn254::Bn254;
+type BnFr = ark_bn254::Fr;
+
+fn read_circom(r1cs: &Path) -> R1cs<BnFr> {
+    read_r1cs(&std::fs::read(r1cs).unwrap()).unwrap()
+}
+
+fn read_witness(wtns: &Path) -> Vec<BnFr> {
+    read_wtns(&std::fs::read(wtns).unwrap()).unwrap()
+}
+
+fn test_circom_mpc(r1cs: R1cs<BnFr>, witness: Vec<MpcVal<BnFr>>, mpc_setup: bool) {
+    let rng = &mut test_rng();
+    let circ_no_data = CircomCircuit {
+        r1cs: r1cs.clone(),
+        witness: None,
+    };
+
+    let params = if mpc_setup {
+        generator::generate_random_parameters::<Bn, _, _>(circ_no_data, &mut party_rng())
+    } else {
+        generate_random_parameters::<Bn, _, _>(circ_no_data, rng)
+    }
+    .unwrap();
+
+    let pvk = prepare_verifying_key::<Bn>(&params.vk);
+    let mpc_params = pk_to_mpc(params);
+
+    let circ_data = CircomCircuit {
+        r1cs: r1cs.to_mpc(),
+        witness: Some(witness),
+    };
+    let public_inputs: Vec<BnFr> =
+        channel::field_batch_publicize(circ_data.public_inputs().unwrap())
+            .into_iter()
+            .map(MpcWire::publicize_unwrap)
+            .collect();
+    channel::reset_stats();
+    let timer = start_timer!(|| "timed section");
+    let mpc_proof = groth::create_shared_random_proof::<Bn, _>(circ_data, &mpc_params).unwrap();
+    let proof = pf_publicize(mpc_proof);
+    end_timer!(timer);
+
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+    println!("Public inputs:");
+    for (i, x) in public_inputs.iter().enumerate() {
+        println!("  {}: {}", i, x);
+    }
+}
+
+fn test_circom_local(r1cs: R1cs<BnFr>, witness: Vec<BnFr>, ark: bool) {
+    let rng = &mut test_rng();
+    let circ_no_data = CircomCircuit {
+        r1cs: r1cs.clone(),
+        witness: None,
+    };
+
+    let params = generate_random_parameters::<Bn, _, _>(circ_no_data, rng).unwrap();
+
+    let pvk = prepare_verifying_key::<Bn>(&params.vk);
+
+    let circ_data = CircomCircuit {
+        r1cs,
+        witness: Some(witness),
+    };
+    let public_inputs = circ_data.public_inputs().unwrap();
+    let timer = start_timer!(|| "timed section");
+    let proof = if ark {
+        ark_groth16::create_random_proof::<Bn, _, _>(circ_data, &params, rng)
+    } else {
+        create_random_proof::<Bn, _, _>(circ_data, &params, rng)
+    }
+    .unwrap();
+    end_timer!(timer);
+
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+}
+
 #[derive(Debug, StructOpt)]
 struct PartyInfo {
     /// Your host
@@ -181,6 +271,7 @@ arg_enum! {
     #[derive(PartialEq, Debug, Clone, Copy)]
     pub enum Computation {
         Squaring,
+        Circom,
     }
 }
 
@@ -189,6 +280,15 @@ enum FieldOpt {
     Mpc {
         #[structopt(flatten)]
         party_info: PartyInfo,
+
+        /// Generate the parameters in MPC
+        #[structopt(long)]
+        mpc_setup: bool,
+
+        /// Take the witness from an input provider, listening on this port: the start of the chain
+        /// (`x`), or each circom wire (by index)
+        #[structopt(long)]
+        input_port: Option<u16>,
     },
     Local {},
     ArkLocal {},
@@ -197,22 +297,34 @@ enum FieldOpt {
 impl FieldOpt {
     fn setup(&self) {
         match self {
-            FieldOpt::Mpc { party_info } => party_info.setup(),
+            FieldOpt::Mpc { party_info, .. } => party_info.setup(),
             _ => {}
         }
     }
     fn teardown(&self) {
         match self {
-            FieldOpt::Mpc { party_info } => party_info.teardown(),
+            FieldOpt::Mpc { party_info, .. } => party_info.teardown(),
             _ => {}
         }
     }
-    fn run(&self, computation: Computation, computation_size: usize) {
+    /// Receive inputs from an input provider, as shares of scalars of `G`
+    fn provided<G: ProjectiveCurve>(&self) -> Option<SharedInputs<G::ScalarField>> {
+        match self {
+            FieldOpt::Mpc {
+                input_port: Some(port),
+                party_info,
+                ..
+            } => Some(receive_shares::<G, _>((party_info.host.as_str(), *port)).into_shared()),
+            _ => None,
+        }
+    }
+    fn run(&self, computation: Computation, computation_size: usize, circom: &CircomOpt) {
         self.setup();
         match computation {
             Computation::Squaring => match self {
-                FieldOpt::Mpc { .. } => {
-                    test_squaring_mpc(computation_size);
+                FieldOpt::Mpc { mpc_setup, .. } => {
+                    let provided = self.provided::<ark_bls12_377::G1Projective>();
+                    test_squaring_mpc(computation_size, *mpc_setup, provided);
                 }
                 FieldOpt::Local {} => {
                     test_squaring_local(computation_size);
@@ -221,11 +333,44 @@ impl FieldOpt {
                     test_squaring_local_ark(computation_size);
                 }
             },
+            Computation::Circom => {
+                let r1cs = read_circom(circom.r1cs.as_ref().expect("circom needs --r1cs"));
+                match self {
+                    FieldOpt::Mpc { mpc_setup, .. } => {
+                        let witness = match (self.provided::<ark_bn254::G1Projective>(), &circom.wtns) {
+                            (Some(inputs), _) => (0..r1cs.num_wires)
+                                .map(|i| inputs.assignment(&i.to_string()).unwrap())
+                                .collect(),
+                            (None, Some(shares)) => read_witness(shares)
+                                .into_iter()
+                                .map(MpcVal::from_shared)
+                                .collect(),
+                            (None, None) => panic!("circom in MPC needs --input-port or --wtns"),
+                        };
+                        test_circom_mpc(r1cs, witness, *mpc_setup);
+                    }
+                    FieldOpt::Local {} | FieldOpt::ArkLocal {} => {
+                        let witness = read_witness(circom.wtns.as_ref().expect("circom needs --wtns"));
+                        test_circom_local(r1cs, witness, matches!(self, FieldOpt::ArkLocal {}));
+                    }
+                }
+            }
         }
         self.teardown();
     }
 }
 
+#[derive(Debug, StructOpt)]
+struct CircomOpt {
+    /// The circom constraint system (.r1cs)
+    #[structopt(long, parse(from_os_str))]
+    r1cs: Option<PathBuf>,
+
+    /// The circom witness (.wtns): the plaintext for local proofs, or in MPC, this party's shares
+    #[structopt(long, parse(from_os_str))]
+    wtns: Option<PathBuf>,
+}
+
 #[derive(Debug, StructOpt)]
 #[structopt(name = "proof", about = "Standard and MPC proofs")]
 struct Opt {
@@ -237,6 +382,9 @@ struct Opt {
     #[structopt(long, default_value = "10")]
     computation_size: usize,
 
+    #[structopt(flatten)]
+    circom: CircomOpt,
+
     #[structopt(subcommand)]
     field: FieldOpt,
 }
@@ -246,5 +394,5 @@ impl Opt {}
 fn main() {
     let opt = Opt::from_args();
     env_logger::init();
-    opt.field.run(opt.computation, opt.computation_size);
+    opt.field.run(opt.computation, opt.computation_size, &opt.circom);
 }